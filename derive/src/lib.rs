@@ -0,0 +1,81 @@
+//! Derive macros for beewallet-core-spark
+//!
+//! `#[derive(ScrollType)]` ties a serde type to a Scroll `type_`, so scrolls
+//! can be decoded into it and registered in a `SchemaRegistry`:
+//!
+//! ```rust,ignore
+//! use beewallet_core_spark::nine_s::schema::ScrollType;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, ScrollType)]
+//! #[scroll(type = "wallet/payment@v1")]
+//! struct Payment {
+//!     txid: String,
+//!     amount_sat: u64,
+//! }
+//!
+//! let payment: Payment = scroll.decode()?;
+//! ```
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, LitStr};
+
+/// Implement `nine_s::schema::ScrollType` from `#[scroll(type = "domain/entity@vN")]`
+#[proc_macro_derive(ScrollType, attributes(scroll))]
+pub fn derive_scroll_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match scroll_type(&input) {
+        Ok(type_) => {
+            let name = &input.ident;
+            let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+            quote! {
+                impl #impl_generics ::beewallet_core_spark::nine_s::schema::ScrollType
+                    for #name #ty_generics #where_clause
+                {
+                    const TYPE: &'static str = #type_;
+                }
+            }
+            .into()
+        }
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Find and check the `type` in `#[scroll(...)]`
+fn scroll_type(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut found = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("scroll")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("type") {
+                found = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `type = \"domain/entity@vN\"`"))
+            }
+        })?;
+    }
+
+    let type_ = found.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "ScrollType needs #[scroll(type = \"domain/entity@vN\")]",
+        )
+    })?;
+
+    // Same shape nine_s::schema::parse_type accepts
+    let value = type_.value();
+    let valid = value.rsplit_once("@v").is_some_and(|(name, version)| {
+        !name.is_empty() && !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit())
+    });
+    if !valid {
+        return Err(syn::Error::new_spanned(
+            &type_,
+            "scroll type must look like \"domain/entity@v1\"",
+        ));
+    }
+
+    Ok(type_)
+}
@@ -58,6 +58,10 @@
 //! - `crypto` - Crypto primitives for encrypted storage
 //! - `keys` - BIP39 + Nostr identity
 //! - `std-channel` - Std threading for watch channels
+//! - `mock` - Offline `MockWalletBackend` for tests (implies `wallet`)
+
+// Lets #[derive(ScrollType)] name this crate from inside it
+extern crate self as beewallet_core_spark;
 
 // 9S Protocol - always available
 pub mod nine_s;
@@ -93,7 +97,7 @@ pub mod wallet_spark;
 pub use nine_s::{
     current_iso_time, current_time_millis, kingdoms, types as scroll_types, verbs,
     Error as NineSError, FileNamespace, Kernel, MemoryNamespace, Metadata, Namespace,
-    Scroll, Store, Tense,
+    Precondition, Scroll, Store, Tense,
 };
 // Git-like primitives
 pub use nine_s::{Anchor, Patch, PatchError, PatchOp};
@@ -144,6 +148,10 @@ pub use wallet_spark::{
     EventListener, SdkEvent, Payment, PaymentState, PaymentType, PaymentDetails,
 };
 
+// Offline wallet for deterministic tests (mock feature)
+#[cfg(feature = "mock")]
+pub use wallet_spark::{MockLedger, MockWalletBackend};
+
 // Default WalletManager alias
 #[cfg(feature = "wallet")]
 pub use wallet_spark::WalletManager;
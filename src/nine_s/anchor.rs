@@ -22,10 +22,28 @@
 //! // The anchor contains the full scroll state
 //! assert_eq!(anchor.scroll.data["title"], "Important");
 //! ```
+//!
+//! # Signed Anchors
+//!
+//! The hash only proves the content is self-consistent: anyone who can
+//! write the file can make a new anchor with a matching hash. With the
+//! `keys` feature an anchor can also be signed with the user's NIP-06
+//! identity key (BIP-340 Schnorr) and checked against the pubkey you
+//! expect:
+//!
+//! ```rust,ignore
+//! let signer = NostrSigner::from_master_key(&master)?;
+//! let anchor = anchor::sign(anchor::create(&scroll, Some("v1")), &signer)?;
+//!
+//! assert!(anchor::verify_signed(&anchor, &signer.public_key_hex()));
+//! ```
 
 use crate::nine_s::{current_time_millis, Scroll};
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "keys")]
+use crate::nostr::{self, NostrError, NostrSigner};
+
 /// An immutable checkpoint of a scroll's state
 ///
 /// Anchors freeze a scroll at a specific point in time.
@@ -46,6 +64,12 @@ pub struct Anchor {
     /// Optional description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Signer's public key (x-only hex), if signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<String>,
+    /// BIP-340 Schnorr signature over [`signing_message`] (hex), if signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 /// Create an anchor from a scroll
@@ -68,6 +92,8 @@ pub fn create(scroll: &Scroll, label: Option<&str>) -> Anchor {
         timestamp,
         label: label.map(String::from),
         description: None,
+        pubkey: None,
+        signature: None,
     }
 }
 
@@ -80,11 +106,61 @@ pub fn create_with_description(scroll: &Scroll, label: Option<&str>, description
 
 /// Verify an anchor's integrity
 ///
-/// Returns true if the scroll content matches the stored hash.
+/// Returns true if the scroll content matches the stored hash. This says
+/// nothing about who made the anchor; see `verify_signed`.
 pub fn verify(anchor: &Anchor) -> bool {
     anchor.scroll.compute_hash() == anchor.hash
 }
 
+/// The message an anchor signature commits to
+///
+/// Covers the id, path, content hash, timestamp, label and description,
+/// so none of them can be changed without invalidating the signature.
+pub fn signing_message(anchor: &Anchor) -> String {
+    serde_json::json!([
+        "9s-anchor",
+        anchor.id,
+        anchor.scroll.key,
+        anchor.hash,
+        anchor.timestamp,
+        anchor.label,
+        anchor.description,
+    ])
+    .to_string()
+}
+
+/// Sign an anchor with a Nostr identity key (BIP-340 Schnorr)
+///
+/// Records the signer's public key and a signature over
+/// [`signing_message`]. Sign last: changing the label or description
+/// afterwards invalidates the signature.
+#[cfg(feature = "keys")]
+pub fn sign(mut anchor: Anchor, signer: &NostrSigner) -> Result<Anchor, NostrError> {
+    anchor.signature = Some(signer.sign_message_schnorr(&signing_message(&anchor))?);
+    anchor.pubkey = Some(signer.public_key_hex());
+    Ok(anchor)
+}
+
+/// Verify an anchor's integrity and that `expected_pubkey` signed it
+///
+/// `expected_pubkey` may be hex or npub. False for unsigned anchors,
+/// anchors signed by anyone else, and any tampering with the content or
+/// the signed fields.
+#[cfg(feature = "keys")]
+pub fn verify_signed(anchor: &Anchor, expected_pubkey: &str) -> bool {
+    let (Some(pubkey), Some(signature)) = (&anchor.pubkey, &anchor.signature) else {
+        return false;
+    };
+    let Ok(expected) = nostr::parse_public_key(expected_pubkey) else {
+        return false;
+    };
+
+    verify(anchor)
+        && *pubkey == expected.to_hex()
+        && nostr::verify_message_schnorr(pubkey, &signing_message(anchor), signature)
+            .unwrap_or(false)
+}
+
 /// Check if two anchors represent the same state
 ///
 /// Two anchors are equivalent if their content hashes match,
@@ -161,6 +237,8 @@ mod tests {
             timestamp: anchor1.timestamp + 1000,
             label: Some("second".to_string()),
             description: None,
+            pubkey: None,
+            signature: None,
         };
 
         assert!(equivalent(&anchor1, &anchor2));
@@ -219,4 +297,46 @@ mod tests {
         assert_eq!(anchor.label, Some("v1.0".to_string()));
         assert_eq!(anchor.description, Some("Initial release".to_string()));
     }
+
+    #[test]
+    fn test_unsigned_anchor_serializes_without_signature_fields() {
+        let scroll = Scroll::new("/notes/abc", json!({"title": "Test"}));
+        let anchor = create(&scroll, None);
+
+        let value = serde_json::to_value(&anchor).unwrap();
+        assert!(value.get("pubkey").is_none());
+        assert!(value.get("signature").is_none());
+    }
+
+    #[cfg(feature = "keys")]
+    #[test]
+    fn test_signed_anchor_verifies_against_expected_pubkey() {
+        use crate::keys::MasterKey;
+
+        let master = MasterKey::from_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        let signer = NostrSigner::from_master_key(&master).unwrap();
+        let stranger = NostrSigner::from_master_key_with_account(&master, 1).unwrap();
+
+        let scroll = Scroll::new("/wallet/balance", json!({"sats": 1000}));
+        let anchor = sign(create(&scroll, Some("v1")), &signer).unwrap();
+
+        assert!(verify(&anchor));
+        assert!(verify_signed(&anchor, &signer.public_key_hex()));
+        assert!(verify_signed(&anchor, &signer.npub().unwrap()));
+        assert!(!verify_signed(&anchor, &stranger.public_key_hex()));
+        assert!(!verify_signed(&create(&scroll, Some("v1")), &signer.public_key_hex()));
+
+        // Relabeling breaks the signature even though the hash still matches
+        let mut relabeled = anchor.clone();
+        relabeled.label = Some("v2".to_string());
+        assert!(verify(&relabeled));
+        assert!(!verify_signed(&relabeled, &signer.public_key_hex()));
+
+        // Re-signed by someone else and passed off as ours
+        let forged = sign(anchor, &stranger).unwrap();
+        assert!(!verify_signed(&forged, &signer.public_key_hex()));
+    }
 }
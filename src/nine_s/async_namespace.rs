@@ -0,0 +1,671 @@
+//! AsyncNamespace - The five operations, awaitable
+//!
+//! `Namespace` is synchronous, so backends that talk to the network (the
+//! Spark wallet) have to `block_on` their own futures inside every call.
+//! That panics or deadlocks when the caller is already a task on an async
+//! runtime, which is exactly where Tauri commands run.
+//!
+//! `AsyncNamespace` mirrors the same five frozen operations with `async fn`,
+//! and `watch` yields a `Stream` instead of a blocking `Receiver`.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: One sync trait (simple, blocks the runtime)
+//! **Antithesis**: Make everything async (every caller needs a runtime)
+//! **Synthesis**: Two traits with the same five ops, and adapters between them
+//!
+//! # Adapters
+//!
+//! ```text
+//! Namespace ──AsyncAdapter──► AsyncNamespace
+//! AsyncNamespace ──BlockingAdapter──► Namespace
+//! ```
+//!
+//! The adapters are wrappers rather than blanket impls: a backend like
+//! `WalletManager` implements both traits natively, which a blanket impl
+//! would forbid.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use beewallet_core_spark::nine_s::{AsyncKernel, AsyncNamespace, MemoryNamespace};
+//! use std::sync::Arc;
+//!
+//! let kernel = AsyncKernel::new();
+//! kernel.mount_sync("/local", Arc::new(MemoryNamespace::new()));
+//! kernel.mount("/wallet", Arc::new(wallet_manager));
+//!
+//! // Inside a tokio task: nothing blocks
+//! let balance = kernel.read("/wallet/balance").await?;
+//! let mut events = kernel.watch("/wallet/tx/**").await?;
+//! while let Some(scroll) = events.next().await { /* ... */ }
+//! ```
+
+use super::channel::channel;
+use super::kernel::{global_key, normalize_mount_path, route, unstrip_paths, watch_targets, Mount};
+use super::namespace::{path_matches, Error, Namespace, Receiver, Result};
+use super::scroll::Scroll;
+use async_trait::async_trait;
+use futures_core::Stream;
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// Scrolls delivered by `AsyncNamespace::watch`
+pub type ScrollStream = Pin<Box<dyn Stream<Item = Scroll> + Send>>;
+
+/// Scrolls buffered between a blocking receiver and its stream
+const STREAM_BUFFER: usize = 64;
+
+/// AsyncNamespace - The 5 frozen operations, async
+///
+/// Same contract as [`Namespace`]: `read` returns None for absent paths,
+/// `write_scroll` with a tombstone deletes, and so on.
+#[async_trait]
+pub trait AsyncNamespace: Send + Sync {
+    /// Read a scroll by path (None if absent)
+    async fn read(&self, path: &str) -> Result<Option<Scroll>>;
+
+    /// Write data at a path
+    async fn write(&self, path: &str, data: Value) -> Result<Scroll>;
+
+    /// Write a full scroll (type and metadata preserved)
+    async fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
+        self.write(&scroll.key, scroll.data).await
+    }
+
+    /// List paths under a prefix
+    async fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Watch for changes matching a pattern
+    async fn watch(&self, pattern: &str) -> Result<ScrollStream>;
+
+    /// Close the namespace
+    async fn close(&self) -> Result<()>;
+}
+
+// ============================================================================
+// Adapters
+// ============================================================================
+
+/// Use a sync [`Namespace`] where an [`AsyncNamespace`] is expected
+///
+/// Calls run inline on the awaiting task. That suits the local backends
+/// (memory, file, Store); wrap anything slow in your runtime's
+/// `spawn_blocking` instead.
+pub struct AsyncAdapter<N: Namespace + ?Sized> {
+    inner: Arc<N>,
+}
+
+impl<N: Namespace> AsyncAdapter<N> {
+    /// Wrap a namespace
+    pub fn new(ns: N) -> Self {
+        Self { inner: Arc::new(ns) }
+    }
+}
+
+impl<N: Namespace + ?Sized> AsyncAdapter<N> {
+    /// Wrap a shared namespace
+    pub fn from_arc(ns: Arc<N>) -> Self {
+        Self { inner: ns }
+    }
+
+    /// The wrapped namespace
+    pub fn inner(&self) -> &Arc<N> {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<N: Namespace + ?Sized + 'static> AsyncNamespace for AsyncAdapter<N> {
+    async fn read(&self, path: &str) -> Result<Option<Scroll>> {
+        self.inner.read(path)
+    }
+
+    async fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+        self.inner.write(path, data)
+    }
+
+    async fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
+        self.inner.write_scroll(scroll)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        self.inner.list(prefix)
+    }
+
+    async fn watch(&self, pattern: &str) -> Result<ScrollStream> {
+        Ok(receiver_stream(self.inner.watch(pattern)?))
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.inner.close()
+    }
+}
+
+/// Use an [`AsyncNamespace`] where a sync [`Namespace`] is expected
+///
+/// Each call is driven to completion with [`block_on`] on the calling
+/// thread, so this is for plain threads and FFI, never for code already
+/// running on an async runtime. Backends whose futures need a specific
+/// runtime (tokio I/O, timers) must also be called from inside it.
+pub struct BlockingAdapter<N: AsyncNamespace + ?Sized> {
+    inner: Arc<N>,
+}
+
+impl<N: AsyncNamespace> BlockingAdapter<N> {
+    /// Wrap a namespace
+    pub fn new(ns: N) -> Self {
+        Self { inner: Arc::new(ns) }
+    }
+}
+
+impl<N: AsyncNamespace + ?Sized> BlockingAdapter<N> {
+    /// Wrap a shared namespace
+    pub fn from_arc(ns: Arc<N>) -> Self {
+        Self { inner: ns }
+    }
+
+    /// The wrapped namespace
+    pub fn inner(&self) -> &Arc<N> {
+        &self.inner
+    }
+}
+
+impl<N: AsyncNamespace + ?Sized + 'static> Namespace for BlockingAdapter<N> {
+    fn read(&self, path: &str) -> Result<Option<Scroll>> {
+        block_on(self.inner.read(path))
+    }
+
+    fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+        block_on(self.inner.write(path, data))
+    }
+
+    fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
+        block_on(self.inner.write_scroll(scroll))
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        block_on(self.inner.list(prefix))
+    }
+
+    fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
+        let mut stream = block_on(self.inner.watch(pattern))?;
+        let (tx, rx) = channel(16);
+
+        // Drive the stream on its own thread, like Kernel::watch
+        std::thread::spawn(move || {
+            block_on(async move {
+                while let Some(scroll) = next(&mut stream).await {
+                    if tx.send(scroll).is_err() {
+                        break; // Receiver dropped
+                    }
+                }
+            })
+        });
+
+        Ok(rx)
+    }
+
+    fn close(&self) -> Result<()> {
+        block_on(self.inner.close())
+    }
+}
+
+// ============================================================================
+// AsyncKernel
+// ============================================================================
+
+/// AsyncKernel - Mount table for async namespaces
+///
+/// Routes exactly like [`Kernel`](super::Kernel): longest prefix match on
+/// segment boundaries, with the mount prefix stripped on the way in and
+/// restored on the way out. Watches span the mounts present when they start.
+pub struct AsyncKernel {
+    mounts: AsyncMountTable,
+}
+
+type AsyncMountTable = Arc<RwLock<BTreeMap<String, Mount<dyn AsyncNamespace>>>>;
+
+impl AsyncKernel {
+    /// Create a new kernel with empty mount table
+    pub fn new() -> Self {
+        Self {
+            mounts: Arc::new(RwLock::new(BTreeMap::new())),
+        }
+    }
+
+    /// Mount an async namespace at a path
+    pub fn mount(&self, path: impl Into<String>, ns: Arc<dyn AsyncNamespace>) {
+        let path = normalize_mount_path(&path.into());
+        self.mounts.write().unwrap().insert(path, Mount::new(ns));
+    }
+
+    /// Mount an async namespace (convenience method that wraps in Arc)
+    pub fn mount_box(&self, path: impl Into<String>, ns: impl AsyncNamespace + 'static) {
+        self.mount(path, Arc::new(ns));
+    }
+
+    /// Mount a sync namespace through an [`AsyncAdapter`]
+    pub fn mount_sync(&self, path: impl Into<String>, ns: Arc<dyn Namespace>) {
+        self.mount(path, Arc::new(AsyncAdapter::from_arc(ns)));
+    }
+
+    /// Unmount a namespace from a path
+    ///
+    /// Returns the previously mounted namespace, or None if nothing was mounted.
+    pub fn unmount(&self, path: &str) -> Option<Arc<dyn AsyncNamespace>> {
+        self.mounts.write().unwrap().remove(path).map(|m| m.ns)
+    }
+
+    /// Find the namespace and translated path (lock released before awaiting)
+    fn resolve(&self, path: &str) -> Result<(Arc<dyn AsyncNamespace>, String)> {
+        route(&self.mounts.read().unwrap(), path).map(|(m, stripped)| (m.ns, stripped))
+    }
+}
+
+impl Default for AsyncKernel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AsyncNamespace for AsyncKernel {
+    async fn read(&self, path: &str) -> Result<Option<Scroll>> {
+        let (ns, stripped) = self.resolve(path)?;
+        let scroll = ns.read(&stripped).await?;
+
+        // Restore original path if scroll exists
+        Ok(scroll.map(|mut s| {
+            s.key = path.to_string();
+            s
+        }))
+    }
+
+    async fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+        let (ns, stripped) = self.resolve(path)?;
+        let mut scroll = ns.write(&stripped, data).await?;
+
+        // Restore original path
+        scroll.key = path.to_string();
+        Ok(scroll)
+    }
+
+    async fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
+        let (ns, stripped) = self.resolve(&scroll.key)?;
+        let key = scroll.key.clone();
+
+        let mut stripped_scroll = scroll;
+        stripped_scroll.key = stripped;
+
+        let mut result = ns.write_scroll(stripped_scroll).await?;
+
+        // Restore original path
+        result.key = key;
+        Ok(result)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let (ns, stripped) = self.resolve(prefix)?;
+        let paths = ns.list(&stripped).await?;
+
+        // Restore mount prefix to paths
+        Ok(unstrip_paths(prefix, &stripped, paths))
+    }
+
+    async fn watch(&self, pattern: &str) -> Result<ScrollStream> {
+        let targets = watch_targets(&self.mounts.read().unwrap(), pattern);
+        if targets.is_empty() {
+            return Err(Error::NotFound(format!(
+                "no namespace mounted for path: {}",
+                pattern
+            )));
+        }
+
+        // Fail only if no mount could be watched at all
+        let mut sources = Vec::new();
+        let mut first_error = None;
+        for (mount_path, mount, local) in targets {
+            match mount.ns.watch(&local).await {
+                Ok(stream) => sources.push((mount_path, mount.id, stream)),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if let (true, Some(e)) = (sources.is_empty(), first_error) {
+            return Err(e);
+        }
+
+        Ok(Box::pin(KernelStream {
+            mounts: self.mounts.clone(),
+            pattern: pattern.to_string(),
+            sources,
+        }))
+    }
+
+    async fn close(&self) -> Result<()> {
+        let mounts: Vec<_> = self.mounts.read().unwrap().values().cloned().collect();
+        for mount in mounts {
+            let _ = mount.ns.close().await;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Bridges: Receiver <-> Stream, futures -> threads
+// ============================================================================
+
+/// Turn a blocking watch receiver into a stream
+///
+/// A forwarding thread blocks on the receiver and hands scrolls to the
+/// stream, holding at most `STREAM_BUFFER` before it waits for the
+/// consumer. The thread exits when the source closes, or on the next
+/// scroll after the stream is dropped.
+pub fn receiver_stream(mut rx: Receiver<Scroll>) -> ScrollStream {
+    let shared = Arc::new(Bridge::default());
+    let producer = shared.clone();
+
+    std::thread::spawn(move || {
+        while let Some(scroll) = rx.recv() {
+            let mut state = producer.state.lock().unwrap();
+            while state.queue.len() >= STREAM_BUFFER && !state.dropped {
+                state = producer.space.wait(state).unwrap();
+            }
+            if state.dropped {
+                return;
+            }
+            state.queue.push_back(scroll);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+
+        let mut state = producer.state.lock().unwrap();
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    });
+
+    Box::pin(BridgeStream { shared })
+}
+
+#[derive(Default)]
+struct Bridge {
+    state: Mutex<BridgeState>,
+    /// Signalled when the consumer frees buffer space or goes away
+    space: Condvar,
+}
+
+#[derive(Default)]
+struct BridgeState {
+    queue: VecDeque<Scroll>,
+    waker: Option<Waker>,
+    /// Source receiver closed
+    closed: bool,
+    /// Stream dropped
+    dropped: bool,
+}
+
+struct BridgeStream {
+    shared: Arc<Bridge>,
+}
+
+impl Stream for BridgeStream {
+    type Item = Scroll;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Scroll>> {
+        let mut state = self.shared.state.lock().unwrap();
+        if let Some(scroll) = state.queue.pop_front() {
+            self.shared.space.notify_one();
+            return Poll::Ready(Some(scroll));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for BridgeStream {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.dropped = true;
+        }
+        self.shared.space.notify_one();
+    }
+}
+
+/// Merged watch over several mounts, rewriting keys to global form
+///
+/// A source is dropped once its mount is unmounted or replaced; scrolls a
+/// deeper mount shadows are skipped, as in `Kernel::watch`.
+struct KernelStream {
+    mounts: AsyncMountTable,
+    pattern: String,
+    sources: Vec<(String, u64, ScrollStream)>,
+}
+
+impl Stream for KernelStream {
+    type Item = Scroll;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Scroll>> {
+        let this = &mut *self;
+        let mut i = 0;
+
+        while i < this.sources.len() {
+            let (mount_path, id, stream) = &mut this.sources[i];
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(mut scroll)) => {
+                    scroll.key = global_key(mount_path, &scroll.key);
+
+                    let table = this.mounts.read().unwrap();
+                    match table.get(mount_path.as_str()) {
+                        Some(current) if current.id == *id => {
+                            if current.serves(&table, &scroll.key)
+                                && path_matches(&scroll.key, &this.pattern)
+                            {
+                                return Poll::Ready(Some(scroll));
+                            }
+                            // Not ours to report: poll this source again
+                        }
+                        _ => {
+                            // Unmounted or replaced
+                            drop(table);
+                            drop(this.sources.swap_remove(i));
+                        }
+                    }
+                }
+                Poll::Ready(None) => {
+                    drop(this.sources.swap_remove(i));
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        if this.sources.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Await the next item of a stream
+pub async fn next<S: Stream + Unpin + ?Sized>(stream: &mut S) -> Option<S::Item> {
+    std::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+}
+
+/// Run a future to completion on the current thread
+///
+/// A minimal executor: polls, parks the thread until woken, repeats. Never
+/// call it from inside an async runtime; that's the deadlock this module
+/// exists to avoid.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        std::thread::park();
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nine_s::{Error, MemoryNamespace};
+    use serde_json::json;
+
+    /// Natively async namespace: a map behind a mutex
+    #[derive(Default)]
+    struct AsyncMap {
+        scrolls: Mutex<BTreeMap<String, Scroll>>,
+    }
+
+    #[async_trait]
+    impl AsyncNamespace for AsyncMap {
+        async fn read(&self, path: &str) -> Result<Option<Scroll>> {
+            Ok(self.scrolls.lock().unwrap().get(path).cloned())
+        }
+
+        async fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+            let scroll = Scroll::new(path, data);
+            self.scrolls.lock().unwrap().insert(path.to_string(), scroll.clone());
+            Ok(scroll)
+        }
+
+        async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+            Ok(self
+                .scrolls
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|k| k.starts_with(prefix))
+                .cloned()
+                .collect())
+        }
+
+        async fn watch(&self, _pattern: &str) -> Result<ScrollStream> {
+            let (_tx, rx) = channel(1);
+            Ok(receiver_stream(rx))
+        }
+
+        async fn close(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn async_adapter_runs_sync_namespace() {
+        let ns = AsyncAdapter::new(MemoryNamespace::new());
+
+        block_on(async {
+            ns.write("/a", json!({"v": 1})).await.unwrap();
+            let scroll = ns.read("/a").await.unwrap().unwrap();
+            assert_eq!(scroll.data["v"], 1);
+            assert_eq!(ns.list("/").await.unwrap(), vec!["/a".to_string()]);
+        });
+    }
+
+    #[test]
+    fn blocking_adapter_runs_async_namespace() {
+        let ns = BlockingAdapter::new(AsyncMap::default());
+
+        ns.write("/x", json!("hello")).unwrap();
+        assert_eq!(ns.read("/x").unwrap().unwrap().data, "hello");
+        assert!(ns.read("/missing").unwrap().is_none());
+        assert_eq!(ns.list("/").unwrap(), vec!["/x".to_string()]);
+    }
+
+    #[test]
+    fn async_kernel_routes_by_longest_prefix() {
+        let kernel = AsyncKernel::new();
+        kernel.mount_sync("/local", Arc::new(MemoryNamespace::new()));
+        kernel.mount_box("/local/remote", AsyncMap::default());
+
+        block_on(async {
+            kernel.write("/local/a", json!(1)).await.unwrap();
+            let remote = kernel.write("/local/remote/b", json!(2)).await.unwrap();
+            assert_eq!(remote.key, "/local/remote/b");
+
+            assert_eq!(kernel.read("/local/a").await.unwrap().unwrap().key, "/local/a");
+            assert_eq!(
+                kernel.list("/local/remote").await.unwrap(),
+                vec!["/local/remote/b".to_string()]
+            );
+            assert!(kernel.read("/local/remote/a").await.unwrap().is_none());
+            assert!(matches!(kernel.read("/elsewhere").await, Err(Error::NotFound(_))));
+        });
+    }
+
+    #[test]
+    fn async_kernel_watch_streams_with_mount_prefix() {
+        let kernel = AsyncKernel::new();
+        let memory = Arc::new(MemoryNamespace::new());
+        kernel.mount_sync("/app", memory.clone());
+
+        let mut events = block_on(kernel.watch("/app/items/*")).unwrap();
+        memory.write("/items/1", json!({"n": 1})).unwrap();
+        memory.write("/items/2", json!({"n": 2})).unwrap();
+
+        let first = block_on(next(&mut events)).unwrap();
+        let second = block_on(next(&mut events)).unwrap();
+        assert_eq!(first.key, "/app/items/1");
+        assert_eq!(second.data["n"], 2);
+    }
+
+    #[test]
+    fn async_kernel_watch_spans_mounts() {
+        let kernel = AsyncKernel::new();
+        let root = Arc::new(MemoryNamespace::new());
+        let app = Arc::new(MemoryNamespace::new());
+        kernel.mount_sync("/", root.clone());
+        kernel.mount_sync("/app", app.clone());
+
+        let mut events = block_on(kernel.watch("/**")).unwrap();
+        root.write("/app/shadow", json!(0)).unwrap(); // Shadowed by /app
+        root.write("/top", json!(1)).unwrap();
+        app.write("/inner", json!(2)).unwrap();
+
+        let mut keys = vec![
+            block_on(next(&mut events)).unwrap().key,
+            block_on(next(&mut events)).unwrap().key,
+        ];
+        keys.sort();
+        assert_eq!(keys, vec!["/app/inner".to_string(), "/top".to_string()]);
+    }
+
+    #[test]
+    fn blocking_adapter_watch_bridges_stream_to_receiver() {
+        let memory = Arc::new(MemoryNamespace::new());
+        let ns = BlockingAdapter::new(AsyncAdapter::from_arc(memory.clone()));
+
+        let mut rx = ns.watch("/feed/*").unwrap();
+        memory.write("/feed/1", json!("a")).unwrap();
+
+        assert_eq!(rx.recv().unwrap().key, "/feed/1");
+    }
+}
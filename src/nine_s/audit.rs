@@ -0,0 +1,429 @@
+//! Audit - Store-wide, hash-chained log of history changes
+//!
+//! Each patch chains to its own scroll's previous state, but nothing ties
+//! scrolls together: a patch file deleted from `_history/`, or two swapped,
+//! leaves no trace. The audit log does. Every patch the Store writes is
+//! appended as an entry that commits to a digest of the patch and to the
+//! hash of the entry before it, so any gap, reordering or rewrite shows up
+//! when the log is replayed against history.
+//!
+//! Pure data structures and checks; `Store` seals and persists the log.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Per-scroll hash chains (cheap, blind to missing files)
+//! **Antithesis**: Snapshot and sign the whole store on every write (slow)
+//! **Synthesis**: One append-only chain over patch digests
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! let report = store.verify_integrity()?;
+//! if !report.is_intact() {
+//!     for issue in &report.issues {
+//!         eprintln!("history tampered: {}", issue);
+//!     }
+//! }
+//!
+//! // Pin the head somewhere the store can't rewrite it (relay, backup, ...)
+//! let pinned = report.head;
+//! ```
+
+use super::patch::Patch;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// What an audit entry records about a scroll's history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum AuditAction {
+    /// A patch was written (see [`patch_digest`])
+    Patch { seq: u64, digest: String },
+    /// Patches below `below` were compacted away
+    Compact { below: u64 },
+    /// The whole history was erased
+    Purge,
+}
+
+/// One link in the audit chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the log (1-based, gapless)
+    pub seq: u64,
+    /// Scroll path the action applies to
+    pub path: String,
+    /// What happened
+    pub action: AuditAction,
+    /// Unix millis when the entry was appended
+    pub timestamp: i64,
+    /// Hash of the previous entry (None for the first)
+    pub prev: Option<String>,
+    /// Hash over every other field
+    pub hash: String,
+}
+
+/// Fields covered by an entry's hash
+#[derive(Serialize)]
+struct EntryBody<'a> {
+    seq: u64,
+    path: &'a str,
+    action: &'a AuditAction,
+    timestamp: i64,
+    prev: Option<&'a str>,
+}
+
+impl AuditEntry {
+    /// Create the entry following `prev` (None starts the log)
+    pub fn new(prev: Option<&AuditHead>, path: impl Into<String>, action: AuditAction) -> Self {
+        let mut entry = Self {
+            seq: prev.map_or(1, |h| h.seq + 1),
+            path: path.into(),
+            action,
+            timestamp: super::current_time_millis(),
+            prev: prev.map(|h| h.hash.clone()),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        entry
+    }
+
+    /// SHA-256 (hex) over every field but `hash`
+    pub fn compute_hash(&self) -> String {
+        let body = EntryBody {
+            seq: self.seq,
+            path: &self.path,
+            action: &self.action,
+            timestamp: self.timestamp,
+            prev: self.prev.as_deref(),
+        };
+        hex::encode(Sha256::digest(serde_json::to_vec(&body).unwrap_or_default()))
+    }
+
+    /// Check that the stored hash matches the contents
+    pub fn verify(&self) -> bool {
+        self.hash == self.compute_hash()
+    }
+
+    /// Head of a log ending at this entry
+    pub fn head(&self) -> AuditHead {
+        AuditHead {
+            seq: self.seq,
+            hash: self.hash.clone(),
+        }
+    }
+}
+
+/// Latest entry of the log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditHead {
+    /// Sequence number of the last entry
+    pub seq: u64,
+    /// Hash of the last entry
+    pub hash: String,
+}
+
+/// Digest of a patch as committed to by the log
+///
+/// SHA-256 (hex) over the patch's JSON, so it's independent of how the
+/// patch is sealed on disk and survives key rotation.
+pub fn patch_digest(patch: &Patch) -> String {
+    hex::encode(Sha256::digest(serde_json::to_vec(patch).unwrap_or_default()))
+}
+
+/// Something `Store::verify_integrity` found wrong
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// An entry file is missing from the log
+    MissingEntry { seq: u64 },
+    /// An entry is unreadable or its contents don't match its hash
+    TamperedEntry { seq: u64 },
+    /// An entry sits at the wrong position in the log
+    OutOfOrder { seq: u64 },
+    /// An entry doesn't commit to the hash of the one before it
+    BrokenLink { seq: u64 },
+    /// The recorded head doesn't match the last entry (truncated or extended)
+    HeadMismatch,
+    /// A logged patch is gone from history
+    MissingPatch { path: String, seq: u64 },
+    /// A patch differs from the one that was logged
+    RewrittenPatch { path: String, seq: u64 },
+    /// A patch in history was never logged
+    UnloggedPatch { path: String, seq: u64 },
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::MissingEntry { seq } => write!(f, "audit entry {} is missing", seq),
+            IntegrityIssue::TamperedEntry { seq } => write!(f, "audit entry {} was altered", seq),
+            IntegrityIssue::OutOfOrder { seq } => write!(f, "audit entry {} is out of order", seq),
+            IntegrityIssue::BrokenLink { seq } => {
+                write!(f, "audit entry {} does not follow its predecessor", seq)
+            }
+            IntegrityIssue::HeadMismatch => write!(f, "audit head does not match the log"),
+            IntegrityIssue::MissingPatch { path, seq } => {
+                write!(f, "patch {} of {} is missing", seq, path)
+            }
+            IntegrityIssue::RewrittenPatch { path, seq } => {
+                write!(f, "patch {} of {} was rewritten", seq, path)
+            }
+            IntegrityIssue::UnloggedPatch { path, seq } => {
+                write!(f, "patch {} of {} is not in the audit log", seq, path)
+            }
+        }
+    }
+}
+
+/// Result of `Store::verify_integrity`
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    /// Audit entries walked
+    pub entries: u64,
+    /// Patches checked against the log
+    pub patches: usize,
+    /// Head of the log, for pinning outside the store
+    pub head: Option<AuditHead>,
+    /// Everything that didn't check out
+    pub issues: Vec<IntegrityIssue>,
+    /// Newest patch of a path that was logged but never landed (a write
+    /// cut short); the next write at the path replaces it
+    pub interrupted: Vec<(String, u64)>,
+}
+
+impl IntegrityReport {
+    /// True if the log and history agree completely
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// A patch the log says should be in history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedPatch {
+    /// Digest it was logged with
+    pub digest: String,
+    /// Compacted or purged since: may be absent, must match if present
+    pub optional: bool,
+}
+
+/// Check the chain links of a log
+///
+/// `entries` maps each entry file's position to its contents (None if it
+/// couldn't be read); positions absent from the map are missing files.
+/// Positions run from 1 to the larger of the head and the last file.
+pub fn verify_chain(
+    entries: &BTreeMap<u64, Option<AuditEntry>>,
+    head: Option<&AuditHead>,
+) -> Vec<IntegrityIssue> {
+    let mut issues = Vec::new();
+    let last = entries
+        .keys()
+        .next_back()
+        .copied()
+        .unwrap_or(0)
+        .max(head.map_or(0, |h| h.seq));
+
+    // None once the chain is broken: the next link can't be checked
+    let mut expected_prev: Option<Option<String>> = Some(None);
+    for seq in 1..=last {
+        let entry = match entries.get(&seq) {
+            None => {
+                issues.push(IntegrityIssue::MissingEntry { seq });
+                expected_prev = None;
+                continue;
+            }
+            Some(None) => {
+                issues.push(IntegrityIssue::TamperedEntry { seq });
+                expected_prev = None;
+                continue;
+            }
+            Some(Some(entry)) => entry,
+        };
+
+        if !entry.verify() {
+            issues.push(IntegrityIssue::TamperedEntry { seq });
+            expected_prev = None;
+            continue;
+        }
+        if entry.seq != seq {
+            issues.push(IntegrityIssue::OutOfOrder { seq });
+        } else if expected_prev.as_ref().is_some_and(|prev| *prev != entry.prev) {
+            issues.push(IntegrityIssue::BrokenLink { seq });
+        }
+        expected_prev = Some(Some(entry.hash.clone()));
+    }
+
+    let last_hash = entries.get(&last).and_then(|e| e.as_ref()).map(|e| &e.hash);
+    let head_matches = match head {
+        Some(h) => h.seq == last && Some(&h.hash) == last_hash,
+        None => last == 0,
+    };
+    if !head_matches {
+        issues.push(IntegrityIssue::HeadMismatch);
+    }
+
+    issues
+}
+
+/// Replay a log into the patches each path should have, by seq
+///
+/// Later entries win, so a path whose history was purged and rewritten
+/// expects the new patches.
+pub fn expected_patches<'a>(
+    entries: impl IntoIterator<Item = &'a AuditEntry>,
+) -> BTreeMap<String, BTreeMap<u64, ExpectedPatch>> {
+    let mut expected: BTreeMap<String, BTreeMap<u64, ExpectedPatch>> = BTreeMap::new();
+    for entry in entries {
+        let patches = expected.entry(entry.path.clone()).or_default();
+        match &entry.action {
+            AuditAction::Patch { seq, digest } => {
+                patches.insert(
+                    *seq,
+                    ExpectedPatch {
+                        digest: digest.clone(),
+                        optional: false,
+                    },
+                );
+            }
+            AuditAction::Compact { below } => {
+                for (_, p) in patches.range_mut(..*below) {
+                    p.optional = true;
+                }
+            }
+            AuditAction::Purge => {
+                for p in patches.values_mut() {
+                    p.optional = true;
+                }
+            }
+        }
+    }
+    expected
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(actions: Vec<(&str, AuditAction)>) -> BTreeMap<u64, Option<AuditEntry>> {
+        let mut head: Option<AuditHead> = None;
+        let mut entries = BTreeMap::new();
+        for (path, action) in actions {
+            let entry = AuditEntry::new(head.as_ref(), path, action);
+            head = Some(entry.head());
+            entries.insert(entry.seq, Some(entry));
+        }
+        entries
+    }
+
+    fn patch(seq: u64, digest: &str) -> AuditAction {
+        AuditAction::Patch {
+            seq,
+            digest: digest.to_string(),
+        }
+    }
+
+    fn head_of(entries: &BTreeMap<u64, Option<AuditEntry>>) -> Option<AuditHead> {
+        entries.values().next_back().and_then(|e| e.as_ref()).map(|e| e.head())
+    }
+
+    #[test]
+    fn chain_of_appended_entries_verifies() {
+        let entries = log(vec![("/a", patch(1, "d1")), ("/b", patch(1, "d2")), ("/a", patch(2, "d3"))]);
+        let head = head_of(&entries);
+
+        assert!(verify_chain(&entries, head.as_ref()).is_empty());
+        assert!(verify_chain(&BTreeMap::new(), None).is_empty());
+    }
+
+    #[test]
+    fn chain_reports_gaps_rewrites_and_reordering() {
+        let entries = log(vec![
+            ("/a", patch(1, "d1")),
+            ("/a", patch(2, "d2")),
+            ("/a", patch(3, "d3")),
+            ("/a", patch(4, "d4")),
+        ]);
+        let head = head_of(&entries);
+
+        let mut gap = entries.clone();
+        gap.remove(&2);
+        assert_eq!(
+            verify_chain(&gap, head.as_ref()),
+            vec![IntegrityIssue::MissingEntry { seq: 2 }]
+        );
+
+        let mut rewritten = entries.clone();
+        if let Some(Some(e)) = rewritten.get_mut(&3) {
+            e.action = patch(3, "forged");
+        }
+        assert_eq!(
+            verify_chain(&rewritten, head.as_ref()),
+            vec![IntegrityIssue::TamperedEntry { seq: 3 }]
+        );
+
+        let mut swapped = entries.clone();
+        let (two, three) = (swapped[&2].clone(), swapped[&3].clone());
+        swapped.insert(2, three);
+        swapped.insert(3, two);
+        let issues = verify_chain(&swapped, head.as_ref());
+        assert!(issues.contains(&IntegrityIssue::OutOfOrder { seq: 2 }));
+        assert!(issues.contains(&IntegrityIssue::OutOfOrder { seq: 3 }));
+
+        let mut truncated = entries.clone();
+        truncated.remove(&4);
+        let stale_head = head_of(&truncated);
+        assert_eq!(
+            verify_chain(&truncated, head.as_ref()),
+            vec![IntegrityIssue::MissingEntry { seq: 4 }, IntegrityIssue::HeadMismatch]
+        );
+        assert!(verify_chain(&truncated, stale_head.as_ref()).is_empty());
+    }
+
+    #[test]
+    fn replay_marks_compacted_and_purged_patches_optional() {
+        let entries = log(vec![
+            ("/a", patch(1, "a1")),
+            ("/a", patch(2, "a2")),
+            ("/a", patch(3, "a3")),
+            ("/a", AuditAction::Compact { below: 3 }),
+            ("/b", patch(1, "b1")),
+            ("/b", AuditAction::Purge),
+            ("/b", patch(1, "b1-new")),
+        ]);
+        let expected = expected_patches(entries.values().flatten());
+
+        let a = &expected["/a"];
+        assert!(a[&1].optional && a[&2].optional);
+        assert!(!a[&3].optional);
+
+        let b = &expected["/b"];
+        assert_eq!(b.len(), 1);
+        assert_eq!(b[&1].digest, "b1-new");
+        assert!(!b[&1].optional);
+    }
+
+    #[test]
+    fn patch_digest_tracks_contents() {
+        let patch = Patch {
+            key: "/a".to_string(),
+            ops: Vec::new(),
+            parent: None,
+            hash: "h".to_string(),
+            timestamp: 1,
+            seq: 1,
+            deleted: false,
+            migration: None,
+        };
+        let mut other = patch.clone();
+        other.seq = 2;
+
+        assert_eq!(patch_digest(&patch), patch_digest(&patch.clone()));
+        assert_ne!(patch_digest(&patch), patch_digest(&other));
+    }
+}
@@ -11,27 +11,53 @@
 //!       bar.json      <- /foo/bar scroll
 //!       baz.json      <- /foo/baz scroll
 //!     root.json       <- / scroll (if written)
+//!   _locks/
+//!     {sha256}.lock   <- held during a conditional write
+//!   _expires/
+//!     {sha256}.json   <- TTL of a scroll with `expires_at`
 //! ```
 //!
+//! # Expiry
+//! Scrolls whose `expires_at` has passed are invisible to `read` and
+//! `list`. `sweep_expired()` deletes them and notifies watchers. The
+//! `_expires/` sidecars let `list` and the sweeper find expiring scrolls
+//! without opening every file.
+//!
+//! # Deletion
+//! Writing a scroll marked deleted removes the file, as does `delete()`;
+//! watchers receive the last state marked deleted. `purge()` overwrites
+//! the file before unlinking it.
+//!
 //! # Security
 //! - Path traversal prevented (no .. allowed)
 //! - Segment boundary matching (same as MemoryNamespace)
 
-use super::super::namespace::{path_matches, validate_path, Error, Namespace, Receiver, Result};
-use super::super::scroll::{current_iso_time, Scroll};
+use super::super::namespace::{
+    path_matches, validate_path, Error, Namespace, Precondition, Receiver, Result,
+};
+use super::super::scroll::{current_iso_time, current_time_millis, Scroll};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 #[cfg(feature = "std-channel")]
 use super::super::channel::{channel, Sender};
 
 const MAX_WATCHERS: usize = 1024;
 
+/// Lock files older than this are assumed abandoned by a crashed process
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(10);
+
+/// How long to wait for a contended path lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Check if a path is under a prefix on segment boundaries.
 fn is_path_under_prefix(path: &str, prefix: &str) -> bool {
     if prefix == "/" {
@@ -109,6 +135,20 @@ fn fs_path_to_scroll(base: &Path, fs_path: &Path) -> Option<String> {
     Some(format!("/{}", parts.join("/")))
 }
 
+/// Cross-process lock on a single scroll path
+///
+/// Held as an exclusively-created file under `_locks/` and released
+/// (removed) on drop.
+pub(crate) struct PathLock {
+    file: PathBuf,
+}
+
+impl Drop for PathLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.file);
+    }
+}
+
 struct Watcher {
     pattern: String,
     tx: Sender<Scroll>,
@@ -117,6 +157,14 @@ struct Watcher {
     dropped: Arc<AtomicU64>,
 }
 
+/// TTL sidecar stored in `_expires/`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExpiryEntry {
+    path: String,
+    expires_at: i64,
+}
+
 /// FileNamespace - Persistent storage using filesystem
 pub struct FileNamespace {
     base_dir: PathBuf,
@@ -204,19 +252,7 @@ impl Namespace for FileNamespace {
         self.check_closed()?;
         validate_path(path)?;
 
-        let fs_path = scroll_path_to_fs(&self.base_dir, path);
-
-        if !fs_path.exists() {
-            return Ok(None);
-        }
-
-        let file = File::open(&fs_path)
-            .map_err(|e| Error::Internal(format!("Failed to open file: {}", e)))?;
-
-        let scroll: Scroll = serde_json::from_reader(BufReader::new(file))
-            .map_err(|e| Error::InvalidData(format!("Failed to parse scroll: {}", e)))?;
-
-        Ok(Some(scroll))
+        Ok(self.read_file(path)?.filter(|s| !s.is_expired()))
     }
 
     fn write(&self, path: &str, data: Value) -> Result<Scroll> {
@@ -250,6 +286,7 @@ impl Namespace for FileNamespace {
 
         // Update version cache
         self.set_version(path, prev_version + 1);
+        self.track_expiry(&scroll)?;
 
         // Notify watchers
         self.notify_watchers(&scroll);
@@ -261,6 +298,11 @@ impl Namespace for FileNamespace {
         self.check_closed()?;
         validate_path(&scroll.key)?;
 
+        // Writing a tombstone deletes the path
+        if scroll.is_deleted() {
+            return Ok(self.delete(&scroll.key)?.unwrap_or(scroll));
+        }
+
         let fs_path = scroll_path_to_fs(&self.base_dir, &scroll.key);
 
         // Ensure parent directory exists
@@ -290,6 +332,7 @@ impl Namespace for FileNamespace {
 
         // Update version cache
         self.set_version(&scroll.key, prev_version + 1);
+        self.track_expiry(&new_scroll)?;
 
         // Notify watchers
         self.notify_watchers(&new_scroll);
@@ -328,10 +371,13 @@ impl Namespace for FileNamespace {
         walk_dir(&scrolls_dir, &self.base_dir, &mut paths)
             .map_err(|e| Error::Internal(format!("Failed to list directory: {}", e)))?;
 
-        // Filter by prefix with segment boundary check
+        // Filter by prefix with segment boundary check, hiding expired scrolls
+        let expiring = self.expiring()?;
+        let now = current_time_millis();
         let filtered: Vec<String> = paths
             .into_iter()
             .filter(|p| is_path_under_prefix(p, prefix))
+            .filter(|p| !expiring.get(p).is_some_and(|&expires| expires <= now))
             .collect();
 
         Ok(filtered)
@@ -378,6 +424,275 @@ impl Namespace for FileNamespace {
 }
 
 impl FileNamespace {
+    /// Conditional write (compare-and-swap)
+    ///
+    /// Writes `data` only if the scroll on disk satisfies `expected`.
+    /// The check and write run under a lock file, so conditional writers
+    /// in different processes sharing the directory cannot both succeed.
+    pub fn write_if(&self, path: &str, data: Value, expected: &Precondition) -> Result<Scroll> {
+        self.write_scroll_if(Scroll::new(path, data), expected)
+    }
+
+    /// Conditional write of a full scroll (preserves type and metadata)
+    pub fn write_scroll_if(&self, scroll: Scroll, expected: &Precondition) -> Result<Scroll> {
+        self.check_closed()?;
+        validate_path(&scroll.key)?;
+
+        let _lock = self.lock_path(&scroll.key)?;
+
+        // Always check against disk: another process may have written
+        let current = self.read(&scroll.key)?;
+        expected.check(&scroll.key, current.as_ref())?;
+
+        // Sync the version cache with what we just read
+        let version = current.map(|s| s.metadata.version).unwrap_or(0);
+        self.set_version(&scroll.key, version);
+
+        self.write_scroll(scroll)
+    }
+
+    /// Delete a scroll, notifying watchers
+    ///
+    /// Watchers receive the removed scroll marked deleted. Returns the
+    /// removed scroll (expired or not), or None if nothing was there.
+    pub fn delete(&self, path: &str) -> Result<Option<Scroll>> {
+        self.check_closed()?;
+        validate_path(path)?;
+
+        let scroll = self.read_file(path)?;
+        let _ = fs::remove_file(self.expiry_file(path));
+        let Some(scroll) = scroll else {
+            return Ok(None);
+        };
+
+        fs::remove_file(scroll_path_to_fs(&self.base_dir, path))
+            .map_err(|e| Error::Internal(format!("Failed to delete scroll: {}", e)))?;
+
+        let removed = scroll.mark_deleted();
+        self.notify_watchers(&removed);
+
+        Ok(Some(removed))
+    }
+
+    /// Delete a scroll, overwriting its file before unlinking it
+    ///
+    /// Unlike `delete`, watchers receive a bare tombstone (`null` data), so
+    /// the erased content doesn't travel any further. Returns false if
+    /// nothing was there.
+    pub fn purge(&self, path: &str) -> Result<bool> {
+        self.check_closed()?;
+        validate_path(path)?;
+
+        let _ = fs::remove_file(self.expiry_file(path));
+        let fs_path = scroll_path_to_fs(&self.base_dir, path);
+        if !fs_path.exists() {
+            return Ok(false);
+        }
+
+        shred_file(&fs_path)
+            .map_err(|e| Error::Internal(format!("Failed to purge scroll: {}", e)))?;
+
+        self.notify_watchers(&Scroll::new(path, Value::Null).mark_deleted());
+
+        Ok(true)
+    }
+
+    /// Delete every scroll whose `expires_at` has passed
+    ///
+    /// Returns the removed scrolls, marked deleted (as watchers see them).
+    pub fn sweep_expired(&self) -> Result<Vec<Scroll>> {
+        self.check_closed()?;
+
+        let mut removed = Vec::new();
+        for path in self.expired_paths()? {
+            let _lock = self.lock_path(&path)?;
+
+            match self.read_file(&path)? {
+                Some(scroll) if scroll.is_expired() => removed.extend(self.delete(&path)?),
+                // Rewritten with a later TTL since the sidecar was read
+                Some(scroll) => self.track_expiry(&scroll)?,
+                None => {
+                    self.delete(&path)?;
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Move a scroll to another path without notifying watchers
+    ///
+    /// The stored key is rewritten and the TTL sidecar follows. If a scroll
+    /// already exists at `to` it wins, and `from` is simply removed.
+    /// Returns false if there was nothing at `from`. Used by `Store` to
+    /// move files to blinded names.
+    #[cfg(feature = "crypto")]
+    pub(crate) fn relocate(&self, from: &str, to: &str) -> Result<bool> {
+        self.check_closed()?;
+        validate_path(to)?;
+
+        let Some(mut scroll) = self.read_file(from)? else {
+            return Ok(false);
+        };
+
+        let to_fs = scroll_path_to_fs(&self.base_dir, to);
+        if !to_fs.exists() {
+            if let Some(parent) = to_fs.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| Error::Internal(format!("Failed to create directory: {}", e)))?;
+            }
+            scroll.key = to.to_string();
+
+            // Temp file + rename: a crash leaves the scroll at one path or both
+            let tmp_path = to_fs.with_extension("json.tmp");
+            let file = File::create(&tmp_path)
+                .map_err(|e| Error::Internal(format!("Failed to create file: {}", e)))?;
+            serde_json::to_writer_pretty(BufWriter::new(file), &scroll)
+                .map_err(|e| Error::Internal(format!("Failed to write scroll: {}", e)))?;
+            fs::rename(&tmp_path, &to_fs)
+                .map_err(|e| Error::Internal(format!("Failed to move scroll: {}", e)))?;
+            self.track_expiry(&scroll)?;
+        }
+
+        fs::remove_file(scroll_path_to_fs(&self.base_dir, from))
+            .map_err(|e| Error::Internal(format!("Failed to remove scroll: {}", e)))?;
+        let _ = fs::remove_file(self.expiry_file(from));
+        self.versions.write().unwrap().remove(from);
+
+        Ok(true)
+    }
+
+    /// Paths whose TTL sidecar says they have expired
+    pub fn expired_paths(&self) -> Result<Vec<String>> {
+        let now = current_time_millis();
+        let mut paths: Vec<String> = self
+            .expiring()?
+            .into_iter()
+            .filter(|(_, expires)| *expires <= now)
+            .map(|(path, _)| path)
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Read a scroll file as-is, ignoring expiry
+    pub(crate) fn read_file(&self, path: &str) -> Result<Option<Scroll>> {
+        let fs_path = scroll_path_to_fs(&self.base_dir, path);
+
+        if !fs_path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(&fs_path)
+            .map_err(|e| Error::Internal(format!("Failed to open file: {}", e)))?;
+
+        let scroll: Scroll = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| Error::InvalidData(format!("Failed to parse scroll: {}", e)))?;
+
+        Ok(Some(scroll))
+    }
+
+    /// Sidecar file holding a path's TTL
+    fn expiry_file(&self, path: &str) -> PathBuf {
+        let name = hex::encode(Sha256::digest(path.as_bytes()));
+        self.base_dir.join("_expires").join(format!("{}.json", name))
+    }
+
+    /// Create, update or remove the TTL sidecar for a just-written scroll
+    fn track_expiry(&self, scroll: &Scroll) -> Result<()> {
+        let file_path = self.expiry_file(&scroll.key);
+        let expires_at = scroll
+            .metadata
+            .expires_at
+            .as_deref()
+            .and_then(|t| t.parse::<i64>().ok());
+
+        match expires_at {
+            Some(expires_at) => {
+                if let Some(parent) = file_path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| Error::Internal(format!("Failed to create directory: {}", e)))?;
+                }
+                let entry = ExpiryEntry {
+                    path: scroll.key.clone(),
+                    expires_at,
+                };
+                let file = File::create(&file_path)
+                    .map_err(|e| Error::Internal(format!("Failed to create file: {}", e)))?;
+                serde_json::to_writer(BufWriter::new(file), &entry)
+                    .map_err(|e| Error::Internal(format!("Failed to write expiry: {}", e)))
+            }
+            None => {
+                if file_path.exists() {
+                    let _ = fs::remove_file(&file_path);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Map of path -> expires_at (Unix millis) for every expiring scroll
+    fn expiring(&self) -> Result<HashMap<String, i64>> {
+        let dir = self.base_dir.join("_expires");
+        let mut expiring = HashMap::new();
+        if !dir.exists() {
+            return Ok(expiring);
+        }
+
+        for entry in fs::read_dir(&dir)
+            .map_err(|e| Error::Internal(format!("Failed to list directory: {}", e)))?
+            .flatten()
+        {
+            let parsed = File::open(entry.path())
+                .ok()
+                .and_then(|f| serde_json::from_reader::<_, ExpiryEntry>(BufReader::new(f)).ok());
+            if let Some(e) = parsed {
+                expiring.insert(e.path, e.expires_at);
+            }
+        }
+
+        Ok(expiring)
+    }
+
+    /// Acquire the cross-process lock for a scroll path
+    ///
+    /// Spins until the lock is free, breaking locks older than
+    /// `LOCK_STALE_AFTER`. Fails with `Error::Timeout` after `LOCK_TIMEOUT`.
+    pub(crate) fn lock_path(&self, path: &str) -> Result<PathLock> {
+        let lock_dir = self.base_dir.join("_locks");
+        fs::create_dir_all(&lock_dir)
+            .map_err(|e| Error::Internal(format!("Failed to create lock dir: {}", e)))?;
+
+        // Hash the path so nested paths don't need nested directories
+        let name = hex::encode(Sha256::digest(path.as_bytes()));
+        let file = lock_dir.join(format!("{}.lock", name));
+        let start = Instant::now();
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&file) {
+                Ok(_) => return Ok(PathLock { file }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&file)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|t| t.elapsed().ok())
+                        .is_some_and(|age| age > LOCK_STALE_AFTER);
+                    if stale {
+                        let _ = fs::remove_file(&file);
+                        continue;
+                    }
+                    if start.elapsed() > LOCK_TIMEOUT {
+                        return Err(Error::Timeout);
+                    }
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Err(e) => {
+                    return Err(Error::Internal(format!("Failed to acquire lock: {}", e)));
+                }
+            }
+        }
+    }
+
     /// Delete all scrolls in this namespace (DANGER: destroys all data)
     ///
     /// This physically removes all files in the _scrolls directory.
@@ -397,6 +712,12 @@ impl FileNamespace {
         fs::create_dir_all(&scrolls_dir)
             .map_err(|e| Error::Internal(format!("Failed to recreate scrolls dir: {}", e)))?;
 
+        let expires_dir = self.base_dir.join("_expires");
+        if expires_dir.exists() {
+            fs::remove_dir_all(&expires_dir)
+                .map_err(|e| Error::Internal(format!("Failed to delete expiries: {}", e)))?;
+        }
+
         // Clear version cache
         self.versions.write().unwrap().clear();
 
@@ -404,6 +725,19 @@ impl FileNamespace {
     }
 }
 
+/// Overwrite a file with zeros, flush to disk, then unlink it
+///
+/// Best effort: journaling filesystems and flash wear-levelling may still
+/// hold older copies of the blocks.
+pub(crate) fn shred_file(path: &Path) -> std::io::Result<()> {
+    let len = fs::metadata(path)?.len();
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    file.write_all(&vec![0u8; len as usize])?;
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -677,4 +1011,119 @@ mod tests {
         let scroll = ns.read("/new").unwrap().unwrap();
         assert_eq!(scroll.metadata.version, 1);
     }
+
+    #[test]
+    fn file_write_if_conflict() {
+        let dir = tempdir().unwrap();
+        let ns = FileNamespace::new(dir.path()).unwrap();
+
+        let created = ns.write_if("/doc", json!({"v": 1}), &Precondition::Absent).unwrap();
+        let hash = created.metadata.hash.clone().unwrap();
+
+        ns.write_if("/doc", json!({"v": 2}), &Precondition::Hash(hash.clone())).unwrap();
+
+        let result = ns.write_if("/doc", json!({"v": 3}), &Precondition::Hash(hash));
+        assert!(matches!(result, Err(Error::Conflict(_))));
+        assert_eq!(ns.read("/doc").unwrap().unwrap().data["v"], 2);
+
+        // Lock is released
+        assert_eq!(fs::read_dir(dir.path().join("_locks")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn file_write_if_sees_other_instance() {
+        let dir = tempdir().unwrap();
+        let a = FileNamespace::new(dir.path()).unwrap();
+        let b = FileNamespace::new(dir.path()).unwrap();
+
+        a.write("/doc", json!({"v": 1})).unwrap();
+        let seen_by_b = b.read("/doc").unwrap().unwrap();
+
+        // A moves on; B's version cache knows nothing about it
+        a.write("/doc", json!({"v": 2, "author": "A"})).unwrap();
+
+        let result = b.write_if(
+            "/doc",
+            json!({"v": 2, "author": "B"}),
+            &Precondition::Version(seen_by_b.metadata.version),
+        );
+        assert!(matches!(result, Err(Error::Conflict(_))));
+        assert_eq!(a.read("/doc").unwrap().unwrap().data["author"], "A");
+    }
+
+    #[test]
+    fn file_lock_breaks_stale_lock() {
+        let dir = tempdir().unwrap();
+        let ns = FileNamespace::new(dir.path()).unwrap();
+
+        // Leave a lock behind, as a crashed process would, and age it
+        let lock = ns.lock_path("/doc").unwrap();
+        let file = lock.file.clone();
+        std::mem::forget(lock);
+        let old = std::time::SystemTime::now() - Duration::from_secs(60);
+        File::options().write(true).open(&file).unwrap().set_modified(old).unwrap();
+
+        assert!(ns.write_if("/doc", json!(1), &Precondition::Absent).is_ok());
+    }
+
+    #[test]
+    fn file_delete_and_purge_notify_watchers() {
+        let dir = tempdir().unwrap();
+        let ns = FileNamespace::new(dir.path()).unwrap();
+        ns.write("/contacts/alice", json!({"name": "Alice"})).unwrap();
+        ns.write("/contacts/bob", json!({"name": "Bob"})).unwrap();
+        let mut rx = ns.watch("/contacts/*").unwrap();
+
+        ns.write_scroll(Scroll::new("/contacts/alice", Value::Null).mark_deleted())
+            .unwrap();
+        assert!(ns.read("/contacts/alice").unwrap().is_none());
+        assert_eq!(ns.list("/contacts").unwrap(), vec!["/contacts/bob".to_string()]);
+
+        let event = rx.try_recv().unwrap();
+        assert!(event.is_deleted());
+        assert_eq!(event.data["name"], "Alice");
+
+        assert!(ns.purge("/contacts/bob").unwrap());
+        assert!(!ns.purge("/contacts/bob").unwrap());
+        assert!(!dir.path().join("_scrolls/contacts/bob.json").exists());
+
+        let event = rx.try_recv().unwrap();
+        assert!(event.is_deleted());
+        assert!(event.data.is_null());
+    }
+
+    #[test]
+    fn file_expired_scrolls_hidden_and_swept() {
+        let dir = tempdir().unwrap();
+        let ns = FileNamespace::new(dir.path()).unwrap();
+        let past = (current_time_millis() - 1).to_string();
+        let future = (current_time_millis() + 60_000).to_string();
+
+        ns.write_scroll(Scroll::new("/invoice/old", json!({})).with_expires_at(past))
+            .unwrap();
+        ns.write_scroll(Scroll::new("/invoice/new", json!({})).with_expires_at(future))
+            .unwrap();
+        ns.write("/invoice/plain", json!({})).unwrap();
+
+        assert!(ns.read("/invoice/old").unwrap().is_none());
+        assert!(ns.read("/invoice/new").unwrap().is_some());
+        let mut paths = ns.list("/invoice").unwrap();
+        paths.sort();
+        assert_eq!(paths, vec!["/invoice/new".to_string(), "/invoice/plain".to_string()]);
+        assert_eq!(ns.expired_paths().unwrap(), vec!["/invoice/old".to_string()]);
+
+        let mut rx = ns.watch("/invoice/*").unwrap();
+        let swept = ns.sweep_expired().unwrap();
+        assert_eq!(swept.len(), 1);
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.key, "/invoice/old");
+        assert!(event.is_deleted());
+        assert!(!dir.path().join("_scrolls/invoice/old.json").exists());
+        assert!(ns.expired_paths().unwrap().is_empty());
+
+        // Rewriting without a TTL clears the sidecar
+        ns.write("/invoice/new", json!({})).unwrap();
+        assert_eq!(fs::read_dir(dir.path().join("_expires")).unwrap().count(), 0);
+    }
 }
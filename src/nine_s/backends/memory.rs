@@ -7,12 +7,18 @@
 //! - Watchers are automatically cleaned up when their receivers are dropped
 //! - Uses an `alive` flag that the receiver sets to false on Drop
 //! - Periodic pruning removes dead watchers during notify operations
+//!
+//! # Deletion
+//! Writing a scroll marked deleted removes the entry, as does `delete()`;
+//! watchers receive the last state marked deleted.
 
 #[cfg(feature = "std-channel")]
 use super::super::channel::{channel, Sender};
 
-use super::super::namespace::{path_matches, validate_path, Error, Namespace, Receiver, Result};
-use super::super::scroll::{current_iso_time, Scroll};
+use super::super::namespace::{
+    path_matches, validate_path, Error, Namespace, Precondition, Receiver, Result,
+};
+use super::super::scroll::{current_iso_time, current_time_millis, Scroll};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -213,6 +219,103 @@ impl MemoryNamespace {
 
         Ok(WatchReceiver::new(rx, alive))
     }
+
+    /// Conditional write (compare-and-swap)
+    ///
+    /// Writes `data` only if the current scroll satisfies `expected`.
+    /// The check and the write happen under one lock, so concurrent
+    /// conditional writers cannot both succeed.
+    pub fn write_if(&self, path: &str, data: Value, expected: &Precondition) -> Result<Scroll> {
+        self.write_scroll_if(Scroll::new(path, data), expected)
+    }
+
+    /// Conditional write of a full scroll (preserves type and metadata)
+    pub fn write_scroll_if(&self, scroll: Scroll, expected: &Precondition) -> Result<Scroll> {
+        self.check_closed()?;
+        validate_path(&scroll.key)?;
+
+        let mut store = self.inner.store.write().unwrap();
+        let current = store.get(&scroll.key).filter(|s| !s.is_expired());
+        expected.check(&scroll.key, current)?;
+
+        if scroll.is_deleted() {
+            let removed = store.remove(&scroll.key).map(|s| s.mark_deleted());
+            drop(store);
+            return Ok(self.deleted(removed).unwrap_or(scroll));
+        }
+
+        let new_scroll = next_scroll(store.get(&scroll.key), scroll);
+        store.insert(new_scroll.key.clone(), new_scroll.clone());
+
+        drop(store);
+        self.notify_watchers(&new_scroll);
+
+        Ok(new_scroll)
+    }
+
+    /// Delete a scroll
+    ///
+    /// Watchers receive the removed scroll marked deleted. Returns it the
+    /// same way, or None if nothing was there.
+    pub fn delete(&self, path: &str) -> Result<Option<Scroll>> {
+        self.check_closed()?;
+        validate_path(path)?;
+
+        let removed = self.inner.store.write().unwrap().remove(path);
+        Ok(self.deleted(removed.map(|s| s.mark_deleted())))
+    }
+
+    /// Notify watchers of a removal (store lock already released)
+    fn deleted(&self, removed: Option<Scroll>) -> Option<Scroll> {
+        if let Some(ref scroll) = removed {
+            self.notify_watchers(scroll);
+        }
+        removed
+    }
+
+    /// Remove every scroll whose `expires_at` has passed
+    ///
+    /// Expired scrolls are already invisible to `read` and `list`; this
+    /// frees them and tells watchers. Each removed scroll is delivered to
+    /// matching watchers marked deleted, and returned the same way.
+    pub fn sweep_expired(&self) -> Result<Vec<Scroll>> {
+        self.check_closed()?;
+
+        let now = current_time_millis();
+        let mut store = self.inner.store.write().unwrap();
+        let expired: Vec<String> = store
+            .iter()
+            .filter(|(_, s)| s.is_expired_at(now))
+            .map(|(k, _)| k.clone())
+            .collect();
+
+        let removed: Vec<Scroll> = expired
+            .iter()
+            .filter_map(|k| store.remove(k))
+            .map(|s| s.mark_deleted())
+            .collect();
+
+        drop(store);
+        for scroll in &removed {
+            self.notify_watchers(scroll);
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Stamp version, hash and timestamps onto a scroll replacing `prev`
+fn next_scroll(prev: Option<&Scroll>, scroll: Scroll) -> Scroll {
+    let prev_version = prev.map(|s| s.metadata.version).unwrap_or(0);
+
+    let mut new_scroll = scroll;
+    new_scroll.metadata.version = prev_version + 1;
+    new_scroll.metadata.hash = Some(new_scroll.compute_hash());
+    if new_scroll.metadata.created_at.is_none() {
+        new_scroll.metadata.created_at = Some(current_iso_time());
+    }
+    new_scroll.metadata.updated_at = Some(current_iso_time());
+    new_scroll
 }
 
 impl Default for MemoryNamespace {
@@ -227,7 +330,7 @@ impl Namespace for MemoryNamespace {
         validate_path(path)?;
 
         let store = self.inner.store.read().unwrap();
-        Ok(store.get(path).cloned())
+        Ok(store.get(path).filter(|s| !s.is_expired()).cloned())
     }
 
     fn write(&self, path: &str, data: Value) -> Result<Scroll> {
@@ -263,24 +366,17 @@ impl Namespace for MemoryNamespace {
         self.check_closed()?;
         validate_path(&scroll.key)?;
 
-        let mut store = self.inner.store.write().unwrap();
+        // Writing a tombstone deletes the path
+        if scroll.is_deleted() {
+            return Ok(self.delete(&scroll.key)?.unwrap_or(scroll));
+        }
 
-        // Get previous version
-        let prev_version = store
-            .get(&scroll.key)
-            .map(|s| s.metadata.version)
-            .unwrap_or(0);
+        let mut store = self.inner.store.write().unwrap();
 
         // Create new scroll preserving type and other metadata from input
-        let mut new_scroll = scroll.clone();
-        new_scroll.metadata.version = prev_version + 1;
-        new_scroll.metadata.hash = Some(new_scroll.compute_hash());
-        if new_scroll.metadata.created_at.is_none() {
-            new_scroll.metadata.created_at = Some(current_iso_time());
-        }
-        new_scroll.metadata.updated_at = Some(current_iso_time());
+        let new_scroll = next_scroll(store.get(&scroll.key), scroll);
 
-        store.insert(scroll.key.clone(), new_scroll.clone());
+        store.insert(new_scroll.key.clone(), new_scroll.clone());
 
         // Notify watchers
         drop(store);
@@ -295,9 +391,9 @@ impl Namespace for MemoryNamespace {
 
         let store = self.inner.store.read().unwrap();
         let paths: Vec<String> = store
-            .keys()
-            .filter(|k| is_path_under_prefix(k, prefix))
-            .cloned()
+            .iter()
+            .filter(|(k, s)| is_path_under_prefix(k, prefix) && !s.is_expired())
+            .map(|(k, _)| k.clone())
             .collect();
 
         Ok(paths)
@@ -531,4 +627,122 @@ mod tests {
         // Only the final watcher should remain
         // This verifies no memory/thread exhaustion
     }
+
+    #[test]
+    fn memory_write_if_version() {
+        let ns = MemoryNamespace::new();
+        ns.write("/doc", json!({"v": 1})).unwrap();
+
+        let updated = ns.write_if("/doc", json!({"v": 2}), &Precondition::Version(1)).unwrap();
+        assert_eq!(updated.metadata.version, 2);
+
+        // Stale version is rejected and nothing is written
+        let result = ns.write_if("/doc", json!({"v": 3}), &Precondition::Version(1));
+        assert!(matches!(result, Err(Error::Conflict(_))));
+        assert_eq!(ns.read("/doc").unwrap().unwrap().data["v"], 2);
+    }
+
+    #[test]
+    fn memory_write_if_hash_and_absent() {
+        let ns = MemoryNamespace::new();
+
+        let created = ns.write_if("/doc", json!({"v": 1}), &Precondition::Absent).unwrap();
+        assert!(ns.write_if("/doc", json!({"v": 1}), &Precondition::Absent).is_err());
+
+        let hash = created.metadata.hash.clone().unwrap();
+        ns.write_if("/doc", json!({"v": 2}), &Precondition::Hash(hash.clone())).unwrap();
+        assert!(matches!(
+            ns.write_if("/doc", json!({"v": 3}), &Precondition::Hash(hash)),
+            Err(Error::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn memory_write_if_concurrent_increments() {
+        let ns = MemoryNamespace::new();
+        ns.write("/counter", json!({"n": 0})).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let ns = ns.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        loop {
+                            let current = ns.read("/counter").unwrap().unwrap();
+                            let n = current.data["n"].as_u64().unwrap();
+                            let expected = Precondition::Version(current.metadata.version);
+                            if ns.write_if("/counter", json!({"n": n + 1}), &expected).is_ok() {
+                                break;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+
+        // No lost updates
+        assert_eq!(ns.read("/counter").unwrap().unwrap().data["n"], 200);
+    }
+
+    #[test]
+    fn memory_delete_notifies_watchers() {
+        let ns = MemoryNamespace::new();
+        ns.write("/contacts/alice", json!({"name": "Alice"})).unwrap();
+        ns.write("/contacts/bob", json!({"name": "Bob"})).unwrap();
+        let mut rx = ns.watch("/contacts/*").unwrap();
+
+        let removed = ns.delete("/contacts/alice").unwrap().unwrap();
+        assert!(removed.is_deleted());
+        assert!(ns.delete("/contacts/alice").unwrap().is_none());
+
+        // Writing a tombstone is the same as delete
+        ns.write_scroll(Scroll::new("/contacts/bob", Value::Null).mark_deleted())
+            .unwrap();
+
+        assert!(ns.read("/contacts/alice").unwrap().is_none());
+        assert!(ns.list("/contacts").unwrap().is_empty());
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.key, "/contacts/alice");
+        assert!(event.is_deleted());
+        assert_eq!(event.data["name"], "Alice");
+        assert_eq!(rx.try_recv().unwrap().key, "/contacts/bob");
+        assert!(rx.try_recv().is_none());
+    }
+
+    #[test]
+    fn memory_expired_scrolls_hidden_and_swept() {
+        let ns = MemoryNamespace::new();
+        let past = (current_time_millis() - 1).to_string();
+        let future = (current_time_millis() + 60_000).to_string();
+
+        ns.write_scroll(Scroll::new("/invoice/old", json!({})).with_expires_at(past))
+            .unwrap();
+        ns.write_scroll(Scroll::new("/invoice/new", json!({})).with_expires_at(future))
+            .unwrap();
+
+        assert!(ns.read("/invoice/old").unwrap().is_none());
+        assert!(ns.read("/invoice/new").unwrap().is_some());
+        assert_eq!(ns.list("/invoice").unwrap(), vec!["/invoice/new".to_string()]);
+
+        // Expired counts as absent for conditional writes
+        assert!(ns
+            .write_if("/invoice/old", json!({}), &Precondition::Absent)
+            .is_ok());
+        ns.write_scroll(Scroll::new("/invoice/old", json!({})).with_expires_at("0"))
+            .unwrap();
+
+        let mut rx = ns.watch("/invoice/*").unwrap();
+        let swept = ns.sweep_expired().unwrap();
+        assert_eq!(swept.len(), 1);
+        assert!(swept[0].is_deleted());
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.key, "/invoice/old");
+        assert!(event.is_deleted());
+        assert!(ns.sweep_expired().unwrap().is_empty());
+    }
 }
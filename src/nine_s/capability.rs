@@ -0,0 +1,668 @@
+//! Capability - Attenuated namespace handles
+//!
+//! A mini-app gets a `ScopedNamespace`, not the Kernel. The scope wraps any
+//! namespace (usually the Kernel) with a [`Policy`]: path globs × operations
+//! × optional data predicates and daily spend limits. Anything the policy
+//! does not grant fails with `Error::Permission`, and every refusal is kept
+//! in an audit trail.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Plugins share the Kernel (simple, all-powerful)
+//! **Antithesis**: Plugins get nothing (safe, useless)
+//! **Synthesis**: Plugins get exactly the paths, ops and amounts granted
+//!
+//! # Evaluation
+//!
+//! 1. A matching `deny` rule refuses, whatever else is granted
+//! 2. Otherwise some `allow` rule must cover the path and op (default deny)
+//! 3. Writes must then fit every daily limit covering the path
+//!
+//! A daily limit only sees the paths its globs cover. Money usually leaves
+//! by several paths (a wallet pays from `/send`, `/send/confirm` and
+//! `/lnurl/pay`), so a spending cap must name every one of them in a single
+//! [`Policy::limit_daily_across`]; one `limit_daily` per path would give
+//! each path a budget of its own.
+//!
+//! Paths are checked in canonical form (see [`canonical_path`]) and passed
+//! on in that form, so `//vault/seed` or `/wallet/send/` meet the same rules
+//! as `/vault/seed` and `/wallet/send`.
+//!
+//! Scopes nest: a `ScopedNamespace` is a `Namespace`, so it can be narrowed
+//! again but never widened.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use beewallet_core_spark::nine_s::capability::{Ops, Policy};
+//!
+//! let policy = Policy::new()
+//!     .allow("/wallet/balance", Ops::READ)
+//!     .allow("/wallet/send", Ops::WRITE)
+//!     .limit_daily_across(&wallet_spark::SPEND_PATHS, &["amount", "amount_sat"], 10_000)
+//!     .deny("/vault/**", Ops::ALL);
+//!
+//! let plugin_ns = kernel.scoped(policy);
+//! plugin_ns.read("/vault/seed");          // Err(Permission)
+//! plugin_ns.denials();                    // [Denial { op: Read, path: "/vault/seed", .. }]
+//! ```
+
+#[cfg(feature = "std-channel")]
+use super::channel::channel;
+use super::namespace::{canonical_path, path_matches, Error, Namespace, Receiver, Result};
+use super::scroll::{current_time_millis, Scroll};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Denials kept in memory per scope
+const MAX_DENIALS: usize = 256;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+// ============================================================================
+// Operations
+// ============================================================================
+
+/// A single namespace operation, as checked and audited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Op {
+    Read,
+    Write,
+    List,
+    Watch,
+}
+
+impl Op {
+    fn bit(self) -> u8 {
+        match self {
+            Op::Read => 1,
+            Op::Write => 2,
+            Op::List => 4,
+            Op::Watch => 8,
+        }
+    }
+}
+
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::Read => write!(f, "read"),
+            Op::Write => write!(f, "write"),
+            Op::List => write!(f, "list"),
+            Op::Watch => write!(f, "watch"),
+        }
+    }
+}
+
+/// A set of operations a rule applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ops(u8);
+
+impl Ops {
+    pub const NONE: Ops = Ops(0);
+    pub const READ: Ops = Ops(1);
+    pub const WRITE: Ops = Ops(2);
+    pub const LIST: Ops = Ops(4);
+    pub const WATCH: Ops = Ops(8);
+    /// Read, list and watch
+    pub const READ_ONLY: Ops = Ops(1 | 4 | 8);
+    pub const ALL: Ops = Ops(1 | 2 | 4 | 8);
+
+    /// Whether the set includes an operation
+    pub fn contains(self, op: Op) -> bool {
+        self.0 & op.bit() != 0
+    }
+}
+
+impl std::ops::BitOr for Ops {
+    type Output = Ops;
+
+    fn bitor(self, rhs: Ops) -> Ops {
+        Ops(self.0 | rhs.0)
+    }
+}
+
+// ============================================================================
+// Policy
+// ============================================================================
+
+/// Predicate over the data of a write
+pub type DataPredicate = Arc<dyn Fn(&Value) -> bool + Send + Sync>;
+
+enum Rule {
+    Allow {
+        glob: String,
+        ops: Ops,
+        when: Option<DataPredicate>,
+    },
+    Deny {
+        glob: String,
+        ops: Ops,
+    },
+    DailyLimit {
+        globs: Vec<String>,
+        fields: Vec<String>,
+        max: u64,
+    },
+}
+
+/// What a scope may do
+///
+/// Globs use the watch syntax (`/a/b`, `/a/*`, `/a/**`); `/a/**` also
+/// covers `/a` itself, so `deny("/vault/**", ..)` hides the mount point too.
+#[derive(Default)]
+pub struct Policy {
+    rules: Vec<Rule>,
+}
+
+impl Policy {
+    /// An empty policy: everything is denied
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grant `ops` on paths matching `glob`
+    pub fn allow(mut self, glob: impl Into<String>, ops: Ops) -> Self {
+        self.rules.push(Rule::Allow {
+            glob: glob.into(),
+            ops,
+            when: None,
+        });
+        self
+    }
+
+    /// Grant `ops` on `glob` for writes whose data satisfies `when`
+    ///
+    /// The predicate only constrains writes; for the other operations the
+    /// rule acts like a plain `allow`.
+    pub fn allow_if(
+        mut self,
+        glob: impl Into<String>,
+        ops: Ops,
+        when: impl Fn(&Value) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push(Rule::Allow {
+            glob: glob.into(),
+            ops,
+            when: Some(Arc::new(when)),
+        });
+        self
+    }
+
+    /// Refuse `ops` on `glob`, overriding any grant
+    pub fn deny(mut self, glob: impl Into<String>, ops: Ops) -> Self {
+        self.rules.push(Rule::Deny {
+            glob: glob.into(),
+            ops,
+        });
+        self
+    }
+
+    /// Cap the sum of an amount written to `glob` per UTC day
+    ///
+    /// The amount is the first of `fields` present in the write data as an
+    /// unsigned integer. Writes without one are refused, since the callee
+    /// might otherwise pick an amount of its own (e.g. from an invoice).
+    ///
+    /// Only writes to `glob` count; to cap spending that has more than one
+    /// path, use [`Policy::limit_daily_across`].
+    pub fn limit_daily(self, glob: impl Into<String>, fields: &[&str], max: u64) -> Self {
+        let glob = glob.into();
+        self.limit_daily_across(&[glob.as_str()], fields, max)
+    }
+
+    /// Cap the sum of an amount written to any of `globs` per UTC day
+    ///
+    /// All the globs draw on one budget, as [`Policy::limit_daily`] does
+    /// for one glob.
+    pub fn limit_daily_across(mut self, globs: &[&str], fields: &[&str], max: u64) -> Self {
+        self.rules.push(Rule::DailyLimit {
+            globs: globs.iter().map(|g| g.to_string()).collect(),
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+            max,
+        });
+        self
+    }
+
+    /// Whether the policy grants `op` on `path` (limits not considered)
+    pub fn permits(&self, op: Op, path: &str) -> bool {
+        self.decide(op, path, None).is_ok()
+    }
+
+    /// Deny rules first, then some allow rule must match
+    fn decide(&self, op: Op, path: &str, data: Option<&Value>) -> std::result::Result<(), String> {
+        for rule in &self.rules {
+            if let Rule::Deny { glob, ops } = rule {
+                if ops.contains(op) && covers(glob, path) {
+                    return Err(format!("denied by rule {}", glob));
+                }
+            }
+        }
+
+        let granted = self.rules.iter().any(|rule| match rule {
+            Rule::Allow { glob, ops, when } => {
+                ops.contains(op)
+                    && covers(glob, path)
+                    && match (when, data) {
+                        (Some(when), Some(data)) => when(data),
+                        _ => true,
+                    }
+            }
+            _ => false,
+        });
+
+        if granted {
+            Ok(())
+        } else {
+            Err("not granted".to_string())
+        }
+    }
+}
+
+/// Whether a glob covers a path (`/a/**` includes `/a`)
+fn covers(glob: &str, path: &str) -> bool {
+    path_matches(path, glob) || glob.strip_suffix("/**").is_some_and(|base| base == path)
+}
+
+// ============================================================================
+// Audit
+// ============================================================================
+
+/// A refused call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Denial {
+    pub op: Op,
+    pub path: String,
+    pub reason: String,
+    /// Unix milliseconds
+    pub timestamp: i64,
+}
+
+// ============================================================================
+// ScopedNamespace
+// ============================================================================
+
+/// A namespace handle restricted by a [`Policy`]
+///
+/// `close` retires the handle only; the wrapped namespace stays open for
+/// its other users.
+pub struct ScopedNamespace {
+    inner: Arc<dyn Namespace>,
+    policy: Arc<Policy>,
+    /// (UTC day, amount spent) per daily-limit rule index
+    spent: Mutex<HashMap<usize, (i64, u64)>>,
+    denials: Mutex<VecDeque<Denial>>,
+    /// Optional namespace receiving a scroll per denial
+    audit_sink: Option<(Arc<dyn Namespace>, String)>,
+    audit_seq: AtomicU64,
+    closed: AtomicBool,
+}
+
+impl ScopedNamespace {
+    /// Restrict `inner` to what `policy` grants
+    pub fn new(inner: Arc<dyn Namespace>, policy: Policy) -> Self {
+        Self {
+            inner,
+            policy: Arc::new(policy),
+            spent: Mutex::new(HashMap::new()),
+            denials: Mutex::new(VecDeque::new()),
+            audit_sink: None,
+            audit_seq: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Also write each denial as a `capability/denial@v1` scroll under `prefix`
+    ///
+    /// The sink is usually a Store outside the scope's reach, so the log
+    /// survives restarts and the plugin cannot erase it.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn Namespace>, prefix: impl Into<String>) -> Self {
+        self.audit_sink = Some((sink, prefix.into()));
+        self
+    }
+
+    /// The policy this scope enforces
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Most recent denials, oldest first
+    pub fn denials(&self) -> Vec<Denial> {
+        self.denials.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn check_closed(&self) -> Result<()> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(Error::Closed);
+        }
+        Ok(())
+    }
+
+    /// Check a call, recording a denial on refusal
+    ///
+    /// Returns the canonical path, which is what the inner namespace gets.
+    fn authorize(&self, op: Op, path: &str, data: Option<&Value>) -> Result<String> {
+        self.check_closed()?;
+        let path = canonical_path(path)?;
+        self.policy
+            .decide(op, &path, data)
+            .map_err(|reason| self.deny(op, &path, reason))?;
+        Ok(path)
+    }
+
+    fn deny(&self, op: Op, path: &str, reason: String) -> Error {
+        let denial = Denial {
+            op,
+            path: path.to_string(),
+            reason,
+            timestamp: current_time_millis(),
+        };
+        let error = Error::Permission(format!("{} {}: {}", op, path, denial.reason));
+
+        if let Some((sink, prefix)) = &self.audit_sink {
+            let seq = self.audit_seq.fetch_add(1, Ordering::Relaxed);
+            let key = format!("{}/{}-{}", prefix.trim_end_matches('/'), denial.timestamp, seq);
+            // Auditing must not turn a refusal into a different error
+            let _ = sink.write_scroll(Scroll::typed(
+                key,
+                json!(denial),
+                "capability/denial@v1",
+            ));
+        }
+
+        let mut denials = self.denials.lock().unwrap();
+        if denials.len() >= MAX_DENIALS {
+            denials.pop_front();
+        }
+        denials.push_back(denial);
+
+        error
+    }
+
+    /// Authorize a write, run it, and charge the daily limits it touches
+    fn guarded_write(
+        &self,
+        path: &str,
+        data: &Value,
+        write: impl FnOnce(String) -> Result<Scroll>,
+    ) -> Result<Scroll> {
+        let path = self.authorize(Op::Write, path, Some(data))?;
+
+        // Held across the write so concurrent writes cannot overspend
+        let mut spent = self.spent.lock().unwrap();
+        let today = current_time_millis().div_euclid(MILLIS_PER_DAY);
+        let mut charges = Vec::new();
+
+        for (index, rule) in self.policy.rules.iter().enumerate() {
+            let Rule::DailyLimit { globs, fields, max } = rule else {
+                continue;
+            };
+            if !globs.iter().any(|glob| covers(glob, &path)) {
+                continue;
+            }
+
+            let Some(amount) = fields.iter().find_map(|f| data[f.as_str()].as_u64()) else {
+                return Err(self.deny(
+                    Op::Write,
+                    &path,
+                    format!("daily limit needs one of {:?}", fields),
+                ));
+            };
+            let used = match spent.get(&index) {
+                Some((day, used)) if *day == today => *used,
+                _ => 0,
+            };
+            if used.saturating_add(amount) > *max {
+                return Err(self.deny(
+                    Op::Write,
+                    &path,
+                    format!("daily limit of {} exceeded ({} used)", max, used),
+                ));
+            }
+            charges.push((index, used + amount));
+        }
+
+        let scroll = write(path)?;
+        for (index, total) in charges {
+            spent.insert(index, (today, total));
+        }
+        Ok(scroll)
+    }
+}
+
+impl Namespace for ScopedNamespace {
+    fn read(&self, path: &str) -> Result<Option<Scroll>> {
+        let path = self.authorize(Op::Read, path, None)?;
+        self.inner.read(&path)
+    }
+
+    fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+        self.guarded_write(path, &data, |path| self.inner.write(&path, data.clone()))
+    }
+
+    fn write_scroll(&self, mut scroll: Scroll) -> Result<Scroll> {
+        let key = scroll.key.clone();
+        let data = scroll.data.clone();
+        self.guarded_write(&key, &data, |path| {
+            scroll.key = path;
+            self.inner.write_scroll(scroll)
+        })
+    }
+
+    /// List, hiding children the scope may neither read nor list
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = self.authorize(Op::List, prefix, None)?;
+        let paths = self.inner.list(&prefix)?;
+        Ok(paths
+            .into_iter()
+            .filter(|p| self.policy.permits(Op::Read, p) || self.policy.permits(Op::List, p))
+            .collect())
+    }
+
+    /// Watch, delivering only scrolls whose keys the scope may watch
+    #[cfg(feature = "std-channel")]
+    fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
+        let pattern = self.authorize(Op::Watch, pattern, None)?;
+        let mut rx = self.inner.watch(&pattern)?;
+        let (tx, output_rx) = channel(16);
+        let policy = self.policy.clone();
+
+        std::thread::spawn(move || {
+            while let Some(scroll) = rx.recv() {
+                if !policy.permits(Op::Watch, &scroll.key) {
+                    continue;
+                }
+                if tx.send(scroll).is_err() {
+                    break; // Receiver dropped
+                }
+            }
+        });
+
+        Ok(output_rx)
+    }
+
+    #[cfg(not(feature = "std-channel"))]
+    fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
+        let pattern = self.authorize(Op::Watch, pattern, None)?;
+        self.inner.watch(&pattern)
+    }
+
+    fn close(&self) -> Result<()> {
+        self.closed.store(true, Ordering::Release);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nine_s::{Kernel, MemoryNamespace};
+
+    fn kernel() -> Arc<Kernel> {
+        let kernel = Kernel::new();
+        kernel.mount_box("/wallet", MemoryNamespace::new());
+        kernel.mount_box("/vault", MemoryNamespace::new());
+        kernel.write("/wallet/balance", json!({"confirmed": 5000})).unwrap();
+        kernel.write("/wallet/address", json!("sp1qxyz")).unwrap();
+        kernel.write("/vault/seed", json!("abandon ...")).unwrap();
+        Arc::new(kernel)
+    }
+
+    #[test]
+    fn read_only_grant_refuses_writes_and_hidden_paths() {
+        let kernel = kernel();
+        let scope = kernel.scoped(
+            Policy::new()
+                .allow("/wallet/balance", Ops::READ)
+                .allow("/**", Ops::LIST)
+                .deny("/vault/**", Ops::ALL),
+        );
+
+        assert_eq!(scope.read("/wallet/balance").unwrap().unwrap().data["confirmed"], 5000);
+        assert!(matches!(scope.read("/wallet/address"), Err(Error::Permission(_))));
+        assert!(matches!(scope.write("/wallet/balance", json!(1)), Err(Error::Permission(_))));
+        assert!(matches!(scope.read("/vault/seed"), Err(Error::Permission(_))));
+        assert!(matches!(scope.list("/vault"), Err(Error::Permission(_))));
+
+        // Denied calls leave an audit trail; allowed ones don't
+        let denials = scope.denials();
+        assert_eq!(denials.len(), 4);
+        assert_eq!(denials[0].op, Op::Read);
+        assert_eq!(denials[0].path, "/wallet/address");
+        assert_eq!(denials[2].path, "/vault/seed");
+        assert!(denials[2].reason.contains("/vault/**"));
+    }
+
+    #[test]
+    fn daily_limit_caps_the_sum_of_writes() {
+        let kernel = kernel();
+        let scope = kernel.scoped(
+            Policy::new()
+                .allow("/wallet/send", Ops::WRITE)
+                .limit_daily("/wallet/send", &["amount", "amount_sat"], 1000),
+        );
+
+        scope.write("/wallet/send", json!({"to": "a", "amount": 600})).unwrap();
+        // The alternate field counts against the same limit
+        let over = scope.write("/wallet/send", json!({"to": "b", "amount_sat": 500}));
+        assert!(matches!(over, Err(Error::Permission(_))));
+        scope.write("/wallet/send", json!({"to": "c", "amount_sat": 400})).unwrap();
+
+        // No amount: the callee would choose one, so it is refused
+        let unbounded = scope.write("/wallet/send", json!({"to": "lnbc1..."}));
+        assert!(matches!(unbounded, Err(Error::Permission(_))));
+        assert_eq!(scope.denials().len(), 2);
+    }
+
+    #[test]
+    fn one_limit_spans_every_spending_path() {
+        let kernel = kernel();
+        let spend = ["/wallet/send", "/wallet/send/confirm", "/wallet/lnurl/pay"];
+        let scope = kernel.scoped(
+            Policy::new()
+                .allow("/wallet/**", Ops::WRITE)
+                .limit_daily_across(&spend, &["amount", "amount_sat"], 1000),
+        );
+
+        scope.write("/wallet/send", json!({"to": "a", "amount": 600})).unwrap();
+        let confirm = scope.write("/wallet/send/confirm", json!({"quote_id": "q", "amount": 500}));
+        assert!(matches!(confirm, Err(Error::Permission(_))));
+        scope.write("/wallet/lnurl/pay", json!({"to": "b@example.com", "amount": 400})).unwrap();
+        let confirm = scope.write("/wallet/send/confirm", json!({"quote_id": "q", "amount": 1}));
+        assert!(matches!(confirm, Err(Error::Permission(_))));
+
+        // Paths outside the limit don't draw on it
+        scope.write("/wallet/send/prepare", json!({"to": "c", "amount": 5000})).unwrap();
+    }
+
+    #[test]
+    fn non_canonical_paths_meet_the_same_rules() {
+        let kernel = kernel();
+        let scope = kernel.scoped(
+            Policy::new()
+                .allow("/**", Ops::READ | Ops::WRITE)
+                .deny("/vault/**", Ops::ALL)
+                .limit_daily("/wallet/send", &["amount"], 1000),
+        );
+
+        for path in ["//vault/seed", "/vault//seed", "/vault/seed/"] {
+            assert!(matches!(scope.read(path), Err(Error::Permission(_))), "{}", path);
+        }
+        assert_eq!(scope.denials()[0].path, "/vault/seed");
+
+        scope.write("/wallet/send", json!({"amount": 600})).unwrap();
+        for path in ["//wallet/send", "/wallet/send/", "/wallet//send"] {
+            let over = scope.write(path, json!({"amount": 600}));
+            assert!(matches!(over, Err(Error::Permission(_))), "{}", path);
+        }
+
+        // What passes reaches the inner namespace in canonical form
+        scope.write("/wallet//label/", json!("x")).unwrap();
+        assert!(kernel.read("/wallet/label").unwrap().is_some());
+    }
+
+    #[test]
+    fn data_predicates_constrain_writes() {
+        let kernel = kernel();
+        let scope = kernel.scoped(Policy::new().allow_if("/wallet/label/*", Ops::WRITE, |data| {
+            data["text"].as_str().is_some_and(|t| t.len() <= 8)
+        }));
+
+        scope.write("/wallet/label/tx1", json!({"text": "coffee"})).unwrap();
+        let long = scope.write("/wallet/label/tx2", json!({"text": "a very long label"}));
+        assert!(matches!(long, Err(Error::Permission(_))));
+    }
+
+    #[test]
+    fn list_hides_what_the_scope_cannot_see() {
+        let kernel = kernel();
+        let scope = kernel.scoped(
+            Policy::new()
+                .allow("/wallet", Ops::LIST)
+                .allow("/wallet/balance", Ops::READ),
+        );
+
+        assert_eq!(scope.list("/wallet").unwrap(), vec!["/wallet/balance".to_string()]);
+    }
+
+    #[test]
+    fn denials_reach_the_audit_sink_and_close_is_local() {
+        let kernel = kernel();
+        let sink = Arc::new(MemoryNamespace::new());
+        let scope = ScopedNamespace::new(kernel.clone(), Policy::new())
+            .with_audit_sink(sink.clone(), "/denied");
+
+        assert!(scope.read("/wallet/balance").is_err());
+        let logged = sink.list("/denied").unwrap();
+        assert_eq!(logged.len(), 1);
+        let entry = sink.read(&logged[0]).unwrap().unwrap();
+        assert_eq!(entry.type_, "capability/denial@v1");
+        assert_eq!(entry.data["op"], "read");
+
+        scope.close().unwrap();
+        assert!(matches!(scope.read("/wallet/balance"), Err(Error::Closed)));
+        assert!(kernel.read("/wallet/balance").unwrap().is_some());
+    }
+
+    #[cfg(feature = "std-channel")]
+    #[test]
+    fn watch_filters_keys_outside_the_grant() {
+        let kernel = kernel();
+        let scope = kernel.scoped(
+            Policy::new()
+                .allow("/wallet/**", Ops::WATCH)
+                .deny("/wallet/private/**", Ops::ALL),
+        );
+
+        let mut rx = scope.watch("/wallet/**").unwrap();
+        kernel.write("/wallet/private/note", json!("x")).unwrap();
+        kernel.write("/wallet/balance", json!({"confirmed": 1})).unwrap();
+
+        assert_eq!(rx.recv().unwrap().key, "/wallet/balance");
+        assert!(matches!(scope.watch("/vault/**"), Err(Error::Permission(_))));
+    }
+}
@@ -4,21 +4,28 @@
 //! This allows beewallet-core to work in any environment.
 
 use std::sync::mpsc as std_mpsc;
+use std::sync::{Arc, Weak};
 
 /// Bounded channel sender
 pub struct Sender<T> {
     inner: std_mpsc::SyncSender<T>,
+    receiver: Weak<()>,
 }
 
 /// Bounded channel receiver
 pub struct Receiver<T> {
     inner: std_mpsc::Receiver<T>,
+    _alive: Arc<()>,
 }
 
 /// Create a bounded channel with the given capacity
 pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
     let (tx, rx) = std_mpsc::sync_channel(capacity);
-    (Sender { inner: tx }, Receiver { inner: rx })
+    let alive = Arc::new(());
+    (
+        Sender { inner: tx, receiver: Arc::downgrade(&alive) },
+        Receiver { inner: rx, _alive: alive },
+    )
 }
 
 impl<T> Sender<T> {
@@ -35,12 +42,18 @@ impl<T> Sender<T> {
     pub fn send(&self, value: T) -> Result<(), T> {
         self.inner.send(value).map_err(|e| e.0)
     }
+
+    /// Whether the receiver has been dropped, without sending
+    pub fn is_closed(&self) -> bool {
+        self.receiver.strong_count() == 0
+    }
 }
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            receiver: self.receiver.clone(),
         }
     }
 }
@@ -88,4 +101,13 @@ mod tests {
         assert_eq!(rx.recv(), Some(1));
         assert_eq!(rx.recv(), None); // Disconnected
     }
+
+    #[test]
+    fn channel_sender_sees_dropped_receiver() {
+        let (tx, rx) = channel::<i32>(16);
+        assert!(!tx.is_closed());
+        drop(rx);
+        assert!(tx.is_closed());
+        assert!(tx.clone().is_closed());
+    }
 }
@@ -0,0 +1,549 @@
+//! Index - Secondary indexes and queries over scrolls
+//!
+//! Pure data structures: no storage, just computation. `Store` persists
+//! each index (sealed, like scrolls) spread over [`SHARDS`] files by path,
+//! so keeping it current on a write re-seals one shard, not the index.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: `list(prefix)` + N reads (simple, O(n) decrypts per query)
+//! **Antithesis**: A query engine (powerful, a second database)
+//! **Synthesis**: One sorted (value, path) list per declared field
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use beewallet_core_spark::nine_s::index::{IndexSpec, IndexField, Query};
+//!
+//! // Declare once; Store backfills and then maintains it on every write
+//! store.create_index(IndexSpec::new("tx_by_amount", "/wallet/tx", IndexField::pointer("/amount")))?;
+//!
+//! // Largest 20 payments over 10k sats
+//! let page = store.query(&Query::on("tx_by_amount").gte(json!(10_000)).descending().limit(20))?;
+//! for scroll in &page.scrolls { /* ... */ }
+//!
+//! // Next page
+//! let more = store.query(&Query::on("tx_by_amount").gte(json!(10_000)).descending().limit(20)
+//!     .after(page.next_cursor.unwrap()))?;
+//! ```
+
+use super::namespace::{Error, Result};
+use super::scroll::Scroll;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// Number of shards an index's entries are spread over on disk
+pub const SHARDS: u64 = 64;
+
+/// Shard holding a path's entry
+///
+/// FNV-1a, so the assignment is stable across builds and platforms.
+pub fn shard_of(path: &str) -> u64 {
+    let hash = path.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    hash % SHARDS
+}
+
+/// The scroll field an index is keyed on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "pointer", rename_all = "lowercase")]
+pub enum IndexField {
+    /// `scroll.type_`
+    Type,
+    /// `metadata.kingdom`
+    Kingdom,
+    /// `metadata.class`
+    Class,
+    /// JSON pointer into `data` (RFC 6901)
+    Pointer(String),
+}
+
+impl IndexField {
+    /// Index a JSON pointer into `data`
+    pub fn pointer(pointer: impl Into<String>) -> Self {
+        IndexField::Pointer(pointer.into())
+    }
+
+    /// Extract the indexed value from a scroll (None if absent)
+    pub fn extract(&self, scroll: &Scroll) -> Option<Value> {
+        match self {
+            IndexField::Type => Some(Value::String(scroll.type_.clone())),
+            IndexField::Kingdom => scroll.metadata.kingdom.clone().map(Value::String),
+            IndexField::Class => scroll.metadata.class.clone().map(Value::String),
+            IndexField::Pointer(p) => scroll.data.pointer(p).cloned(),
+        }
+    }
+}
+
+/// Declaration of a secondary index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexSpec {
+    /// Unique name (alphanumeric, hyphens, underscores)
+    pub name: String,
+    /// Only scrolls under this prefix are indexed
+    pub prefix: String,
+    /// Field the index is keyed on
+    pub field: IndexField,
+}
+
+impl IndexSpec {
+    /// Create an index spec
+    pub fn new(name: impl Into<String>, prefix: impl Into<String>, field: IndexField) -> Self {
+        Self {
+            name: name.into(),
+            prefix: prefix.into(),
+            field,
+        }
+    }
+
+    /// Check that the name is safe to use as a file name
+    pub fn validate(&self) -> Result<()> {
+        let valid = !self.name.is_empty()
+            && self.name.len() <= 64
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(Error::InvalidPath(format!(
+                "invalid index name: {}",
+                self.name
+            )));
+        }
+        super::namespace::validate_path(&self.prefix)
+    }
+
+    /// Check if a scroll path falls under this index
+    pub fn covers(&self, path: &str) -> bool {
+        self.prefix == "/"
+            || path == self.prefix
+            || path
+                .strip_prefix(self.prefix.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// One indexed scroll
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// Indexed value
+    pub value: Value,
+    /// Scroll path
+    pub path: String,
+}
+
+impl IndexEntry {
+    fn cmp(&self, other: &IndexEntry) -> Ordering {
+        compare_values(&self.value, &other.value).then_with(|| self.path.cmp(&other.path))
+    }
+}
+
+/// A secondary index: entries sorted by (value, path)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    /// Declaration
+    pub spec: IndexSpec,
+    /// Sorted entries
+    pub entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// Create an empty index
+    pub fn new(spec: IndexSpec) -> Self {
+        Self {
+            spec,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Assemble an index from entries in any order (e.g. shard by shard)
+    pub fn from_entries(spec: IndexSpec, mut entries: Vec<IndexEntry>) -> Self {
+        entries.sort_by(|a, b| a.cmp(b));
+        Self { spec, entries }
+    }
+
+    /// Entries grouped by [`shard_of`] their path, each group still sorted
+    pub fn shards(&self) -> BTreeMap<u64, Vec<IndexEntry>> {
+        let mut shards: BTreeMap<u64, Vec<IndexEntry>> = BTreeMap::new();
+        for entry in &self.entries {
+            shards.entry(shard_of(&entry.path)).or_default().push(entry.clone());
+        }
+        shards
+    }
+
+    /// Reflect a scroll's current state (idempotent)
+    ///
+    /// Removes any previous entry for the path, then inserts the new value
+    /// unless the scroll is out of scope, deleted, or lacks the field.
+    pub fn update(&mut self, scroll: &Scroll) {
+        self.remove(&scroll.key);
+
+        if !self.spec.covers(&scroll.key) || scroll.is_deleted() {
+            return;
+        }
+
+        if let Some(value) = self.spec.field.extract(scroll) {
+            let entry = IndexEntry {
+                value,
+                path: scroll.key.clone(),
+            };
+            let at = self
+                .entries
+                .binary_search_by(|e| e.cmp(&entry))
+                .unwrap_or_else(|i| i);
+            self.entries.insert(at, entry);
+        }
+    }
+
+    /// Drop the entry for a path
+    pub fn remove(&mut self, path: &str) {
+        self.entries.retain(|e| e.path != path);
+    }
+
+    /// Run a query, returning matching paths and the cursor for the next page
+    pub fn search(&self, query: &Query) -> Result<(Vec<String>, Option<String>)> {
+        let after = query.cursor.as_deref().map(decode_cursor).transpose()?;
+
+        let matching: Box<dyn Iterator<Item = &IndexEntry>> = match query.order {
+            Order::Ascending => Box::new(self.entries.iter()),
+            Order::Descending => Box::new(self.entries.iter().rev()),
+        };
+
+        let mut matching = matching
+            .filter(|e| query.matches(&e.value))
+            .filter(|e| match (&after, query.order) {
+                (None, _) => true,
+                (Some(c), Order::Ascending) => e.cmp(c) == Ordering::Greater,
+                (Some(c), Order::Descending) => e.cmp(c) == Ordering::Less,
+            })
+            .peekable();
+
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut paths = Vec::new();
+        let mut last = None;
+
+        while paths.len() < limit {
+            match matching.next() {
+                Some(entry) => {
+                    paths.push(entry.path.clone());
+                    last = Some(entry);
+                }
+                None => break,
+            }
+        }
+
+        let next_cursor = match (last, matching.peek()) {
+            (Some(entry), Some(_)) => Some(encode_cursor(entry)),
+            _ => None,
+        };
+
+        Ok((paths, next_cursor))
+    }
+}
+
+/// Result ordering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    /// Smallest value first
+    #[default]
+    Ascending,
+    /// Largest value first
+    Descending,
+}
+
+/// A query against one index
+///
+/// Built fluently; conditions combine with AND.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    /// Index to search
+    pub index: String,
+    /// Lower bound on the indexed value
+    pub lower: Bound<Value>,
+    /// Upper bound on the indexed value
+    pub upper: Bound<Value>,
+    /// Result ordering
+    pub order: Order,
+    /// Maximum number of results
+    pub limit: Option<usize>,
+    /// Opaque cursor from a previous page
+    pub cursor: Option<String>,
+}
+
+impl Query {
+    /// Query an index by name (matches everything until narrowed)
+    pub fn on(index: impl Into<String>) -> Self {
+        Self {
+            index: index.into(),
+            lower: Bound::Unbounded,
+            upper: Bound::Unbounded,
+            order: Order::Ascending,
+            limit: None,
+            cursor: None,
+        }
+    }
+
+    /// Value equals `value`
+    pub fn eq(self, value: Value) -> Self {
+        self.gte(value.clone()).lte(value)
+    }
+
+    /// Value > `value`
+    pub fn gt(mut self, value: Value) -> Self {
+        self.lower = Bound::Excluded(value);
+        self
+    }
+
+    /// Value >= `value`
+    pub fn gte(mut self, value: Value) -> Self {
+        self.lower = Bound::Included(value);
+        self
+    }
+
+    /// Value < `value`
+    pub fn lt(mut self, value: Value) -> Self {
+        self.upper = Bound::Excluded(value);
+        self
+    }
+
+    /// Value <= `value`
+    pub fn lte(mut self, value: Value) -> Self {
+        self.upper = Bound::Included(value);
+        self
+    }
+
+    /// Largest values first
+    pub fn descending(mut self) -> Self {
+        self.order = Order::Descending;
+        self
+    }
+
+    /// Return at most `limit` results
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Continue after a previous page's `next_cursor`
+    pub fn after(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Check if a value satisfies both bounds
+    pub fn matches(&self, value: &Value) -> bool {
+        let above = match &self.lower {
+            Bound::Unbounded => true,
+            Bound::Included(v) => compare_values(value, v) != Ordering::Less,
+            Bound::Excluded(v) => compare_values(value, v) == Ordering::Greater,
+        };
+        let below = match &self.upper {
+            Bound::Unbounded => true,
+            Bound::Included(v) => compare_values(value, v) != Ordering::Greater,
+            Bound::Excluded(v) => compare_values(value, v) == Ordering::Less,
+        };
+        above && below
+    }
+}
+
+/// One page of query results
+#[derive(Debug, Clone)]
+pub struct QueryPage {
+    /// Matching scrolls, in query order
+    pub scrolls: Vec<Scroll>,
+    /// Pass to `Query::after` for the next page (None when exhausted)
+    pub next_cursor: Option<String>,
+}
+
+/// Total order over JSON values
+///
+/// null < bool < number < string < array < object. Numbers compare
+/// numerically; arrays and objects by their serialized form.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    fn rank(v: &Value) -> u8 {
+        match v {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+
+    match (a, b) {
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Number(x), Value::Number(y)) => match (x.as_i64(), y.as_i64()) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            _ => {
+                let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+                x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+            }
+        },
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(_), Value::Array(_)) | (Value::Object(_), Value::Object(_)) => {
+            a.to_string().cmp(&b.to_string())
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+// ============================================================================
+// Internal: cursors
+// ============================================================================
+
+/// Cursor = hex(JSON of the last returned entry)
+fn encode_cursor(entry: &IndexEntry) -> String {
+    hex::encode(serde_json::to_vec(entry).unwrap_or_default())
+}
+
+fn decode_cursor(cursor: &str) -> Result<IndexEntry> {
+    hex::decode(cursor)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| Error::InvalidData(format!("invalid query cursor: {}", cursor)))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tx(path: &str, amount: i64) -> Scroll {
+        Scroll::new(path, json!({"amount": amount})).set_type("wallet/payment@v1")
+    }
+
+    fn amount_index() -> Index {
+        let mut index = Index::new(IndexSpec::new(
+            "by_amount",
+            "/tx",
+            IndexField::pointer("/amount"),
+        ));
+        for (i, amount) in [500, 100, 300, 200, 400].iter().enumerate() {
+            index.update(&tx(&format!("/tx/{}", i), *amount));
+        }
+        index
+    }
+
+    #[test]
+    fn index_update_keeps_entries_sorted() {
+        let mut index = amount_index();
+        let values: Vec<_> = index.entries.iter().map(|e| e.value.clone()).collect();
+        assert_eq!(
+            values,
+            vec![json!(100), json!(200), json!(300), json!(400), json!(500)]
+        );
+
+        // Rewriting a path moves its entry
+        index.update(&tx("/tx/1", 600));
+        assert_eq!(index.entries.len(), 5);
+        assert_eq!(index.entries.last().unwrap().path, "/tx/1");
+
+        // Out of scope, missing field or deleted: not indexed
+        index.update(&tx("/other/x", 1));
+        index.update(&Scroll::new("/tx/9", json!({"memo": "no amount"})));
+        index.update(&tx("/tx/0", 1).mark_deleted());
+        assert_eq!(index.entries.len(), 4);
+    }
+
+    #[test]
+    fn query_equality_and_range() {
+        let index = amount_index();
+
+        let (paths, _) = index
+            .search(&Query::on("by_amount").eq(json!(300)))
+            .unwrap();
+        assert_eq!(paths, vec!["/tx/2"]);
+
+        let (paths, _) = index
+            .search(&Query::on("by_amount").gt(json!(100)).lte(json!(400)))
+            .unwrap();
+        assert_eq!(paths, vec!["/tx/3", "/tx/2", "/tx/4"]);
+
+        let (paths, _) = index
+            .search(&Query::on("by_amount").gte(json!(300)).descending())
+            .unwrap();
+        assert_eq!(paths, vec!["/tx/0", "/tx/4", "/tx/2"]);
+    }
+
+    #[test]
+    fn query_paginates_with_cursor() {
+        let index = amount_index();
+        let query = Query::on("by_amount").descending().limit(2);
+
+        let (first, cursor) = index.search(&query).unwrap();
+        assert_eq!(first, vec!["/tx/0", "/tx/4"]);
+
+        let (second, cursor) = index.search(&query.clone().after(cursor.unwrap())).unwrap();
+        assert_eq!(second, vec!["/tx/2", "/tx/3"]);
+
+        let (third, cursor) = index.search(&query.clone().after(cursor.unwrap())).unwrap();
+        assert_eq!(third, vec!["/tx/1"]);
+        assert!(cursor.is_none());
+
+        assert!(index.search(&query.after("not-a-cursor")).is_err());
+    }
+
+    #[test]
+    fn shards_reassemble_into_the_same_index() {
+        let index = amount_index();
+        let shards = index.shards();
+        assert!(shards.keys().all(|shard| *shard < SHARDS));
+        assert!(shards.iter().all(|(shard, entries)| entries.iter().all(|e| shard_of(&e.path) == *shard)));
+
+        let entries = shards.into_values().rev().flatten().collect();
+        assert_eq!(Index::from_entries(index.spec.clone(), entries), index);
+        assert_eq!(shard_of("/tx/1"), shard_of("/tx/1"));
+    }
+
+    #[test]
+    fn index_field_extracts_metadata() {
+        let scroll = Scroll::new("/a", json!({"nested": {"k": "v"}}))
+            .set_type("t@v1")
+            .with_kingdom("wallet")
+            .with_class("payment");
+
+        assert_eq!(IndexField::Type.extract(&scroll), Some(json!("t@v1")));
+        assert_eq!(IndexField::Kingdom.extract(&scroll), Some(json!("wallet")));
+        assert_eq!(IndexField::Class.extract(&scroll), Some(json!("payment")));
+        assert_eq!(
+            IndexField::pointer("/nested/k").extract(&scroll),
+            Some(json!("v"))
+        );
+        assert_eq!(IndexField::pointer("/missing").extract(&scroll), None);
+    }
+
+    #[test]
+    fn compare_values_orders_across_types() {
+        assert_eq!(compare_values(&json!(2), &json!(10)), Ordering::Less);
+        assert_eq!(compare_values(&json!(1.5), &json!(1)), Ordering::Greater);
+        assert_eq!(compare_values(&json!("b"), &json!("a")), Ordering::Greater);
+        assert_eq!(compare_values(&json!(null), &json!(false)), Ordering::Less);
+        assert_eq!(compare_values(&json!(99), &json!("1")), Ordering::Less);
+    }
+
+    #[test]
+    fn index_spec_validation() {
+        assert!(IndexSpec::new("by_type", "/wallet", IndexField::Type)
+            .validate()
+            .is_ok());
+        assert!(IndexSpec::new("../escape", "/wallet", IndexField::Type)
+            .validate()
+            .is_err());
+        assert!(IndexSpec::new("", "/wallet", IndexField::Type)
+            .validate()
+            .is_err());
+
+        let spec = IndexSpec::new("x", "/wallet", IndexField::Type);
+        assert!(spec.covers("/wallet/tx/1"));
+        assert!(!spec.covers("/wallet_archive/tx"));
+    }
+}
@@ -0,0 +1,587 @@
+//! Intercept - Middleware around the five operations
+//!
+//! Logging, metrics, rate limiting and validation keep getting rebuilt
+//! inside individual namespaces. An `InterceptedNamespace` wraps any
+//! namespace (a backend, a mount, or the whole Kernel) with an ordered
+//! chain of [`Interceptor`]s instead.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Each backend handles its own cross-cutting concerns
+//! **Antithesis**: One god-wrapper doing everything
+//! **Synthesis**: Small hooks, composed in order, around an untouched namespace
+//!
+//! # The Chain
+//!
+//! ```text
+//! call ─► before(1) ─► before(2) ─► namespace ─► after(2) ─► after(1) ─► caller
+//! ```
+//!
+//! - `before` may rewrite the call (path, written scroll) or reject it
+//! - `after` sees the result, success or failure, and may rewrite it
+//! - `on_event` filters or rewrites scrolls delivered by `watch`
+//!
+//! When a `before` rejects, the interceptors whose `before` already ran
+//! still see the error in `after`, so timing and logging count it.
+//!
+//! Calls enter the chain with the path in canonical form (see
+//! [`canonical_path`]), so `/api//a` meets the same globs as `/api/a`.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use beewallet_core_spark::nine_s::intercept::{InterceptedNamespace, RateLimit, Timing, Validate};
+//! use std::time::Duration;
+//!
+//! let timing = Arc::new(Timing::new());
+//! let ns = InterceptedNamespace::new(Arc::new(kernel))
+//!     .with(timing.clone())
+//!     .with(Arc::new(RateLimit::new("/wallet/send", 5, Duration::from_secs(60))))
+//!     .with(Arc::new(Validate::required("/wallet/send", &["to", "amount"])));
+//! ```
+
+use super::capability::{Op, Ops};
+#[cfg(feature = "std-channel")]
+use super::channel::channel;
+use super::namespace::{canonical_path, path_matches, Error, Namespace, Receiver, Result};
+use super::schema::SchemaRegistry;
+use super::scroll::Scroll;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Interceptor
+// ============================================================================
+
+/// A call passing through the chain
+#[derive(Debug, Clone)]
+pub struct Call {
+    /// The operation (`write_scroll` is a `Write`)
+    pub op: Op,
+    /// Path, prefix or pattern the call addresses
+    pub path: String,
+    /// Scroll being written (writes only)
+    pub scroll: Option<Scroll>,
+    /// Whether the caller passed a full scroll (`write_scroll`)
+    full_scroll: bool,
+    started: Instant,
+}
+
+impl Call {
+    /// A call on the canonical form of `path`
+    fn new(op: Op, path: &str, scroll: Option<Scroll>, full_scroll: bool) -> Result<Self> {
+        Ok(Self {
+            op,
+            path: canonical_path(path)?,
+            scroll,
+            full_scroll,
+            started: Instant::now(),
+        })
+    }
+
+    /// Time since the call entered the chain
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+/// What a call produced
+#[derive(Debug, Clone)]
+pub enum Outcome {
+    Read(Option<Scroll>),
+    Write(Scroll),
+    List(Vec<String>),
+    /// The watch was established; scrolls go through `on_event`
+    Watch,
+}
+
+/// A hook around namespace operations
+///
+/// Every method has a pass-through default, so an interceptor implements
+/// only what it cares about. Interceptors are shared between threads and
+/// keep per-call state in the [`Call`], not in `self`.
+pub trait Interceptor: Send + Sync {
+    /// Inspect or rewrite a call before it runs; an error rejects it
+    fn before(&self, _call: &mut Call) -> Result<()> {
+        Ok(())
+    }
+
+    /// Inspect or rewrite the result of a call
+    fn after(&self, _call: &Call, _result: &mut Result<Outcome>) {}
+
+    /// Inspect, rewrite or drop (`None`) a scroll delivered by a watch
+    fn on_event(&self, _call: &Call, scroll: Scroll) -> Option<Scroll> {
+        Some(scroll)
+    }
+}
+
+// ============================================================================
+// InterceptedNamespace
+// ============================================================================
+
+/// A namespace wrapped in an interceptor chain
+///
+/// `close` is passed straight through.
+pub struct InterceptedNamespace {
+    inner: Arc<dyn Namespace>,
+    chain: Vec<Arc<dyn Interceptor>>,
+}
+
+impl InterceptedNamespace {
+    /// Wrap a namespace with an empty chain
+    pub fn new(inner: Arc<dyn Namespace>) -> Self {
+        Self {
+            inner,
+            chain: Vec::new(),
+        }
+    }
+
+    /// Append an interceptor (runs after those already added)
+    pub fn with(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.chain.push(interceptor);
+        self
+    }
+
+    /// Run a call through the chain
+    fn run(&self, mut call: Call, op: impl FnOnce(&Call) -> Result<Outcome>) -> (Call, Result<Outcome>) {
+        let mut entered = 0;
+        let mut result = None;
+
+        for interceptor in &self.chain {
+            if let Err(e) = interceptor.before(&mut call) {
+                result = Some(Err(e));
+                break;
+            }
+            entered += 1;
+        }
+
+        let mut result = result.unwrap_or_else(|| op(&call));
+
+        for interceptor in self.chain[..entered].iter().rev() {
+            interceptor.after(&call, &mut result);
+        }
+
+        (call, result)
+    }
+
+    fn write_call(&self, call: Call) -> Result<Scroll> {
+        let (_, result) = self.run(call, |call| {
+            let mut scroll = call.scroll.clone().unwrap_or_else(|| Scroll::new(&call.path, Value::Null));
+            let written = if call.full_scroll {
+                scroll.key = call.path.clone();
+                self.inner.write_scroll(scroll)?
+            } else {
+                self.inner.write(&call.path, scroll.data)?
+            };
+            Ok(Outcome::Write(written))
+        });
+
+        match result? {
+            Outcome::Write(scroll) => Ok(scroll),
+            other => Err(mismatch(Op::Write, &other)),
+        }
+    }
+}
+
+/// An `after` hook replaced the outcome with one of another operation
+fn mismatch(op: Op, outcome: &Outcome) -> Error {
+    Error::Internal(format!("interceptor returned {:?} for {}", outcome, op))
+}
+
+impl Namespace for InterceptedNamespace {
+    fn read(&self, path: &str) -> Result<Option<Scroll>> {
+        let (_, result) = self.run(Call::new(Op::Read, path, None, false)?, |call| {
+            Ok(Outcome::Read(self.inner.read(&call.path)?))
+        });
+
+        match result? {
+            Outcome::Read(scroll) => Ok(scroll),
+            other => Err(mismatch(Op::Read, &other)),
+        }
+    }
+
+    fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+        self.write_call(Call::new(Op::Write, path, Some(Scroll::new(path, data)), false)?)
+    }
+
+    fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
+        let path = scroll.key.clone();
+        self.write_call(Call::new(Op::Write, &path, Some(scroll), true)?)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let (_, result) = self.run(Call::new(Op::List, prefix, None, false)?, |call| {
+            Ok(Outcome::List(self.inner.list(&call.path)?))
+        });
+
+        match result? {
+            Outcome::List(paths) => Ok(paths),
+            other => Err(mismatch(Op::List, &other)),
+        }
+    }
+
+    #[cfg(feature = "std-channel")]
+    fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
+        let mut inner_rx = None;
+        let (call, result) = self.run(Call::new(Op::Watch, pattern, None, false)?, |call| {
+            inner_rx = Some(self.inner.watch(&call.path)?);
+            Ok(Outcome::Watch)
+        });
+
+        match result? {
+            Outcome::Watch => {}
+            other => return Err(mismatch(Op::Watch, &other)),
+        }
+        let mut rx = inner_rx
+            .ok_or_else(|| Error::Internal("interceptor faked a watch".to_string()))?;
+
+        let (tx, output_rx) = channel(16);
+        let chain = self.chain.clone();
+        std::thread::spawn(move || {
+            while let Some(scroll) = rx.recv() {
+                let delivered = chain
+                    .iter()
+                    .try_fold(scroll, |scroll, interceptor| interceptor.on_event(&call, scroll));
+                if let Some(scroll) = delivered {
+                    if tx.send(scroll).is_err() {
+                        break; // Receiver dropped
+                    }
+                }
+            }
+        });
+
+        Ok(output_rx)
+    }
+
+    #[cfg(not(feature = "std-channel"))]
+    fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
+        self.inner.watch(pattern)
+    }
+
+    fn close(&self) -> Result<()> {
+        self.inner.close()
+    }
+}
+
+// ============================================================================
+// Built-in: Timing
+// ============================================================================
+
+/// Call counts and latencies for one operation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+/// Records how many calls each operation gets and how long they take
+///
+/// Add it first so its figures include time spent in later interceptors.
+#[derive(Default)]
+pub struct Timing {
+    stats: Mutex<HashMap<Op, OpStats>>,
+}
+
+impl Timing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Figures for one operation
+    pub fn stats(&self, op: Op) -> OpStats {
+        self.stats.lock().unwrap().get(&op).copied().unwrap_or_default()
+    }
+}
+
+impl Interceptor for Timing {
+    fn after(&self, call: &Call, result: &mut Result<Outcome>) {
+        let elapsed = call.elapsed();
+        let mut stats = self.stats.lock().unwrap();
+        let entry = stats.entry(call.op).or_default();
+        entry.calls += 1;
+        if result.is_err() {
+            entry.errors += 1;
+        }
+        entry.total += elapsed;
+        entry.max = entry.max.max(elapsed);
+    }
+}
+
+// ============================================================================
+// Built-in: RateLimit
+// ============================================================================
+
+/// Window entries kept before expired ones are swept
+const RATE_LIMIT_SWEEP: usize = 1024;
+
+/// Caps calls per path within a time window
+///
+/// Each path matching the glob gets its own budget of `max_calls` per
+/// `window`; calls over budget fail with `Error::Unavailable` until the
+/// window rolls over. Writes only, unless widened with [`RateLimit::ops`].
+pub struct RateLimit {
+    glob: String,
+    ops: Ops,
+    max_calls: u32,
+    window: Duration,
+    /// path → (window start, calls in window)
+    windows: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimit {
+    pub fn new(glob: impl Into<String>, max_calls: u32, window: Duration) -> Self {
+        Self {
+            glob: glob.into(),
+            ops: Ops::WRITE,
+            max_calls,
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Operations the limit applies to
+    pub fn ops(mut self, ops: Ops) -> Self {
+        self.ops = ops;
+        self
+    }
+}
+
+impl Interceptor for RateLimit {
+    fn before(&self, call: &mut Call) -> Result<()> {
+        if !self.ops.contains(call.op) || !path_matches(&call.path, &self.glob) {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= RATE_LIMIT_SWEEP {
+            windows.retain(|_, (start, _)| now.duration_since(*start) < self.window);
+        }
+
+        let (start, calls) = windows.entry(call.path.clone()).or_insert((now, 0));
+        if now.duration_since(*start) >= self.window {
+            *start = now;
+            *calls = 0;
+        }
+        if *calls >= self.max_calls {
+            return Err(Error::Unavailable(format!(
+                "rate limit of {} per {:?} exceeded for {}",
+                self.max_calls, self.window, call.path
+            )));
+        }
+        *calls += 1;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Built-in: Validate
+// ============================================================================
+
+/// Check applied to scrolls before they are written
+pub type ScrollCheck = Arc<dyn Fn(&Scroll) -> std::result::Result<(), String> + Send + Sync>;
+
+/// Rejects writes whose scroll fails a check
+///
+/// Failures surface as `Error::InvalidData` and nothing is written.
+pub struct Validate {
+    glob: String,
+    check: ScrollCheck,
+}
+
+impl Validate {
+    /// Validate writes under `glob` with `check`
+    pub fn new(
+        glob: impl Into<String>,
+        check: impl Fn(&Scroll) -> std::result::Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            glob: glob.into(),
+            check: Arc::new(check),
+        }
+    }
+
+    /// Require writes under `glob` to be objects carrying `fields`
+    pub fn required(glob: impl Into<String>, fields: &[&str]) -> Self {
+        let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+        Self::new(glob, move |scroll| {
+            let object = scroll.data.as_object().ok_or("data must be an object")?;
+            match fields.iter().find(|f| !object.contains_key(f.as_str())) {
+                Some(missing) => Err(format!("missing field '{}'", missing)),
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Check writes under `glob` against the schema of their `type_`
+    pub fn schemas(glob: impl Into<String>, registry: Arc<SchemaRegistry>) -> Self {
+        Self::new(glob, move |scroll| {
+            registry.validate(scroll).map_err(|e| match e {
+                Error::InvalidData(reason) => reason,
+                other => other.to_string(),
+            })
+        })
+    }
+}
+
+impl Interceptor for Validate {
+    fn before(&self, call: &mut Call) -> Result<()> {
+        if call.op != Op::Write || !path_matches(&call.path, &self.glob) {
+            return Ok(());
+        }
+        let Some(scroll) = &call.scroll else {
+            return Ok(());
+        };
+        // Deletes carry no payload to validate
+        if scroll.is_deleted() {
+            return Ok(());
+        }
+
+        (self.check)(scroll).map_err(|reason| {
+            Error::InvalidData(format!("{}: {}", call.path, reason))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nine_s::MemoryNamespace;
+    use serde_json::json;
+
+    /// Rewrites paths into `/sandbox` and tags written scrolls
+    struct Sandbox;
+
+    impl Interceptor for Sandbox {
+        fn before(&self, call: &mut Call) -> Result<()> {
+            call.path = format!("/sandbox{}", call.path);
+            Ok(())
+        }
+
+        fn after(&self, _call: &Call, result: &mut Result<Outcome>) {
+            if let Ok(Outcome::Write(scroll)) = result {
+                scroll.key = scroll.key.trim_start_matches("/sandbox").to_string();
+            }
+        }
+    }
+
+    #[test]
+    fn interceptors_rewrite_calls_and_results() {
+        let memory = Arc::new(MemoryNamespace::new());
+        let ns = InterceptedNamespace::new(memory.clone()).with(Arc::new(Sandbox));
+
+        let written = ns.write("/note", json!("hi")).unwrap();
+        assert_eq!(written.key, "/note");
+        assert_eq!(memory.read("/sandbox/note").unwrap().unwrap().data, "hi");
+        assert_eq!(ns.read("/note").unwrap().unwrap().data, "hi");
+        assert!(memory.read("/note").unwrap().is_none());
+    }
+
+    #[test]
+    fn rejected_calls_still_reach_earlier_interceptors() {
+        let timing = Arc::new(Timing::new());
+        let ns = InterceptedNamespace::new(Arc::new(MemoryNamespace::new()))
+            .with(timing.clone())
+            .with(Arc::new(Validate::required("/wallet/send", &["to", "amount"])));
+
+        ns.write("/wallet/send", json!({"to": "a", "amount": 1})).unwrap();
+        let rejected = ns.write("/wallet/send", json!({"to": "b"}));
+        assert!(matches!(rejected, Err(Error::InvalidData(ref m)) if m.contains("amount")));
+        ns.read("/wallet/send").unwrap();
+
+        let writes = timing.stats(Op::Write);
+        assert_eq!(writes.calls, 2);
+        assert_eq!(writes.errors, 1);
+        assert!(writes.max <= writes.total);
+        assert_eq!(timing.stats(Op::Read).calls, 1);
+        assert_eq!(timing.stats(Op::List).calls, 0);
+    }
+
+    #[test]
+    fn validate_schemas_checks_declared_types() {
+        use crate::nine_s::schema::{Kind, Schema};
+
+        let mut registry = SchemaRegistry::new();
+        registry
+            .register(Schema::new("wallet/balance@v1").required("confirmed", Kind::U64))
+            .unwrap();
+        let ns = InterceptedNamespace::new(Arc::new(MemoryNamespace::new()))
+            .with(Arc::new(Validate::schemas("/wallet/**", Arc::new(registry))));
+
+        let good = Scroll::typed("/wallet/balance", json!({"confirmed": 5}), "wallet/balance@v1");
+        let bad = Scroll::typed("/wallet/balance", json!({"confirmed": "5"}), "wallet/balance@v1");
+        ns.write_scroll(good).unwrap();
+        assert!(matches!(ns.write_scroll(bad), Err(Error::InvalidData(ref m)) if m.contains("confirmed")));
+        ns.write("/wallet/note", json!("untyped")).unwrap();
+    }
+
+    #[test]
+    fn rate_limit_is_per_path() {
+        let ns = InterceptedNamespace::new(Arc::new(MemoryNamespace::new()))
+            .with(Arc::new(RateLimit::new("/api/*", 2, Duration::from_secs(60))));
+
+        ns.write("/api/a", json!(1)).unwrap();
+        ns.write("/api/a", json!(2)).unwrap();
+        assert!(matches!(ns.write("/api/a", json!(3)), Err(Error::Unavailable(_))));
+
+        // Other paths, other operations and other globs are unaffected
+        ns.write("/api/b", json!(1)).unwrap();
+        ns.read("/api/a").unwrap();
+        ns.write("/other", json!(1)).unwrap();
+    }
+
+    #[test]
+    fn limits_and_checks_see_canonical_paths() {
+        let memory = Arc::new(MemoryNamespace::new());
+        let ns = InterceptedNamespace::new(memory.clone())
+            .with(Arc::new(RateLimit::new("/api/*", 1, Duration::from_secs(60))))
+            .with(Arc::new(Validate::required("/api/*", &["id"])));
+
+        ns.write("/api/a", json!({"id": 1})).unwrap();
+        for path in ["/api//a", "//api/a", "/api/a/"] {
+            assert!(matches!(ns.write(path, json!({"id": 2})), Err(Error::Unavailable(_))), "{}", path);
+        }
+        assert!(matches!(ns.write("/api//b", json!({})), Err(Error::InvalidData(_))));
+
+        ns.write("/api/c/", json!({"id": 3})).unwrap();
+        assert_eq!(memory.read("/api/c").unwrap().unwrap().key, "/api/c");
+    }
+
+    #[test]
+    fn rate_limit_window_rolls_over() {
+        let ns = InterceptedNamespace::new(Arc::new(MemoryNamespace::new())).with(Arc::new(
+            RateLimit::new("/**", 1, Duration::from_millis(20)).ops(Ops::READ),
+        ));
+
+        ns.read("/x").unwrap();
+        assert!(ns.read("/x").is_err());
+        std::thread::sleep(Duration::from_millis(30));
+        ns.read("/x").unwrap();
+    }
+
+    #[cfg(feature = "std-channel")]
+    #[test]
+    fn watch_events_pass_through_on_event() {
+        /// Drops drafts from watch streams
+        struct HideDrafts;
+
+        impl Interceptor for HideDrafts {
+            fn on_event(&self, _call: &Call, scroll: Scroll) -> Option<Scroll> {
+                (!scroll.key.ends_with(".draft")).then_some(scroll)
+            }
+        }
+
+        let memory = Arc::new(MemoryNamespace::new());
+        let ns = InterceptedNamespace::new(memory.clone()).with(Arc::new(HideDrafts));
+
+        let mut rx = ns.watch("/docs/*").unwrap();
+        memory.write("/docs/a.draft", json!(1)).unwrap();
+        memory.write("/docs/b", json!(2)).unwrap();
+
+        assert_eq!(rx.recv().unwrap().key, "/docs/b");
+    }
+}
@@ -7,17 +7,41 @@
 //! # Security
 //! - Mount paths are matched on segment boundaries (no cross-namespace leaks)
 //! - `/foo` does NOT match `/foobar` (only `/foo` or `/foo/...`)
+//!
+//! # Path Rewriting
+//!
+//! A mounted namespace only ever sees local paths: the mount prefix is
+//! stripped on the way in and restored on the way out. That holds for
+//! watches too:
+//!
+//! - A pattern inside one mount (`/wallet/tx/**`) becomes that mount's
+//!   local pattern (`/tx/**`)
+//! - A recursive pattern above several mounts (`/**`) watches each of them
+//!   with `/**`; events a deeper mount shadows are dropped, so every key
+//!   comes from the namespace a `read` of it would reach
+//! - Keys are rewritten to global form (`/tx/1` → `/wallet/tx/1`)
+//! - Watches outlive the mount table: after `unmount` the namespace stops
+//!   contributing, and a namespace mounted later is attached to every live
+//!   watch that covers it
 
 #[cfg(feature = "std-channel")]
-use super::channel::{channel, Receiver};
+use super::channel::{channel, Receiver, Sender};
 #[cfg(not(feature = "std-channel"))]
 use super::namespace::Receiver;
 
+#[cfg(feature = "std-channel")]
+use super::namespace::path_matches;
 use super::namespace::{Error, Namespace, Result};
 use super::scroll::Scroll;
+use super::capability::{Policy, ScopedNamespace};
+use super::union::{Bind, Union};
 use serde_json::Value;
 use std::collections::BTreeMap;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Source of mount ids, so a remount at the same path is a new mount
+static NEXT_MOUNT_ID: AtomicU64 = AtomicU64::new(1);
 
 /// Check if a path matches a mount point on segment boundaries.
 ///
@@ -29,7 +53,7 @@ use std::sync::{Arc, RwLock};
 /// - path == mount_path (exact match)
 /// - path starts with mount_path followed by '/' (proper child)
 /// - mount_path == "/" (root mount matches everything)
-fn is_path_under_mount(path: &str, mount_path: &str) -> bool {
+pub(super) fn is_path_under_mount(path: &str, mount_path: &str) -> bool {
     if mount_path == "/" {
         return path.starts_with('/');
     }
@@ -51,7 +75,7 @@ fn is_path_under_mount(path: &str, mount_path: &str) -> bool {
 ///
 /// - Ensures path starts with '/'
 /// - Removes trailing slashes (except for root "/")
-fn normalize_mount_path(path: &str) -> String {
+pub(super) fn normalize_mount_path(path: &str) -> String {
     let mut normalized = path.to_string();
 
     // Ensure starts with /
@@ -67,11 +91,261 @@ fn normalize_mount_path(path: &str) -> String {
     normalized
 }
 
+/// Find the longest mount covering a path (segment boundaries respected)
+fn longest_mount<'a, T>(mounts: &'a BTreeMap<String, T>, path: &str) -> Option<(&'a String, &'a T)> {
+    let mut best_match: Option<(&String, &T)> = None;
+
+    for (mount_path, ns) in mounts.iter() {
+        // Use segment-boundary-aware matching
+        if is_path_under_mount(path, mount_path) {
+            if let Some((current_best, _)) = best_match {
+                if mount_path.len() > current_best.len() {
+                    best_match = Some((mount_path, ns));
+                }
+            } else {
+                best_match = Some((mount_path, ns));
+            }
+        }
+    }
+
+    best_match
+}
+
+/// Strip a mount prefix, giving the path the mounted namespace sees
+fn strip_mount(mount_path: &str, path: &str) -> String {
+    if mount_path == "/" {
+        path.to_string()
+    } else if path == mount_path {
+        "/".to_string()
+    } else {
+        path[mount_path.len()..].to_string()
+    }
+}
+
+/// Find the mount for a path and the path the mounted namespace sees
+///
+/// Longest prefix match on segment boundaries; shared by `Kernel` and
+/// `AsyncKernel`.
+pub(super) fn route<T: Clone>(mounts: &BTreeMap<String, T>, path: &str) -> Result<(T, String)> {
+    match longest_mount(mounts, path) {
+        Some((mount_path, ns)) => Ok((ns.clone(), strip_mount(mount_path, path))),
+        None => Err(Error::NotFound(format!(
+            "no namespace mounted for path: {}",
+            path
+        ))),
+    }
+}
+
+/// Restore the mount prefix on paths listed by a mounted namespace
+pub(super) fn unstrip_paths(prefix: &str, stripped: &str, paths: Vec<String>) -> Vec<String> {
+    let mount_prefix = if prefix == "/" {
+        "".to_string()
+    } else if stripped == "/" && !prefix.ends_with('/') {
+        // Listing the mount point itself
+        prefix.to_string()
+    } else {
+        let stripped_len = stripped.len();
+        if prefix.len() >= stripped_len {
+            prefix[..prefix.len() - stripped_len].to_string()
+        } else {
+            "".to_string()
+        }
+    };
+
+    paths
+        .into_iter()
+        .map(|p| {
+            if mount_prefix.is_empty() {
+                p
+            } else if p == "/" {
+                mount_prefix.clone()
+            } else {
+                format!("{}{}", mount_prefix, p)
+            }
+        })
+        .collect()
+}
+
+/// Restore the mount prefix on the key of a watched scroll
+pub(super) fn global_key(mount_path: &str, key: &str) -> String {
+    if mount_path == "/" {
+        key.to_string()
+    } else if key == "/" {
+        mount_path.to_string()
+    } else {
+        format!("{}{}", mount_path, key)
+    }
+}
+
+/// Every mount a watch pattern reaches, with the local pattern to watch it by
+///
+/// Patterns are a literal path with an optional trailing `/*` or `/**`.
+/// The mount that routes the literal part gets the stripped pattern; mounts
+/// below the literal part are reached by `/**` (everything in them) or by
+/// `/*` when the mount point itself is a direct child.
+pub(super) fn watch_targets<T: Clone>(
+    mounts: &BTreeMap<String, T>,
+    pattern: &str,
+) -> Vec<(String, T, String)> {
+    let (base, wildcard) = if let Some(base) = pattern.strip_suffix("/**") {
+        (base, "/**")
+    } else if let Some(base) = pattern.strip_suffix("/*") {
+        (base, "/*")
+    } else {
+        (pattern, "")
+    };
+    let base = if base.is_empty() { "/" } else { base };
+
+    let mut targets = Vec::new();
+    let routed = longest_mount(mounts, base).map(|(mount_path, ns)| {
+        let stripped = strip_mount(mount_path, base);
+        let local = match (stripped.as_str(), wildcard) {
+            (_, "") => stripped.clone(),
+            ("/", _) => wildcard.to_string(),
+            (_, _) => format!("{}{}", stripped, wildcard),
+        };
+        targets.push((mount_path.clone(), ns.clone(), local));
+        mount_path.clone()
+    });
+
+    if wildcard.is_empty() {
+        return targets;
+    }
+
+    for (mount_path, ns) in mounts.iter() {
+        if Some(mount_path) == routed.as_ref() || mount_path == base {
+            continue;
+        }
+        if !is_path_under_mount(mount_path, base) {
+            continue;
+        }
+
+        if wildcard == "/**" {
+            targets.push((mount_path.clone(), ns.clone(), "/**".to_string()));
+        } else {
+            // `/*` reaches only the mount point itself, one level down
+            let rest = strip_mount(base, mount_path);
+            if !rest[1..].contains('/') {
+                targets.push((mount_path.clone(), ns.clone(), "/".to_string()));
+            }
+        }
+    }
+
+    targets
+}
+
+/// A mounted namespace
+///
+/// The id tells a remount at the same path apart from the original, so
+/// watchers attached to an unmounted namespace can retire.
+pub(super) struct Mount<N: ?Sized> {
+    pub(super) id: u64,
+    pub(super) ns: Arc<N>,
+}
+
+impl<N: ?Sized> Mount<N> {
+    pub(super) fn new(ns: Arc<N>) -> Self {
+        Self {
+            id: NEXT_MOUNT_ID.fetch_add(1, Ordering::Relaxed),
+            ns,
+        }
+    }
+
+    /// Whether `key` (global) is served by this mount in `mounts`
+    pub(super) fn serves(&self, mounts: &BTreeMap<String, Mount<N>>, key: &str) -> bool {
+        longest_mount(mounts, key).is_some_and(|(_, m)| m.id == self.id)
+    }
+}
+
+impl<N: ?Sized> Clone for Mount<N> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            ns: self.ns.clone(),
+        }
+    }
+}
+
+type MountTable = Arc<RwLock<BTreeMap<String, Mount<dyn Namespace>>>>;
+
+/// A live `Kernel::watch`, kept so later mounts can join it
+#[cfg(feature = "std-channel")]
+struct KernelWatch {
+    pattern: String,
+    tx: Sender<Scroll>,
+}
+
+#[cfg(feature = "std-channel")]
+impl KernelWatch {
+    /// Whether the kernel-side receiver is still held
+    fn is_live(&self) -> bool {
+        !self.tx.is_closed()
+    }
+
+    /// Watch one mount and forward its scrolls under global keys
+    ///
+    /// The forwarding thread retires once the mount is replaced or removed,
+    /// or the kernel-side receiver is dropped (both noticed on the next
+    /// scroll). Mounting prunes dropped watches before attaching, so they
+    /// never gain new threads.
+    fn attach(
+        self: &Arc<Self>,
+        mounts: &MountTable,
+        mount_path: String,
+        mount: Mount<dyn Namespace>,
+        local: &str,
+    ) -> Result<()> {
+        let mut rx = mount.ns.watch(local)?;
+        let watch = self.clone();
+        let mounts = mounts.clone();
+        let id = mount.id;
+        drop(mount);
+
+        std::thread::spawn(move || {
+            while let Some(mut scroll) = rx.recv() {
+                if !watch.is_live() {
+                    break;
+                }
+
+                scroll.key = global_key(&mount_path, &scroll.key);
+
+                {
+                    let table = mounts.read().unwrap();
+                    match table.get(&mount_path) {
+                        Some(current) if current.id == id => {
+                            // Shadowed by a deeper mount: not ours to report
+                            if !current.serves(&table, &scroll.key) {
+                                continue;
+                            }
+                        }
+                        _ => break, // Unmounted or replaced
+                    }
+                }
+
+                if !path_matches(&scroll.key, &watch.pattern) {
+                    continue;
+                }
+
+                if watch.tx.send(scroll).is_err() {
+                    break; // Receiver dropped
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
 /// Kernel - Namespace composition via mount table
 ///
 /// Mount namespaces at paths. Operations are routed by longest prefix match.
 pub struct Kernel {
-    mounts: Arc<RwLock<BTreeMap<String, Arc<dyn Namespace>>>>,
+    mounts: MountTable,
+    /// Live watches, joined by namespaces mounted after they started
+    #[cfg(feature = "std-channel")]
+    watches: Arc<Mutex<Vec<Arc<KernelWatch>>>>,
+    /// Unions created by `bind`, keyed by path with their mount id
+    unions: Mutex<BTreeMap<String, (u64, Arc<Union>)>>,
 }
 
 impl Kernel {
@@ -79,6 +353,9 @@ impl Kernel {
     pub fn new() -> Self {
         Self {
             mounts: Arc::new(RwLock::new(BTreeMap::new())),
+            #[cfg(feature = "std-channel")]
+            watches: Arc::new(Mutex::new(Vec::new())),
+            unions: Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -90,9 +367,85 @@ impl Kernel {
     /// # Path Normalization
     /// - Paths are normalized (leading `/` added, trailing `/` removed)
     /// - `/foo/` becomes `/foo`
+    ///
+    /// # Watches
+    /// Live watches whose pattern covers the new mount start receiving its
+    /// scrolls; a namespace previously mounted at `path` stops contributing.
     pub fn mount(&self, path: impl Into<String>, ns: Arc<dyn Namespace>) {
-        let path = normalize_mount_path(&path.into());
-        self.mounts.write().unwrap().insert(path, ns);
+        self.install(normalize_mount_path(&path.into()), ns);
+    }
+
+    /// Put a namespace in the mount table, returning its mount id
+    fn install(&self, path: String, ns: Arc<dyn Namespace>) -> u64 {
+        let mount = Mount::new(ns);
+        let id = mount.id;
+
+        #[cfg(feature = "std-channel")]
+        {
+            // Held across insert and attach so a concurrent watch sees the mount once
+            let mut watches = self.watches.lock().unwrap();
+            watches.retain(|w| w.is_live());
+            self.mounts.write().unwrap().insert(path, mount.clone());
+
+            let table = self.mounts.read().unwrap().clone();
+            for watch in watches.iter() {
+                for (mount_path, target, local) in watch_targets(&table, &watch.pattern) {
+                    if target.id == mount.id {
+                        // Best effort: a namespace without watch support just stays silent
+                        let _ = watch.attach(&self.mounts, mount_path, target, &local);
+                    }
+                }
+            }
+        }
+
+        #[cfg(not(feature = "std-channel"))]
+        self.mounts.write().unwrap().insert(path, mount);
+
+        id
+    }
+
+    /// Bind a read-only namespace into the union at `path`
+    ///
+    /// Plan 9 style: whatever is mounted at `path` becomes a union (keeping
+    /// its role as the writable layer) and `ns` joins it above or below.
+    /// Reads fall through the layers, `list` merges them.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// kernel.mount("/prefs", Arc::new(store));
+    /// kernel.bind("/prefs", Arc::new(defaults), Bind::After);
+    /// ```
+    pub fn bind(&self, path: impl Into<String>, ns: Arc<dyn Namespace>, order: Bind) {
+        self.bind_layer(path.into(), ns, order, false);
+    }
+
+    /// Bind a namespace as the union's writable layer
+    ///
+    /// Writes under `path` go to `ns` from now on; the previous writable
+    /// layer stays in the union for reads.
+    pub fn bind_writable(&self, path: impl Into<String>, ns: Arc<dyn Namespace>, order: Bind) {
+        self.bind_layer(path.into(), ns, order, true);
+    }
+
+    fn bind_layer(&self, path: String, ns: Arc<dyn Namespace>, order: Bind, writable: bool) {
+        let path = normalize_mount_path(&path);
+        let mut unions = self.unions.lock().unwrap();
+        let current = self.mounts.read().unwrap().get(&path).cloned();
+
+        let union = match (current, unions.get(&path)) {
+            (Some(mount), Some((id, union))) if mount.id == *id => union.clone(),
+            (Some(mount), _) => Arc::new(Union::new().with_write_layer(mount.ns)),
+            (None, _) => Arc::new(Union::new()),
+        };
+        if writable {
+            union.bind_writable(ns, order);
+        } else {
+            union.bind(ns, order);
+        }
+
+        // Remount so live watches pick up the new layer
+        let id = self.install(path.clone(), union.clone());
+        unions.insert(path, (id, union));
     }
 
     /// Mount a namespace (convenience method that wraps in Arc)
@@ -104,7 +457,16 @@ impl Kernel {
     ///
     /// Returns the previously mounted namespace, or None if nothing was mounted.
     pub fn unmount(&self, path: &str) -> Option<Arc<dyn Namespace>> {
-        self.mounts.write().unwrap().remove(path)
+        self.unions.lock().unwrap().remove(path);
+        self.mounts.write().unwrap().remove(path).map(|m| m.ns)
+    }
+
+    /// Derive a handle restricted to what `policy` grants
+    ///
+    /// For untrusted callers such as plugins: refused calls fail with
+    /// `Error::Permission` and are logged on the returned handle.
+    pub fn scoped(self: &Arc<Self>, policy: Policy) -> ScopedNamespace {
+        ScopedNamespace::new(self.clone(), policy)
     }
 
     /// Find the namespace and translated path for a given path
@@ -115,42 +477,7 @@ impl Kernel {
     /// - Only matches on segment boundaries (`/foo` matches `/foo/bar` but NOT `/foobar`)
     /// - Prevents cross-namespace data leakage
     fn resolve(&self, path: &str) -> Result<(Arc<dyn Namespace>, String)> {
-        let mounts = self.mounts.read().unwrap();
-
-        // Find longest matching prefix with segment boundary check
-        let mut best_match: Option<(&String, &Arc<dyn Namespace>)> = None;
-
-        for (mount_path, ns) in mounts.iter() {
-            // Use segment-boundary-aware matching
-            if is_path_under_mount(path, mount_path) {
-                if let Some((current_best, _)) = best_match {
-                    if mount_path.len() > current_best.len() {
-                        best_match = Some((mount_path, ns));
-                    }
-                } else {
-                    best_match = Some((mount_path, ns));
-                }
-            }
-        }
-
-        match best_match {
-            Some((mount_path, ns)) => {
-                // Strip prefix
-                let stripped = if mount_path == "/" {
-                    path.to_string()
-                } else if path == mount_path {
-                    "/".to_string()
-                } else {
-                    path[mount_path.len()..].to_string()
-                };
-
-                Ok((ns.clone(), stripped))
-            }
-            None => Err(Error::NotFound(format!(
-                "no namespace mounted for path: {}",
-                path
-            ))),
-        }
+        route(&self.mounts.read().unwrap(), path).map(|(m, stripped)| (m.ns, stripped))
     }
 }
 
@@ -200,73 +527,63 @@ impl Namespace for Kernel {
         let paths = ns.list(&stripped)?;
 
         // Restore mount prefix to paths
-        let mount_prefix = if prefix == "/" {
-            "".to_string()
-        } else {
-            let stripped_len = stripped.len();
-            if prefix.len() >= stripped_len {
-                prefix[..prefix.len() - stripped_len].to_string()
-            } else {
-                "".to_string()
-            }
-        };
-
-        Ok(paths
-            .into_iter()
-            .map(|p| {
-                if mount_prefix.is_empty() {
-                    p
-                } else if p == "/" {
-                    mount_prefix.clone()
-                } else {
-                    format!("{}{}", mount_prefix, p)
-                }
-            })
-            .collect())
+        Ok(unstrip_paths(prefix, &stripped, paths))
     }
 
+    /// Watch every mount the pattern reaches, with keys in global form
+    ///
+    /// The watch stays registered until its receiver is dropped, picking up
+    /// namespaces mounted later (see the module docs on path rewriting).
+    #[cfg(feature = "std-channel")]
     fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
-        let (ns, stripped) = self.resolve(pattern)?;
-        let mut rx = ns.watch(&stripped)?;
+        // Held until registered so a concurrent mount sees the watch once
+        let mut watches = self.watches.lock().unwrap();
 
-        // Create new channel to translate paths back
-        let (tx, output_rx) = channel(16);
-        let pattern_owned = pattern.to_string();
-        let stripped_owned = stripped.clone();
+        let table = self.mounts.read().unwrap().clone();
+        let targets = watch_targets(&table, pattern);
+        if targets.is_empty() {
+            return Err(Error::NotFound(format!(
+                "no namespace mounted for path: {}",
+                pattern
+            )));
+        }
 
-        // Spawn thread to translate scroll keys
-        std::thread::spawn(move || {
-            while let Some(mut scroll) = rx.recv() {
-                // Restore original path prefix
-                if stripped_owned == "/" {
-                    scroll.key = format!(
-                        "{}{}",
-                        pattern_owned.trim_end_matches("/**").trim_end_matches("/*"),
-                        scroll.key
-                    );
-                } else {
-                    let mount_prefix =
-                        &pattern_owned[..pattern_owned.len() - stripped_owned.len()];
-                    scroll.key = if scroll.key == "/" {
-                        mount_prefix.to_string()
-                    } else {
-                        format!("{}{}", mount_prefix, scroll.key)
-                    };
-                }
+        let (tx, output_rx) = channel(16);
+        let watch = Arc::new(KernelWatch {
+            pattern: pattern.to_string(),
+            tx,
+        });
 
-                if tx.send(scroll).is_err() {
-                    break; // Receiver dropped
+        // Fail only if no mount could be watched at all
+        let mut first_error = None;
+        let mut attached = 0;
+        for (mount_path, mount, local) in targets {
+            match watch.attach(&self.mounts, mount_path, mount, &local) {
+                Ok(()) => attached += 1,
+                Err(e) => {
+                    first_error.get_or_insert(e);
                 }
             }
-        });
+        }
+        if let (0, Some(e)) = (attached, first_error) {
+            return Err(e);
+        }
+
+        watches.retain(|w| w.is_live());
+        watches.push(watch);
 
         Ok(output_rx)
     }
 
+    #[cfg(not(feature = "std-channel"))]
+    fn watch(&self, _pattern: &str) -> Result<Receiver<Scroll>> {
+        Err(Error::Unavailable("watch requires the std-channel feature".to_string()))
+    }
+
     fn close(&self) -> Result<()> {
         let mounts = self.mounts.read().unwrap();
-        for ns in mounts.values() {
-            let _ = ns.close();
+        for mount in mounts.values() {
+            let _ = mount.ns.close();
         }
         Ok(())
     }
@@ -329,5 +646,172 @@ mod tests {
         assert_eq!(normalize_mount_path("foo/"), "/foo");
     }
 
+    #[test]
+    fn test_unstrip_paths_at_mount_point() {
+        let paths = vec!["/a".to_string(), "/b/c".to_string()];
+        assert_eq!(
+            unstrip_paths("/wallet", "/", paths.clone()),
+            vec!["/wallet/a".to_string(), "/wallet/b/c".to_string()]
+        );
+        assert_eq!(
+            unstrip_paths("/wallet/", "/", paths.clone()),
+            vec!["/wallet/a".to_string(), "/wallet/b/c".to_string()]
+        );
+        assert_eq!(unstrip_paths("/", "/", paths.clone()), paths);
+    }
+
+    #[test]
+    fn test_watch_targets() {
+        let mut mounts = BTreeMap::new();
+        for path in ["/", "/wallet", "/wallet/cache", "/vault"] {
+            mounts.insert(path.to_string(), path);
+        }
+        let targets = |pattern| {
+            let mut t: Vec<_> = watch_targets(&mounts, pattern)
+                .into_iter()
+                .map(|(path, _, local)| (path, local))
+                .collect();
+            t.sort();
+            t
+        };
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        // Inside one mount: the longest mount, local pattern
+        assert_eq!(targets("/wallet/tx/**"), vec![pair("/wallet", "/tx/**")]);
+        assert_eq!(targets("/wallet/*"), vec![pair("/wallet", "/*"), pair("/wallet/cache", "/")]);
+        assert_eq!(targets("/vault"), vec![pair("/vault", "/")]);
+
+        // Above several mounts: each of them
+        assert_eq!(
+            targets("/**"),
+            vec![
+                pair("/", "/**"),
+                pair("/vault", "/**"),
+                pair("/wallet", "/**"),
+                pair("/wallet/cache", "/**"),
+            ]
+        );
+        assert_eq!(targets("/wallet/**"), vec![pair("/wallet", "/**"), pair("/wallet/cache", "/**")]);
+    }
+
+    #[cfg(feature = "std-channel")]
+    #[test]
+    fn test_watch_spans_mounts_with_global_keys() {
+        use crate::nine_s::MemoryNamespace;
+        use serde_json::json;
+
+        let root = Arc::new(MemoryNamespace::new());
+        let app = Arc::new(MemoryNamespace::new());
+        let kernel = Kernel::new();
+        kernel.mount("/", root.clone());
+        kernel.mount("/app", app.clone());
+
+        let mut rx = kernel.watch("/**").unwrap();
+
+        app.write("/x", json!(1)).unwrap();
+        // Shadowed by the /app mount: a read of /app/shadow never reaches root
+        root.write("/app/shadow", json!(2)).unwrap();
+        root.write("/y", json!(3)).unwrap();
+
+        let mut keys = vec![rx.recv().unwrap().key, rx.recv().unwrap().key];
+        keys.sort();
+        assert_eq!(keys, vec!["/app/x".to_string(), "/y".to_string()]);
+        assert!(rx.try_recv().is_none());
+    }
+
+    #[cfg(feature = "std-channel")]
+    #[test]
+    fn test_watch_survives_remount() {
+        use crate::nine_s::MemoryNamespace;
+        use serde_json::json;
+
+        let first = Arc::new(MemoryNamespace::new());
+        let kernel = Kernel::new();
+        kernel.mount("/app", first.clone());
+
+        let mut rx = kernel.watch("/app/items/*").unwrap();
+        first.write("/items/1", json!(1)).unwrap();
+        assert_eq!(rx.recv().unwrap().key, "/app/items/1");
+
+        // Unmounted namespaces stop contributing
+        kernel.unmount("/app");
+        first.write("/items/2", json!(2)).unwrap();
+
+        // The replacement joins the existing watch
+        let second = Arc::new(MemoryNamespace::new());
+        kernel.mount("/app", second.clone());
+        second.write("/items/3", json!(3)).unwrap();
+        second.write("/other", json!(4)).unwrap();
+
+        let scroll = rx.recv().unwrap();
+        assert_eq!(scroll.key, "/app/items/3");
+        assert_eq!(scroll.data, json!(3));
+    }
+
+    #[test]
+    fn test_bind_overlays_existing_mount() {
+        use crate::nine_s::MemoryNamespace;
+        use serde_json::json;
+
+        let user = Arc::new(MemoryNamespace::new());
+        let defaults = Arc::new(MemoryNamespace::new());
+        defaults.write("/theme", json!("dark")).unwrap();
+        defaults.write("/unit", json!("sat")).unwrap();
+
+        let kernel = Kernel::new();
+        kernel.mount("/prefs", user.clone());
+        kernel.bind("/prefs", defaults.clone(), Bind::After);
+
+        // Reads fall through, writes still land in the original mount
+        assert_eq!(kernel.read("/prefs/theme").unwrap().unwrap().data, "dark");
+        kernel.write("/prefs/theme", json!("light")).unwrap();
+        assert_eq!(user.read("/theme").unwrap().unwrap().data, "light");
+        assert_eq!(kernel.read("/prefs/theme").unwrap().unwrap().data, "light");
+        assert_eq!(
+            kernel.list("/prefs").unwrap(),
+            vec!["/prefs/theme".to_string(), "/prefs/unit".to_string()]
+        );
+
+        // A writable layer bound on top takes the writes
+        let session = Arc::new(MemoryNamespace::new());
+        kernel.bind_writable("/prefs", session.clone(), Bind::Before);
+        kernel.write("/prefs/unit", json!("btc")).unwrap();
+        assert_eq!(session.read("/unit").unwrap().unwrap().data, "btc");
+        assert!(user.read("/unit").unwrap().is_none());
+        assert_eq!(kernel.read("/prefs/theme").unwrap().unwrap().data, "light");
+    }
+
+    #[cfg(feature = "std-channel")]
+    #[test]
+    fn test_mount_skips_dropped_watches() {
+        use crate::nine_s::MemoryNamespace;
+
+        let kernel = Kernel::new();
+        kernel.mount("/app", Arc::new(MemoryNamespace::new()));
+        let _kept = kernel.watch("/app/**").unwrap();
+        drop(kernel.watch("/app/**").unwrap());
+
+        // Nothing was written, so no send has failed yet
+        kernel.mount("/app", Arc::new(MemoryNamespace::new()));
+        assert_eq!(kernel.watches.lock().unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "std-channel")]
+    #[test]
+    fn test_watch_follows_bind() {
+        use crate::nine_s::MemoryNamespace;
+        use serde_json::json;
+
+        let kernel = Kernel::new();
+        kernel.mount("/prefs", Arc::new(MemoryNamespace::new()));
+        let mut rx = kernel.watch("/prefs/*").unwrap();
+
+        let defaults = Arc::new(MemoryNamespace::new());
+        kernel.bind("/prefs", defaults.clone(), Bind::After);
+        defaults.write("/theme", json!("dark")).unwrap();
+
+        assert_eq!(rx.recv().unwrap().key, "/prefs/theme");
+    }
+
     // Integration tests with MemoryNamespace in backends/memory.rs
 }
@@ -0,0 +1,366 @@
+//! Merge - Three-way merge for divergent Scroll states
+//!
+//! Pure functions built on `patch::diff`. Given a common ancestor and two
+//! divergent states, computes a merged Scroll and reports every JSON
+//! pointer where both sides changed the same data differently.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Last write wins (simple, loses data)
+//! **Antithesis**: Reject every concurrent write (safe, unusable)
+//! **Synthesis**: Merge what doesn't overlap, resolve what does by policy
+//!
+//! # Algorithm
+//!
+//! ```text
+//!         base
+//!        /    \
+//!   diff /      \ diff
+//!      ours    theirs
+//!        \      /
+//!         merged
+//! ```
+//!
+//! 1. Diff base → ours and base → theirs (RFC 6902 ops)
+//! 2. Ops whose pointers don't overlap are applied from both sides
+//! 3. Overlapping ops collapse to their common ancestor pointer; if both
+//!    sides ended up with the same value there it's taken as-is, otherwise
+//!    it's a conflict and the `MergePolicy` decides
+//!
+//! # Usage
+//!
+//! ```rust
+//! use beewallet_core_spark::nine_s::Scroll;
+//! use beewallet_core_spark::nine_s::merge::{self, MergePolicy};
+//! use serde_json::json;
+//!
+//! let base = Scroll::new("/doc", json!({"title": "Draft", "tags": 1}));
+//! let ours = Scroll::new("/doc", json!({"title": "Final", "tags": 1}));
+//! let theirs = Scroll::new("/doc", json!({"title": "Draft", "tags": 2}));
+//!
+//! let result = merge::three_way(&base, &ours, &theirs, &MergePolicy::Ours);
+//! assert!(result.is_clean());
+//! assert_eq!(result.scroll.data, json!({"title": "Final", "tags": 2}));
+//! ```
+
+use super::patch::{diff, Patch, PatchOp};
+use super::scroll::Scroll;
+use serde_json::Value;
+
+/// One pointer where both sides diverged from the ancestor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// JSON pointer into `data` (RFC 6901, "" for the whole payload)
+    pub pointer: String,
+    /// Value in the common ancestor (None if absent)
+    pub base: Option<Value>,
+    /// Value on our side (None if removed)
+    pub ours: Option<Value>,
+    /// Value on their side (None if removed)
+    pub theirs: Option<Value>,
+}
+
+/// Resolver callback: returns the value to keep, or None to remove
+pub type Resolver = Box<dyn Fn(&Conflict) -> Option<Value> + Send + Sync>;
+
+/// How conflicting pointers are resolved
+pub enum MergePolicy {
+    /// Keep our value
+    Ours,
+    /// Take their value
+    Theirs,
+    /// Decide per conflict; return None to remove the pointer
+    Custom(Resolver),
+}
+
+impl MergePolicy {
+    /// Build a custom policy from a closure
+    pub fn custom(f: impl Fn(&Conflict) -> Option<Value> + Send + Sync + 'static) -> Self {
+        MergePolicy::Custom(Box::new(f))
+    }
+
+    fn resolve(&self, conflict: &Conflict) -> Option<Value> {
+        match self {
+            MergePolicy::Ours => conflict.ours.clone(),
+            MergePolicy::Theirs => conflict.theirs.clone(),
+            MergePolicy::Custom(f) => f(conflict),
+        }
+    }
+}
+
+impl std::fmt::Debug for MergePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MergePolicy::Ours => write!(f, "Ours"),
+            MergePolicy::Theirs => write!(f, "Theirs"),
+            MergePolicy::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Result of a three-way merge
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// The merged scroll (conflicts already resolved by policy)
+    pub scroll: Scroll,
+    /// JSON pointers where both sides changed the same data differently
+    pub conflicts: Vec<String>,
+}
+
+impl MergeResult {
+    /// True if no pointer needed the policy to decide
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Three-way merge of two divergent states against their common ancestor
+///
+/// The merged scroll takes its key, type and metadata from `ours`, unless
+/// only `theirs` changed the type. Conflicts never fail the merge: the
+/// policy always produces a value, and the pointers are reported so the
+/// caller can surface them.
+pub fn three_way(base: &Scroll, ours: &Scroll, theirs: &Scroll, policy: &MergePolicy) -> MergeResult {
+    let our_ops = diff::create(&base.key, Some(base), ours).ops;
+    let their_ops = diff::create(&base.key, Some(base), theirs).ops;
+
+    // Pointers touched by both sides collapse to their common ancestor
+    let mut overlaps: Vec<String> = Vec::new();
+    for a in &our_ops {
+        for b in &their_ops {
+            let (pa, pb) = (op_path(a), op_path(b));
+            if let Some(root) = overlap(pa, pb) {
+                overlaps.push(root.to_string());
+            }
+        }
+    }
+    let roots = outermost(overlaps);
+
+    let mut data = base.data.clone();
+
+    // Non-overlapping changes from both sides
+    for op in our_ops.iter().chain(their_ops.iter()) {
+        if !roots.iter().any(|r| is_under(op_path(op), r)) {
+            apply_one(&mut data, op.clone());
+        }
+    }
+
+    // Overlapping changes: converged, or resolved by policy
+    let mut conflicts = Vec::new();
+    for root in &roots {
+        let ours_val = get(&ours.data, root);
+        let theirs_val = get(&theirs.data, root);
+
+        let resolved = if ours_val == theirs_val {
+            ours_val
+        } else {
+            let conflict = Conflict {
+                pointer: root.clone(),
+                base: get(&base.data, root),
+                ours: ours_val,
+                theirs: theirs_val,
+            };
+            conflicts.push(root.clone());
+            policy.resolve(&conflict)
+        };
+
+        match resolved {
+            Some(value) => apply_one(&mut data, PatchOp::Add { path: root.clone(), value }),
+            None if get(&data, root).is_some() => {
+                apply_one(&mut data, PatchOp::Remove { path: root.clone() })
+            }
+            None => {}
+        }
+    }
+
+    let mut scroll = ours.clone();
+    if ours.type_ == base.type_ {
+        scroll.type_ = theirs.type_.clone();
+    }
+    scroll.data = data;
+
+    MergeResult { scroll, conflicts }
+}
+
+// ============================================================================
+// Internal: pointer helpers
+// ============================================================================
+
+/// Pointer an op writes to
+fn op_path(op: &PatchOp) -> &str {
+    match op {
+        PatchOp::Add { path, .. }
+        | PatchOp::Remove { path }
+        | PatchOp::Replace { path, .. }
+        | PatchOp::Move { path, .. }
+        | PatchOp::Copy { path, .. }
+        | PatchOp::Test { path, .. } => path,
+    }
+}
+
+/// Check if `path` equals `root` or lies beneath it (segment boundary)
+fn is_under(path: &str, root: &str) -> bool {
+    path == root || (path.starts_with(root) && path[root.len()..].starts_with('/'))
+}
+
+/// If two pointers overlap, return the outer one
+fn overlap<'a>(a: &'a str, b: &'a str) -> Option<&'a str> {
+    if is_under(b, a) {
+        Some(a)
+    } else if is_under(a, b) {
+        Some(b)
+    } else {
+        None
+    }
+}
+
+/// Drop duplicates and pointers nested under another pointer in the set
+fn outermost(mut pointers: Vec<String>) -> Vec<String> {
+    pointers.sort();
+    pointers.dedup();
+    let all = pointers.clone();
+    pointers.retain(|p| !all.iter().any(|r| r != p && is_under(p, r)));
+    pointers
+}
+
+/// Value at a pointer (None if absent)
+fn get(data: &Value, pointer: &str) -> Option<Value> {
+    data.pointer(pointer).cloned()
+}
+
+/// Apply a single op via `diff::apply`
+///
+/// Ops come from diffs against the same base and touch disjoint pointers,
+/// so failures only arise from already-converged removals and are ignored.
+fn apply_one(data: &mut Value, op: PatchOp) {
+    let patch = Patch {
+        key: String::new(),
+        ops: vec![op],
+        parent: None,
+        hash: String::new(),
+        timestamp: 0,
+        seq: 0,
+        deleted: false,
+        migration: None,
+    };
+    if let Ok(result) = diff::apply(&Scroll::new("", data.clone()), &patch) {
+        *data = result.data;
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scroll(data: Value) -> Scroll {
+        Scroll::new("/doc", data)
+    }
+
+    #[test]
+    fn merge_disjoint_changes_is_clean() {
+        let base = scroll(json!({"a": 1, "b": 1, "c": 1}));
+        let ours = scroll(json!({"a": 2, "b": 1, "c": 1}));
+        let theirs = scroll(json!({"a": 1, "b": 1, "d": 4}));
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Ours);
+        assert!(result.is_clean());
+        assert_eq!(result.scroll.data, json!({"a": 2, "b": 1, "d": 4}));
+    }
+
+    #[test]
+    fn merge_conflict_policies() {
+        let base = scroll(json!({"title": "Draft", "n": 1}));
+        let ours = scroll(json!({"title": "Ours", "n": 1}));
+        let theirs = scroll(json!({"title": "Theirs", "n": 2}));
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Ours);
+        assert_eq!(result.conflicts, vec!["/title".to_string()]);
+        assert_eq!(result.scroll.data, json!({"title": "Ours", "n": 2}));
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Theirs);
+        assert_eq!(result.scroll.data, json!({"title": "Theirs", "n": 2}));
+    }
+
+    #[test]
+    fn merge_custom_policy_sees_all_sides() {
+        let base = scroll(json!({"balance": 100}));
+        let ours = scroll(json!({"balance": 90}));
+        let theirs = scroll(json!({"balance": 120}));
+
+        // Apply both deltas relative to the ancestor
+        let policy = MergePolicy::custom(|c| {
+            let b = c.base.as_ref()?.as_i64()?;
+            let o = c.ours.as_ref()?.as_i64()?;
+            let t = c.theirs.as_ref()?.as_i64()?;
+            Some(json!(b + (o - b) + (t - b)))
+        });
+
+        let result = three_way(&base, &ours, &theirs, &policy);
+        assert_eq!(result.conflicts, vec!["/balance".to_string()]);
+        assert_eq!(result.scroll.data["balance"], 110);
+    }
+
+    #[test]
+    fn merge_identical_changes_converge() {
+        let base = scroll(json!({"status": "draft", "old": true}));
+        let ours = scroll(json!({"status": "published"}));
+        let theirs = scroll(json!({"status": "published"}));
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Theirs);
+        assert!(result.is_clean());
+        assert_eq!(result.scroll.data, json!({"status": "published"}));
+    }
+
+    #[test]
+    fn merge_nested_conflict_reports_outer_pointer() {
+        let base = scroll(json!({"settings": {"theme": "light"}}));
+        // Ours edits inside the object, theirs replaces it entirely
+        let ours = scroll(json!({"settings": {"theme": "dark"}}));
+        let theirs = scroll(json!({"settings": "reset"}));
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Ours);
+        assert_eq!(result.conflicts, vec!["/settings".to_string()]);
+        assert_eq!(result.scroll.data["settings"]["theme"], "dark");
+    }
+
+    #[test]
+    fn merge_nested_disjoint_keys_are_clean() {
+        let base = scroll(json!({"settings": {"theme": "light", "lang": "en"}}));
+        let ours = scroll(json!({"settings": {"theme": "dark", "lang": "en"}}));
+        let theirs = scroll(json!({"settings": {"theme": "light", "lang": "fr"}}));
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Ours);
+        assert!(result.is_clean());
+        assert_eq!(result.scroll.data, json!({"settings": {"theme": "dark", "lang": "fr"}}));
+    }
+
+    #[test]
+    fn merge_remove_vs_edit_conflict() {
+        let base = scroll(json!({"note": "hi", "keep": 1}));
+        let ours = scroll(json!({"keep": 1}));
+        let theirs = scroll(json!({"note": "hello", "keep": 1}));
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Ours);
+        assert_eq!(result.conflicts, vec!["/note".to_string()]);
+        assert!(result.scroll.data.get("note").is_none());
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Theirs);
+        assert_eq!(result.scroll.data["note"], "hello");
+    }
+
+    #[test]
+    fn merge_type_change_from_theirs_is_kept() {
+        let base = scroll(json!({})).set_type("doc@v1");
+        let ours = scroll(json!({"a": 1})).set_type("doc@v1");
+        let theirs = scroll(json!({})).set_type("doc@v2");
+
+        let result = three_way(&base, &ours, &theirs, &MergePolicy::Ours);
+        assert_eq!(result.scroll.type_, "doc@v2");
+        assert_eq!(result.scroll.data, json!({"a": 1}));
+    }
+}
@@ -0,0 +1,217 @@
+//! Migrate - Moving scrolls forward one `type_` version at a time
+//!
+//! When `wallet/payment@v1` becomes `@v2`, scrolls already on disk keep
+//! the old shape. A [`Migrations`] set holds one step per version bump;
+//! [`Store`](super::Store) runs them lazily on read or eagerly with
+//! `Store::migrate(prefix)`, and each upgrade lands in history as a patch
+//! tagged with the type change.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Readers cope with every old shape (branches everywhere)
+//! **Antithesis**: Rewrite the store offline (downtime, lost history)
+//! **Synthesis**: Small version steps, applied on contact, recorded as patches
+//!
+//! # Chained Steps
+//!
+//! ```text
+//! wallet/payment@v1 ──step(1)──► @v2 ──step(2)──► @v3   (latest)
+//! ```
+//!
+//! A scroll runs every step from its version up to the first version that
+//! has no step, so a v1 scroll reaches v3 in one read.
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! let mut migrations = Migrations::new();
+//! migrations.register("wallet/payment@v1", |mut data| {
+//!     // v2 renamed `amount` to `amount_sat`
+//!     let amount = data["amount"].take();
+//!     data["amount_sat"] = amount;
+//!     data.as_object_mut().ok_or("not an object")?.remove("amount");
+//!     Ok(data)
+//! })?;
+//!
+//! let store = Store::at(path, &key)?.with_migrations(Arc::new(migrations));
+//! store.migrate("/wallet/tx")?;  // or let reads upgrade as they go
+//! ```
+//!
+//! Steps are plain `Value -> Value` functions, so they can be tested
+//! against fixture JSON with [`Migrations::upgrade_data`] alone.
+
+use super::namespace::{Error, Result};
+use super::schema::parse_type;
+use super::scroll::Scroll;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// One version bump: data at `@vN` in, data at `@vN+1` out
+pub type Step = Arc<dyn Fn(Value) -> std::result::Result<Value, String> + Send + Sync>;
+
+/// Migration steps by type name and source version
+#[derive(Default, Clone)]
+pub struct Migrations {
+    /// name → from version → step to the next version
+    steps: BTreeMap<String, BTreeMap<u32, Step>>,
+}
+
+impl Migrations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the step from `from` (`domain/entity@vN`) to `@vN+1`
+    ///
+    /// # Errors
+    /// `InvalidData` if `from` is not a versioned type.
+    pub fn register(
+        &mut self,
+        from: &str,
+        step: impl Fn(Value) -> std::result::Result<Value, String> + Send + Sync + 'static,
+    ) -> Result<()> {
+        let (name, version) = parse_type(from)
+            .ok_or_else(|| Error::InvalidData(format!("not a versioned type: {}", from)))?;
+        self.steps
+            .entry(name.to_string())
+            .or_default()
+            .insert(version, Arc::new(step));
+        Ok(())
+    }
+
+    /// Whether no steps are registered
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The type a scroll of `type_` ends up as (itself if already current)
+    pub fn target(&self, type_: &str) -> String {
+        let Some((name, mut version)) = parse_type(type_) else {
+            return type_.to_string();
+        };
+        let Some(steps) = self.steps.get(name) else {
+            return type_.to_string();
+        };
+        while steps.contains_key(&version) {
+            version += 1;
+        }
+        format!("{}@v{}", name, version)
+    }
+
+    /// Whether reading this scroll would migrate it
+    pub fn applies_to(&self, scroll: &Scroll) -> bool {
+        !scroll.is_deleted() && self.target(&scroll.type_) != scroll.type_
+    }
+
+    /// Run the steps for `type_` over `data`
+    ///
+    /// # Returns
+    /// The final type and data; unchanged when no step applies.
+    ///
+    /// # Errors
+    /// `InvalidData` naming the step that failed.
+    pub fn upgrade_data(&self, type_: &str, mut data: Value) -> Result<(String, Value)> {
+        let Some((name, mut version)) = parse_type(type_) else {
+            return Ok((type_.to_string(), data));
+        };
+        let Some(steps) = self.steps.get(name) else {
+            return Ok((type_.to_string(), data));
+        };
+
+        while let Some(step) = steps.get(&version) {
+            data = step(data).map_err(|reason| {
+                Error::InvalidData(format!(
+                    "migrating {}@v{} to v{}: {}",
+                    name,
+                    version,
+                    version + 1,
+                    reason
+                ))
+            })?;
+            version += 1;
+        }
+
+        Ok((format!("{}@v{}", name, version), data))
+    }
+
+    /// Upgrade a scroll to the latest version of its type
+    ///
+    /// # Returns
+    /// `None` if the scroll is already current (or a tombstone). Metadata
+    /// is kept; the caller decides whether to write the result.
+    pub fn upgrade(&self, scroll: &Scroll) -> Result<Option<Scroll>> {
+        if !self.applies_to(scroll) {
+            return Ok(None);
+        }
+        let (type_, data) = self
+            .upgrade_data(&scroll.type_, scroll.data.clone())
+            .map_err(|e| match e {
+                Error::InvalidData(reason) => Error::InvalidData(format!("{}: {}", scroll.key, reason)),
+                other => other,
+            })?;
+        Ok(Some(scroll.clone().set_type(type_).set_data(data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// v1 `amount` → v2 `amount_sat` → v3 adds `fee_sat`
+    fn payment_migrations() -> Migrations {
+        let mut migrations = Migrations::new();
+        migrations
+            .register("test/payment@v1", |mut data| {
+                let object = data.as_object_mut().ok_or("not an object")?;
+                let amount = object.remove("amount").ok_or("missing amount")?;
+                object.insert("amount_sat".into(), amount);
+                Ok(data)
+            })
+            .unwrap();
+        migrations
+            .register("test/payment@v2", |mut data| {
+                data["fee_sat"] = json!(0);
+                Ok(data)
+            })
+            .unwrap();
+        migrations
+    }
+
+    #[test]
+    fn steps_chain_to_the_latest_version() {
+        let migrations = payment_migrations();
+
+        let fixtures = [
+            ("test/payment@v1", json!({"amount": 5}), json!({"amount_sat": 5, "fee_sat": 0})),
+            ("test/payment@v2", json!({"amount_sat": 7}), json!({"amount_sat": 7, "fee_sat": 0})),
+            ("test/payment@v3", json!({"amount_sat": 9, "fee_sat": 1}), json!({"amount_sat": 9, "fee_sat": 1})),
+        ];
+        for (type_, input, expected) in fixtures {
+            let (upgraded, data) = migrations.upgrade_data(type_, input).unwrap();
+            assert_eq!(upgraded, "test/payment@v3");
+            assert_eq!(data, expected);
+        }
+
+        assert_eq!(migrations.target("test/other@v1"), "test/other@v1");
+        assert_eq!(migrations.target("untyped"), "untyped");
+    }
+
+    #[test]
+    fn upgrade_keeps_metadata_and_reports_failures() {
+        let migrations = payment_migrations();
+
+        let mut scroll = Scroll::typed("/tx/a", json!({"amount": 5}), "test/payment@v1");
+        scroll.metadata.created_at = Some("2024-01-01T00:00:00Z".into());
+        let upgraded = migrations.upgrade(&scroll).unwrap().unwrap();
+        assert_eq!(upgraded.type_, "test/payment@v3");
+        assert_eq!(upgraded.metadata.created_at, scroll.metadata.created_at);
+        assert!(migrations.upgrade(&upgraded).unwrap().is_none());
+        assert!(migrations.upgrade(&scroll.clone().mark_deleted()).unwrap().is_none());
+
+        let broken = Scroll::typed("/tx/b", json!({"amt": 5}), "test/payment@v1");
+        let err = migrations.upgrade(&broken).unwrap_err();
+        assert!(matches!(err, Error::InvalidData(ref m) if m.contains("/tx/b") && m.contains("missing amount")));
+    }
+}
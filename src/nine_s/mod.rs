@@ -35,7 +35,11 @@
 //! - [`Scroll`]: Universal data envelope (key + JSON data + metadata)
 //! - [`Namespace`]: The 5-operation trait any backend implements
 //! - [`Kernel`]: Mount table for composing namespaces
+//! - [`Union`]: Layered namespaces at one mount point (`Kernel::bind`)
+//! - [`SchemaRegistry`]: Versioned schemas for `Scroll.type_`, `#[derive(ScrollType)]`
+//! - [`Migrations`]: Steps from one `type_` version to the next, run by [`Store`]
 //! - [`MemoryNamespace`]: In-memory backend with wildcard watch support
+//! - `AsyncNamespace` / `AsyncKernel`: The same five ops with `async fn` (`std-channel`)
 //!
 //! ## WASM Compatibility
 //!
@@ -45,10 +49,18 @@
 pub mod scroll;
 pub mod namespace;
 pub mod kernel;
+pub mod union;
+pub mod capability;
+pub mod intercept;
+pub mod schema;
+pub mod migrate;
 pub mod backends;
 pub mod store;
 pub mod patch;
 pub mod anchor;
+pub mod merge;
+pub mod index;
+pub mod audit;
 
 // Sealed scrolls for sharing (requires crypto feature)
 #[cfg(feature = "crypto")]
@@ -57,16 +69,29 @@ pub mod sealed;
 #[cfg(feature = "std-channel")]
 pub mod channel;
 
+#[cfg(feature = "std-channel")]
+pub mod async_namespace;
+
 pub use scroll::{Scroll, Metadata, Tense, current_iso_time, current_time_millis};
 pub use scroll::{types, kingdoms, verbs};
-pub use namespace::{Namespace, Error, Result, Receiver};
+pub use namespace::{Namespace, Error, Result, Receiver, Precondition};
 pub use kernel::Kernel;
-pub use store::Store;
+pub use union::{Bind, Union};
+pub use capability::{Ops, Policy, ScopedNamespace};
+pub use intercept::{InterceptedNamespace, Interceptor};
+pub use schema::{Schema, SchemaRegistry, ScrollType};
+pub use migrate::Migrations;
+pub use store::{Store, Snapshot};
+#[cfg(feature = "crypto")]
+pub use store::{BackupReport, BackupScope, RotationReport, Transaction};
 pub use backends::memory::MemoryNamespace;
 
 // Git-like primitives
-pub use patch::{Patch, PatchOp, PatchError};
+pub use patch::{Patch, PatchOp, PatchError, TypeChange};
 pub use anchor::Anchor;
+pub use merge::{MergePolicy, MergeResult};
+pub use index::{IndexField, IndexSpec, Query, QueryPage};
+pub use audit::{IntegrityIssue, IntegrityReport};
 
 // Sealed scrolls for sharing (requires crypto feature)
 #[cfg(feature = "crypto")]
@@ -77,3 +102,5 @@ pub use backends::file::FileNamespace;
 
 #[cfg(feature = "std-channel")]
 pub use backends::memory::WatchReceiver;
+#[cfg(feature = "std-channel")]
+pub use async_namespace::{AsyncKernel, AsyncNamespace, ScrollStream};
@@ -53,10 +53,72 @@ pub enum Error {
 
     #[error("internal error: {0}")]
     Internal(String),
+
+    /// A conditional write's precondition did not hold
+    #[error("conflict: {0}")]
+    Conflict(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Expected current state for a conditional (compare-and-swap) write
+///
+/// Backends expose `write_if` / `write_scroll_if` alongside the five
+/// operations. The write only lands if the scroll currently at the path
+/// satisfies the precondition; otherwise it fails with `Error::Conflict`
+/// and nothing is written.
+///
+/// # Example
+/// ```rust,ignore
+/// let current = ns.read("/doc")?.unwrap();
+/// ns.write_if("/doc", json!({"v": 2}), &Precondition::Hash(current.metadata.hash.unwrap()))?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Precondition {
+    /// Nothing may exist at the path yet (create-only)
+    Absent,
+    /// The current scroll must be at exactly this version
+    Version(u64),
+    /// The current scroll's `metadata.hash` must equal this
+    Hash(String),
+}
+
+impl Precondition {
+    /// Check the precondition against the scroll currently at `path`
+    pub fn check(&self, path: &str, current: Option<&Scroll>) -> Result<()> {
+        let holds = match (self, current) {
+            (Precondition::Absent, None) => true,
+            (Precondition::Absent, Some(_)) => false,
+            (_, None) => false,
+            (Precondition::Version(v), Some(s)) => s.metadata.version == *v,
+            (Precondition::Hash(h), Some(s)) => s.metadata.hash.as_deref() == Some(h.as_str()),
+        };
+
+        if holds {
+            return Ok(());
+        }
+
+        let found = match current {
+            None => "nothing".to_string(),
+            Some(s) => format!(
+                "version {} (hash {})",
+                s.metadata.version,
+                s.metadata.hash.as_deref().unwrap_or("none")
+            ),
+        };
+        let expected = match self {
+            Precondition::Absent => "nothing".to_string(),
+            Precondition::Version(v) => format!("version {}", v),
+            Precondition::Hash(h) => format!("hash {}", h),
+        };
+
+        Err(Error::Conflict(format!(
+            "{}: expected {}, found {}",
+            path, expected, found
+        )))
+    }
+}
+
 /// Namespace - The 5 frozen operations
 ///
 /// All functionality in 9S emerges from these five operations.
@@ -78,6 +140,10 @@ pub trait Namespace: Send + Sync {
     ///
     /// The schema in scroll.meta is preserved.
     /// Hash, version, and time are computed by the namespace.
+    ///
+    /// A scroll marked deleted (`Scroll::mark_deleted`) is a tombstone:
+    /// backends that support deletion remove the path, `read` and `list`
+    /// stop returning it, and watchers receive the tombstone.
     fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
         // Default implementation - can be overridden
         self.write(&scroll.key, scroll.data)
@@ -143,6 +209,17 @@ pub fn validate_path(path: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validate a path and return its canonical form
+///
+/// Backends map `/a//b` and `/a/b/` to the same entry as `/a/b`, so code
+/// that matches globs against a caller's path must match this form and
+/// pass it on, or `//a/b` slips past a rule written for `/a/b`.
+pub fn canonical_path(path: &str) -> Result<String> {
+    validate_path(path)?;
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    Ok(format!("/{}", segments.join("/")))
+}
+
 /// Check if a path matches a pattern (supports * and **)
 pub fn path_matches(path: &str, pattern: &str) -> bool {
     // Exact match
@@ -212,6 +289,45 @@ mod tests {
         assert!(validate_path("/foo/.hidden").is_ok(), "Hidden file names allowed");
     }
 
+    #[test]
+    fn canonical_path_collapses_empty_segments() {
+        assert_eq!(canonical_path("/").unwrap(), "/");
+        assert_eq!(canonical_path("//vault/seed").unwrap(), "/vault/seed");
+        assert_eq!(canonical_path("/wallet//send/").unwrap(), "/wallet/send");
+        assert_eq!(canonical_path("/wallet/**").unwrap(), "/wallet/**");
+        assert!(canonical_path("//../etc").is_err());
+    }
+
+    #[test]
+    fn precondition_check() {
+        use serde_json::json;
+
+        let mut scroll = Scroll::new("/doc", json!({"v": 1}));
+        scroll.metadata.version = 3;
+        scroll.metadata.hash = Some("abc".to_string());
+
+        assert!(Precondition::Absent.check("/doc", None).is_ok());
+        assert!(Precondition::Version(3).check("/doc", Some(&scroll)).is_ok());
+        assert!(Precondition::Hash("abc".into()).check("/doc", Some(&scroll)).is_ok());
+
+        assert!(matches!(
+            Precondition::Absent.check("/doc", Some(&scroll)),
+            Err(Error::Conflict(_))
+        ));
+        assert!(matches!(
+            Precondition::Version(2).check("/doc", Some(&scroll)),
+            Err(Error::Conflict(_))
+        ));
+        assert!(matches!(
+            Precondition::Hash("def".into()).check("/doc", Some(&scroll)),
+            Err(Error::Conflict(_))
+        ));
+        assert!(matches!(
+            Precondition::Version(1).check("/doc", None),
+            Err(Error::Conflict(_))
+        ));
+    }
+
     #[test]
     fn path_matches_exact() {
         assert!(path_matches("/foo", "/foo"));
@@ -63,6 +63,19 @@ pub struct Patch {
     pub timestamp: i64,
     /// Patch sequence number (for ordering)
     pub seq: u64,
+    /// True if this patch deletes the scroll (a tombstone)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+    /// Set when a schema migration wrote this patch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migration: Option<TypeChange>,
+}
+
+/// The `type_` a migration patch moved a scroll between
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TypeChange {
+    pub from: String,
+    pub to: String,
 }
 
 /// Patch errors
@@ -111,6 +124,27 @@ pub mod diff {
             hash: hash(new),
             timestamp: current_time_millis(),
             seq,
+            deleted: false,
+            migration: None,
+        }
+    }
+
+    /// Compute a tombstone patch deleting `old`
+    ///
+    /// The data becomes `null` and the patch is flagged `deleted`, so
+    /// replaying history yields a scroll marked deleted.
+    pub fn delete(key: &str, old: &Scroll) -> Patch {
+        let tombstone = Scroll::typed(key, Value::Null, old.type_.clone()).mark_deleted();
+
+        Patch {
+            key: key.to_string(),
+            ops: compute_ops(Some(&old.data), &Value::Null),
+            parent: Some(hash(old)),
+            hash: hash(&tombstone),
+            timestamp: current_time_millis(),
+            seq: old.metadata.version + 1,
+            deleted: true,
+            migration: None,
         }
     }
 
@@ -125,8 +159,14 @@ pub mod diff {
         }
 
         let mut result = Scroll::new(&scroll.key, new_data);
-        result.type_ = scroll.type_.clone();
+        result.type_ = match &patch.migration {
+            Some(change) => change.to.clone(),
+            None => scroll.type_.clone(),
+        };
         result.metadata.version = patch.seq;
+        if patch.deleted {
+            result.metadata.deleted = Some(true);
+        }
 
         Ok(result)
     }
@@ -443,6 +483,25 @@ mod tests {
         assert!(!patch.ops.is_empty());
     }
 
+    #[test]
+    fn test_delete_patch_is_tombstone() {
+        let mut old = Scroll::new("/test", json!({"title": "Hello"}));
+        old.metadata.version = 3;
+        let patch = diff::delete("/test", &old);
+
+        assert!(patch.deleted);
+        assert_eq!(patch.seq, 4);
+        assert_eq!(patch.parent, Some(diff::hash(&old)));
+
+        let result = diff::apply(&old, &patch).unwrap();
+        assert!(result.is_deleted());
+        assert!(result.data.is_null());
+
+        // Ordinary patches don't serialize the flag
+        let json = serde_json::to_value(diff::create("/test", None, &old)).unwrap();
+        assert!(json.get("deleted").is_none());
+    }
+
     #[test]
     fn test_create_patch_add_field() {
         let old = Scroll::new("/test", json!({"title": "Hello"}));
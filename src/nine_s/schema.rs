@@ -0,0 +1,409 @@
+//! Schema - What a Scroll `type_` promises about its data
+//!
+//! `type_` has always been a hint ("domain/entity@version"). The registry
+//! makes it a contract: each versioned type gets a schema, writes can be
+//! checked against it, and scrolls decode into Rust structs.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Data is opaque JSON (flexible, every reader guesses)
+//! **Antithesis**: One Rust type per path (rigid, breaks the 5 ops)
+//! **Synthesis**: Opaque to the Kernel, checked at the edges by `type_`
+//!
+//! # Two Kinds of Schema
+//!
+//! - **Field specs**: `Schema::new("wallet/balance@v1").required("confirmed", Kind::U64)`
+//! - **Rust types**: `Schema::of::<Payment>()`, valid when the data
+//!   deserializes into `Payment`
+//!
+//! # Typed Scrolls
+//!
+//! ```rust,ignore
+//! use beewallet_core_spark::nine_s::schema::ScrollType;
+//!
+//! #[derive(Serialize, Deserialize, ScrollType)]
+//! #[scroll(type = "wallet/payment@v1")]
+//! struct Payment { txid: String, amount_sat: u64 }
+//!
+//! let scroll = Scroll::encode("/wallet/tx/abc", &payment)?;  // type_ set for you
+//! let payment: Payment = scroll.decode()?;                     // type_ checked
+//! ```
+//!
+//! # Validating Writes
+//!
+//! ```rust,ignore
+//! let mut registry = SchemaRegistry::new();
+//! registry.register_type::<Payment>()?;
+//!
+//! let ns = InterceptedNamespace::new(kernel)
+//!     .with(Arc::new(Validate::schemas("/**", Arc::new(registry))));
+//! ```
+
+use super::namespace::{Error, Result};
+use super::scroll::Scroll;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+pub use beewallet_core_spark_derive::ScrollType;
+
+/// Split `domain/entity@vN` into its name and version
+///
+/// # Returns
+/// `None` unless the string ends in `@v` followed by digits.
+pub fn parse_type(type_: &str) -> Option<(&str, u32)> {
+    let (name, version) = type_.rsplit_once("@v")?;
+    if name.is_empty() || version.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((name, version.parse().ok()?))
+}
+
+// ============================================================================
+// Typed Scrolls
+// ============================================================================
+
+/// A Rust type that is the payload of one Scroll `type_`
+///
+/// Usually derived: `#[derive(ScrollType)] #[scroll(type = "wallet/payment@v1")]`.
+pub trait ScrollType: Serialize + DeserializeOwned {
+    /// Full type string, `domain/entity@vN`
+    const TYPE: &'static str;
+}
+
+impl Scroll {
+    /// Build a scroll of `T::TYPE` carrying `value`
+    pub fn encode<T: ScrollType>(key: impl Into<String>, value: &T) -> Result<Scroll> {
+        let data = serde_json::to_value(value)
+            .map_err(|e| Error::InvalidData(format!("Failed to encode {}: {}", T::TYPE, e)))?;
+        Ok(Scroll::typed(key, data, T::TYPE))
+    }
+
+    /// Decode the data into `T`, which must match the scroll's `type_`
+    pub fn decode<T: ScrollType>(&self) -> Result<T> {
+        if self.type_ != T::TYPE {
+            return Err(Error::InvalidData(format!(
+                "{} is {}, not {}",
+                self.key,
+                self.type_,
+                T::TYPE
+            )));
+        }
+        serde_json::from_value(self.data.clone())
+            .map_err(|e| Error::InvalidData(format!("{} ({}): {}", self.key, self.type_, e)))
+    }
+}
+
+// ============================================================================
+// Schemas
+// ============================================================================
+
+/// JSON shape of a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    String,
+    /// Non-negative integer (amounts in sats, counts)
+    U64,
+    I64,
+    /// Any JSON number
+    Number,
+    Bool,
+    Object,
+    Array,
+    /// Anything but null
+    Any,
+}
+
+impl Kind {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Kind::String => value.is_string(),
+            Kind::U64 => value.is_u64(),
+            Kind::I64 => value.is_i64() || value.is_u64() && value.as_i64().is_some(),
+            Kind::Number => value.is_number(),
+            Kind::Bool => value.is_boolean(),
+            Kind::Object => value.is_object(),
+            Kind::Array => value.is_array(),
+            Kind::Any => !value.is_null(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    kind: Kind,
+    required: bool,
+}
+
+enum Check {
+    Fields { fields: Vec<Field>, closed: bool },
+    Typed(fn(&Value) -> std::result::Result<(), String>),
+}
+
+/// The schema of one versioned type
+pub struct Schema {
+    type_: String,
+    check: Check,
+}
+
+impl Schema {
+    /// A field-spec schema; add fields with `required` / `optional`
+    pub fn new(type_: impl Into<String>) -> Self {
+        Self {
+            type_: type_.into(),
+            check: Check::Fields {
+                fields: Vec::new(),
+                closed: false,
+            },
+        }
+    }
+
+    /// A schema satisfied by data that deserializes into `T`
+    pub fn of<T: ScrollType>() -> Self {
+        fn check<T: ScrollType>(data: &Value) -> std::result::Result<(), String> {
+            T::deserialize(data).map(|_| ()).map_err(|e| e.to_string())
+        }
+
+        Self {
+            type_: T::TYPE.to_string(),
+            check: Check::Typed(check::<T>),
+        }
+    }
+
+    /// Require a field of the given kind
+    pub fn required(self, name: impl Into<String>, kind: Kind) -> Self {
+        self.field(name.into(), kind, true)
+    }
+
+    /// Allow a field; when present (and not null) it must be of `kind`
+    pub fn optional(self, name: impl Into<String>, kind: Kind) -> Self {
+        self.field(name.into(), kind, false)
+    }
+
+    /// Reject fields the schema does not list
+    pub fn closed(mut self) -> Self {
+        if let Check::Fields { closed, .. } = &mut self.check {
+            *closed = true;
+        }
+        self
+    }
+
+    fn field(mut self, name: String, kind: Kind, required: bool) -> Self {
+        if let Check::Fields { fields, .. } = &mut self.check {
+            fields.push(Field { name, kind, required });
+        }
+        self
+    }
+
+    /// The versioned type this schema describes
+    pub fn type_(&self) -> &str {
+        &self.type_
+    }
+
+    /// Check data against the schema
+    pub fn validate(&self, data: &Value) -> Result<()> {
+        let outcome = match &self.check {
+            Check::Typed(check) => check(data),
+            Check::Fields { fields, closed } => check_fields(fields, *closed, data),
+        };
+        outcome.map_err(|reason| Error::InvalidData(format!("{}: {}", self.type_, reason)))
+    }
+}
+
+fn check_fields(fields: &[Field], closed: bool, data: &Value) -> std::result::Result<(), String> {
+    let object = data.as_object().ok_or("data must be an object")?;
+
+    for field in fields {
+        match object.get(&field.name) {
+            None | Some(Value::Null) if field.required => {
+                return Err(format!("missing field '{}'", field.name));
+            }
+            Some(value) if !value.is_null() && !field.kind.matches(value) => {
+                return Err(format!("field '{}' must be {:?}", field.name, field.kind));
+            }
+            _ => {}
+        }
+    }
+
+    if closed {
+        if let Some(extra) = object.keys().find(|k| !fields.iter().any(|f| &f.name == *k)) {
+            return Err(format!("unknown field '{}'", extra));
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// Schemas by name and version
+///
+/// Build it once at startup and share it (`Arc<SchemaRegistry>`).
+#[derive(Default)]
+pub struct SchemaRegistry {
+    /// name → version → schema
+    schemas: BTreeMap<String, BTreeMap<u32, Schema>>,
+    /// Reject scrolls whose type is not registered
+    strict: bool,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treat unregistered types as invalid instead of unchecked
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Register a schema, replacing any for the same version
+    ///
+    /// # Errors
+    /// `InvalidData` if the schema's type is not `domain/entity@vN`.
+    pub fn register(&mut self, schema: Schema) -> Result<()> {
+        let (name, version) = parse_type(&schema.type_).ok_or_else(|| {
+            Error::InvalidData(format!("not a versioned type: {}", schema.type_))
+        })?;
+        self.schemas
+            .entry(name.to_string())
+            .or_default()
+            .insert(version, schema);
+        Ok(())
+    }
+
+    /// Register the schema of a Rust type
+    pub fn register_type<T: ScrollType>(&mut self) -> Result<()> {
+        self.register(Schema::of::<T>())
+    }
+
+    /// Schema for a full type string
+    pub fn get(&self, type_: &str) -> Option<&Schema> {
+        let (name, version) = parse_type(type_)?;
+        self.schemas.get(name)?.get(&version)
+    }
+
+    /// Registered versions of a type name, oldest first
+    pub fn versions(&self, name: &str) -> Vec<u32> {
+        self.schemas
+            .get(name)
+            .map(|v| v.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Newest schema of a type name
+    pub fn latest(&self, name: &str) -> Option<&Schema> {
+        self.schemas.get(name)?.values().next_back()
+    }
+
+    /// Check a scroll against the schema of its `type_`
+    ///
+    /// Tombstones pass, as do unregistered types unless the registry is strict.
+    pub fn validate(&self, scroll: &Scroll) -> Result<()> {
+        if scroll.is_deleted() {
+            return Ok(());
+        }
+        match self.get(&scroll.type_) {
+            Some(schema) => schema.validate(&scroll.data),
+            None if self.strict => Err(Error::InvalidData(format!(
+                "unregistered type: {}",
+                scroll.type_
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, ScrollType)]
+    #[scroll(type = "test/payment@v1")]
+    struct Payment {
+        txid: String,
+        amount_sat: u64,
+        #[serde(default)]
+        memo: Option<String>,
+    }
+
+    fn balance_v1() -> Schema {
+        Schema::new("test/balance@v1")
+            .required("confirmed", Kind::U64)
+            .optional("pending", Kind::U64)
+    }
+
+    #[test]
+    fn parse_type_splits_name_and_version() {
+        assert_eq!(parse_type("wallet/payment@v12"), Some(("wallet/payment", 12)));
+        assert_eq!(parse_type("wallet/payment"), None);
+        assert_eq!(parse_type("wallet/payment@vx"), None);
+        assert_eq!(parse_type("@v1"), None);
+    }
+
+    #[test]
+    fn derive_round_trips_through_scroll() {
+        let payment = Payment {
+            txid: "abc".into(),
+            amount_sat: 2100,
+            memo: None,
+        };
+
+        let scroll = Scroll::encode("/wallet/tx/abc", &payment).unwrap();
+        assert_eq!(scroll.type_, "test/payment@v1");
+        assert_eq!(scroll.decode::<Payment>().unwrap(), payment);
+
+        let wrong = Scroll::typed("/x", scroll.data.clone(), "test/other@v1");
+        assert!(matches!(wrong.decode::<Payment>(), Err(Error::InvalidData(_))));
+    }
+
+    #[test]
+    fn field_schemas_check_presence_and_kind() {
+        let schema = balance_v1();
+        schema.validate(&json!({"confirmed": 10})).unwrap();
+        schema.validate(&json!({"confirmed": 10, "pending": null, "extra": 1})).unwrap();
+
+        assert!(schema.validate(&json!({"pending": 1})).is_err());
+        assert!(schema.validate(&json!({"confirmed": "10"})).is_err());
+        assert!(schema.validate(&json!({"confirmed": -1})).is_err());
+        assert!(schema.validate(&json!([1])).is_err());
+
+        let closed = balance_v1().closed();
+        let err = closed.validate(&json!({"confirmed": 1, "amountSat": 1})).unwrap_err();
+        assert!(err.to_string().contains("amountSat"));
+    }
+
+    #[test]
+    fn registry_validates_by_declared_type() {
+        let mut registry = SchemaRegistry::new();
+        registry.register(balance_v1()).unwrap();
+        registry
+            .register(Schema::new("test/balance@v2").required("total", Kind::U64))
+            .unwrap();
+        registry.register_type::<Payment>().unwrap();
+        assert!(registry.register(Schema::new("unversioned")).is_err());
+
+        assert_eq!(registry.versions("test/balance"), vec![1, 2]);
+        assert_eq!(registry.latest("test/balance").unwrap().type_(), "test/balance@v2");
+
+        let ok = Scroll::typed("/b", json!({"confirmed": 1}), "test/balance@v1");
+        let stale = Scroll::typed("/b", json!({"confirmed": 1}), "test/balance@v2");
+        let bad_payment = Scroll::typed("/p", json!({"txid": "a", "amount": 5}), "test/payment@v1");
+        registry.validate(&ok).unwrap();
+        assert!(registry.validate(&stale).is_err());
+        assert!(registry.validate(&bad_payment).is_err());
+
+        // Unregistered types pass unless strict; tombstones always pass
+        let generic = Scroll::new("/g", json!(1));
+        registry.validate(&generic).unwrap();
+        registry.validate(&bad_payment.clone().mark_deleted()).unwrap();
+        assert!(SchemaRegistry::new().strict().validate(&generic).is_err());
+    }
+}
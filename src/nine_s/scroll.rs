@@ -271,6 +271,22 @@ impl Scroll {
         self.metadata.deleted.unwrap_or(false)
     }
 
+    /// Check if the scroll's TTL (`expires_at`, Unix millis) has passed
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(current_time_millis())
+    }
+
+    /// Check expiry against a given time (Unix millis)
+    ///
+    /// A missing or unparseable `expires_at` never expires.
+    pub fn is_expired_at(&self, now_millis: i64) -> bool {
+        self.metadata
+            .expires_at
+            .as_deref()
+            .and_then(|t| t.parse::<i64>().ok())
+            .is_some_and(|expires| expires <= now_millis)
+    }
+
     // ========================================================================
     // Data field accessors (convenience for common patterns)
     // ========================================================================
@@ -542,4 +558,15 @@ mod tests {
         assert_eq!(scroll.metadata.extensions.get("pinned"), Some(&json!(true)));
         assert_eq!(scroll.metadata.extensions.get("folder"), Some(&json!("work")));
     }
+
+    #[test]
+    fn scroll_expiry() {
+        let scroll = Scroll::new("/invoice/1", json!({})).with_expires_at("1000");
+        assert!(!scroll.is_expired_at(999));
+        assert!(scroll.is_expired_at(1000));
+        assert!(scroll.is_expired());
+
+        assert!(!Scroll::new("/a", json!({})).is_expired());
+        assert!(!Scroll::new("/a", json!({})).with_expires_at("soon").is_expired());
+    }
 }
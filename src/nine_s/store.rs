@@ -22,7 +22,12 @@
 //! ~/.nine_s/
 //!   beewallet/         <- Store::open("beewallet", &master_key)
 //!     _scrolls/          (encrypted AES-256-GCM)
-//!     _history/          (plaintext patches for audit)
+//!     _history/          (encrypted patches + anchors for audit)
+//!     _journal/          (sealed write-ahead journal, empty when idle)
+//!     _index/            (encrypted secondary indexes)
+//!     _manifest/         (encrypted path list, only with path blinding)
+//!     _audit/            (encrypted, hash-chained log of every patch)
+//!     _rekey/            (re-sealed copies during key rotation only)
 //!   nostr-client/      <- Store::open("nostr-client", &master_key)
 //!     _scrolls/          (encrypted with DIFFERENT derived key)
 //!     _history/
@@ -53,10 +58,30 @@
 //! ## Auditable History
 //!
 //! Every write automatically creates a patch in `_history/`. Patches are
-//! computed on plaintext for meaningful diffs. This provides:
+//! computed on plaintext for meaningful diffs, then sealed before they touch
+//! disk. This provides:
 //! - Full audit trail of all changes
 //! - Ability to anchor (checkpoint) important states
 //! - Restore to any previous anchor
+//! - Point-in-time snapshots of a whole prefix ([`Store::snapshot_at`])
+//! - Portable encrypted backups, validated on import ([`Store::export_backup`])
+//! - Tombstone patches for deletions; [`Store::purge`] erases a path entirely
+//!
+//! History is sealed with a subkey derived from the store key
+//! (`HKDF(store_key, "_history")`), so a patch file can never be swapped
+//! in for a scroll file and decrypt. Stores created before history was
+//! encrypted can be upgraded in place with [`Store::migrate_history`].
+//!
+//! Every patch is also appended to a store-wide audit log whose entries
+//! chain by hash, so a deleted, swapped or rewritten patch file is caught
+//! by [`Store::verify_integrity`].
+//!
+//! Stores given [`Migrations`](super::migrate::Migrations) upgrade old
+//! `type_` versions as they are read, or all at once with [`Store::migrate`];
+//! each upgrade is a history patch carrying the type change.
+//!
+//! File names still spell out scroll paths unless the store is switched to
+//! blinded names with [`Store::enable_path_blinding`].
 //!
 //! # Usage
 //!
@@ -101,17 +126,83 @@
 //! ```
 
 use super::anchor::{self, Anchor};
-use super::backends::file::FileNamespace;
-use super::namespace::{Error, Namespace, Receiver, Result};
+use super::backends::file::{shred_file, FileNamespace};
+use super::namespace::{validate_path, Error, Namespace, Receiver, Result};
 use super::patch::{self, Patch};
 use super::scroll::Scroll;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+#[cfg(feature = "crypto")]
+use super::audit::{self, AuditAction, AuditEntry, AuditHead, IntegrityIssue, IntegrityReport};
+#[cfg(feature = "crypto")]
+use super::index::{self, Index, IndexEntry, IndexSpec, Query, QueryPage};
+#[cfg(feature = "crypto")]
+use super::merge::{self, MergePolicy, MergeResult};
+#[cfg(feature = "crypto")]
+use super::migrate::Migrations;
+#[cfg(feature = "crypto")]
+use super::namespace::{path_matches, Precondition};
 #[cfg(feature = "crypto")]
 use crate::vault::crypto::{seal, unseal, SealedValue};
+#[cfg(feature = "crypto")]
+use serde::Deserialize;
+#[cfg(feature = "crypto")]
+use std::collections::{BTreeSet, HashMap, HashSet};
+#[cfg(feature = "crypto")]
+use std::sync::{Arc, RwLock};
+
+/// HKDF info string for the history subkey
+#[cfg(feature = "crypto")]
+const HISTORY_KEY_INFO: &str = "_history";
+
+/// HKDF info string for the index subkey
+#[cfg(feature = "crypto")]
+const INDEX_KEY_INFO: &str = "_index";
+
+/// HKDF info string for the path manifest subkey
+#[cfg(feature = "crypto")]
+const MANIFEST_KEY_INFO: &str = "_manifest";
+
+/// HKDF info string for the audit log subkey
+#[cfg(feature = "crypto")]
+const AUDIT_KEY_INFO: &str = "_audit";
+
+/// File in an index's directory holding its sealed spec
+#[cfg(feature = "crypto")]
+const INDEX_SPEC_FILE: &str = "spec.json";
+
+/// Lock serializing appends to the audit log
+#[cfg(feature = "crypto")]
+const AUDIT_LOCK_PATH: &str = "/_audit";
+
+/// Directories of sealed records, with the HKDF info of their subkey
+#[cfg(feature = "crypto")]
+const SEALED_RECORD_DIRS: [(&str, &str); 4] = [
+    ("_history", HISTORY_KEY_INFO),
+    ("_index", INDEX_KEY_INFO),
+    ("_manifest", MANIFEST_KEY_INFO),
+    ("_audit", AUDIT_KEY_INFO),
+];
+
+/// HKDF info string for the backup archive subkey
+#[cfg(feature = "crypto")]
+const BACKUP_KEY_INFO: &str = "_backup";
+
+/// HKDF info string for key fingerprints in the rotation marker
+#[cfg(feature = "crypto")]
+const KEY_ID_INFO: &str = "_key-id";
+
+/// Backup archive format tag and version
+#[cfg(feature = "crypto")]
+const BACKUP_FORMAT: &str = "nine-s/backup";
+#[cfg(feature = "crypto")]
+const BACKUP_VERSION: u32 = 1;
 
 /// Statistics about a scroll's history
 ///
@@ -172,6 +263,8 @@ pub struct MaintenanceReport {
     pub hive_events_pruned: usize,
     /// Number of wallet history patches compacted
     pub wallet_patches_compacted: usize,
+    /// Number of expired scrolls swept
+    pub scrolls_expired: usize,
 }
 
 impl MaintenanceReport {
@@ -181,7 +274,266 @@ impl MaintenanceReport {
             + self.ln_events_pruned
             + self.hive_events_pruned
             + self.wallet_patches_compacted
+            + self.scrolls_expired
+    }
+}
+
+/// A write that has been versioned, sealed and diffed but not yet applied
+///
+/// This is also the journal entry format: replaying a `StagedWrite` is
+/// idempotent, so recovery can re-apply one that partially landed.
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StagedWrite {
+    /// Versioned plaintext scroll (returned to the caller)
+    scroll: Scroll,
+    /// Sealed scroll as stored in `_scrolls/` (Null when unencrypted)
+    payload: Value,
+    /// History patch for this write
+    patch: Patch,
+}
+
+/// An atomic multi-scroll transaction on a [`Store`]
+///
+/// Created by [`Store::begin`]. Writes are only staged until `commit()`;
+/// dropping the transaction (or calling `abort()`) discards them.
+///
+/// Transactions guarantee atomicity across crashes, not isolation:
+/// concurrent writers to the same path are still last-write-wins.
+#[cfg(feature = "crypto")]
+pub struct Transaction<'a> {
+    store: &'a Store,
+    staged: Vec<Scroll>,
+}
+
+#[cfg(feature = "crypto")]
+impl Transaction<'_> {
+    /// Stage a write of `data` at `path`
+    pub fn write(&mut self, path: &str, data: Value) -> Result<()> {
+        self.write_scroll(Scroll::new(path, data))
+    }
+
+    /// Stage a full scroll (preserves type and metadata)
+    pub fn write_scroll(&mut self, scroll: Scroll) -> Result<()> {
+        validate_path(&scroll.key)?;
+        self.staged.push(scroll);
+        Ok(())
+    }
+
+    /// Number of staged writes
+    pub fn len(&self) -> usize {
+        self.staged.len()
+    }
+
+    /// Check if nothing has been staged
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /// Atomically apply every staged write
+    ///
+    /// Returns the written scrolls in staging order.
+    pub fn commit(self) -> Result<Vec<Scroll>> {
+        self.store.commit_staged(self.staged)
+    }
+
+    /// Discard every staged write
+    pub fn abort(self) {}
+}
+
+/// A read-only view of every scroll under a prefix at a point in time
+///
+/// Created by [`Store::snapshot_at`]. Implements [`Namespace`] so it can be
+/// read, listed or mounted like any other backend; writes fail with
+/// `Error::Permission` and watch with `Error::Unavailable`.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    prefix: String,
+    timestamp: i64,
+    scrolls: BTreeMap<String, Scroll>,
+}
+
+impl Snapshot {
+    /// Prefix the snapshot was taken over
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Point in time (unix millis) the snapshot reflects
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Number of scrolls that existed at that time
+    pub fn len(&self) -> usize {
+        self.scrolls.len()
+    }
+
+    /// Check if no scroll under the prefix existed yet
+    pub fn is_empty(&self) -> bool {
+        self.scrolls.is_empty()
+    }
+
+    /// Iterate scrolls in path order
+    pub fn scrolls(&self) -> impl Iterator<Item = &Scroll> {
+        self.scrolls.values()
+    }
+}
+
+impl Namespace for Snapshot {
+    fn read(&self, path: &str) -> Result<Option<Scroll>> {
+        Ok(self.scrolls.get(path).cloned())
+    }
+
+    fn write(&self, path: &str, _data: Value) -> Result<Scroll> {
+        Err(Error::Permission(format!("snapshot is read-only: {}", path)))
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .scrolls
+            .keys()
+            .filter(|k| is_path_under_prefix(k, prefix))
+            .cloned()
+            .collect())
     }
+
+    fn watch(&self, _pattern: &str) -> Result<Receiver<Scroll>> {
+        Err(Error::Unavailable("snapshots never change".to_string()))
+    }
+
+    fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// What a backup archive carries
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackupScope {
+    /// Current scrolls only; history restarts at the restored state
+    ScrollsOnly,
+    /// Scrolls plus every patch and anchor under `_history/`
+    FullHistory,
+}
+
+/// Summary of a completed export or import
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupReport {
+    /// Scope of the archive
+    pub scope: BackupScope,
+    /// Number of scrolls
+    pub scrolls: usize,
+    /// Number of history patches
+    pub patches: usize,
+    /// Number of anchors
+    pub anchors: usize,
+}
+
+/// Cleartext archive header (repeated inside the sealed payload)
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupHeader {
+    format: String,
+    version: u32,
+    scope: BackupScope,
+    created_at: i64,
+}
+
+/// On-disk archive: header + sealed payload
+#[cfg(feature = "crypto")]
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupArchive {
+    #[serde(flatten)]
+    header: BackupHeader,
+    sealed: SealedValue,
+}
+
+/// Sealed archive contents
+#[cfg(feature = "crypto")]
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupPayload {
+    header: BackupHeader,
+    scrolls: Vec<Scroll>,
+    history: Vec<BackupHistory>,
+}
+
+/// History of one scroll path inside an archive
+#[cfg(feature = "crypto")]
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupHistory {
+    path: String,
+    patches: Vec<Patch>,
+    anchors: Vec<Anchor>,
+}
+
+/// Summary of a completed key rotation
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RotationReport {
+    /// Scroll files re-sealed
+    pub scrolls: usize,
+    /// Sealed records (patches, anchors, indexes, manifest) re-sealed
+    pub records: usize,
+    /// Files already staged by an interrupted rotation and reused
+    pub resumed: usize,
+}
+
+/// Progress marker for an in-flight rotation (`_rotation.json`)
+///
+/// Holds key fingerprints only, never key material.
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RotationMarker {
+    phase: RotationPhase,
+    old_key_id: String,
+    new_key_id: String,
+}
+
+/// Rotation phase; `Swapping` is the commit point
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum RotationPhase {
+    /// Re-sealed copies are being written to `_rekey/`; originals untouched
+    Staging,
+    /// Every copy verified; `_rekey/` is being renamed over the originals
+    Swapping,
+}
+
+/// An import in flight (`_import.json`); removing it is the commit point
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportMarker {
+    /// Audit log position before the import (0 for an empty log)
+    audit_seq: u64,
+}
+
+/// Sealed list of scroll paths for a blinded store (`_manifest/paths.json`)
+#[cfg(feature = "crypto")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    /// Random HMAC key for on-disk names (hex), independent of the store
+    /// key so rotation only re-seals the manifest
+    name_key: String,
+    /// False until every file has moved to its blinded name
+    migrated: bool,
+    /// Every scroll path with a file or history on disk
+    paths: BTreeSet<String>,
+}
+
+/// Path blinding state of an open store
+#[cfg(feature = "crypto")]
+struct Blinding {
+    name_key: [u8; 32],
+    /// On-disk name -> scroll path, for paths known to be in the manifest
+    names: Arc<RwLock<HashMap<String, String>>>,
 }
 
 /// Store - Secure, encrypted namespace storage (Bitcoin 9S)
@@ -209,6 +561,10 @@ pub struct Store {
     app_key: Option<String>,
     #[cfg(feature = "crypto")]
     encryption_key: Option<[u8; 32]>,
+    #[cfg(feature = "crypto")]
+    blinding: Option<Blinding>,
+    #[cfg(feature = "crypto")]
+    migrations: Option<Arc<Migrations>>,
 }
 
 impl Store {
@@ -302,12 +658,45 @@ impl Store {
         fs::create_dir_all(&history_dir)
             .map_err(|e| Error::Internal(format!("Failed to create history dir: {}", e)))?;
 
-        Ok(Self {
+        let mut store = Self {
             inner: FileNamespace::new(&base_dir)?,
             base_dir,
             app_key,
             encryption_key,
-        })
+            blinding: None,
+            migrations: None,
+        };
+
+        // Finish a key rotation that passed its commit point
+        store.recover_rotation()?;
+
+        // Blinded stores stay blinded; finish an interrupted migration
+        if let Some(manifest) = store.load_manifest()? {
+            store.blinding = Some(Blinding::from_manifest(&manifest)?);
+            if !manifest.migrated {
+                store.finish_blinding()?;
+            }
+        }
+
+        // Finish any transaction that committed but didn't fully land
+        store.recover_journal()?;
+
+        // Undo an import that never reached its commit point
+        store.recover_import()?;
+
+        Ok(store)
+    }
+
+    /// Upgrade old `type_` versions with `migrations`
+    ///
+    /// Reads return the latest version, writing the upgrade back on first
+    /// contact; [`Store::migrate`] upgrades a whole prefix up front. A read
+    /// whose upgrade fails returns the scroll as stored; run
+    /// [`Store::migrate`] over its path to see why.
+    #[cfg(feature = "crypto")]
+    pub fn with_migrations(mut self, migrations: Arc<Migrations>) -> Self {
+        self.migrations = Some(migrations);
+        self
     }
 
     /// Get the app_key this store was opened with
@@ -333,11 +722,25 @@ impl Store {
     // ========================================================================
 
     /// Read a scroll, respecting encryption if enabled
+    ///
+    /// Expired scrolls read as absent.
     #[cfg(feature = "crypto")]
     fn read_scroll(&self, path: &str) -> Result<Option<Scroll>> {
+        Ok(self.read_stored(path)?.filter(|s| !s.is_expired()))
+    }
+
+    /// Read and decrypt whatever is on disk, expired or not
+    #[cfg(feature = "crypto")]
+    fn read_stored(&self, path: &str) -> Result<Option<Scroll>> {
+        self.read_disk(&self.disk_path(path))
+    }
+
+    /// `read_stored` by on-disk name
+    #[cfg(feature = "crypto")]
+    fn read_disk(&self, disk_path: &str) -> Result<Option<Scroll>> {
         match self.encryption_key {
             Some(ref key) => {
-                let sealed_opt = self.inner.read(path)?;
+                let sealed_opt = self.inner.read_file(disk_path)?;
                 match sealed_opt {
                     Some(sealed_scroll) => {
                         let sealed: SealedValue = serde_json::from_value(sealed_scroll.data)
@@ -351,7 +754,7 @@ impl Store {
                     None => Ok(None),
                 }
             }
-            None => self.inner.read(path),
+            None => self.inner.read_file(disk_path),
         }
     }
 
@@ -361,53 +764,126 @@ impl Store {
         self.inner.read(path)
     }
 
+    /// Read whatever is on disk, expired or not (non-crypto fallback)
+    #[cfg(not(feature = "crypto"))]
+    fn read_stored(&self, path: &str) -> Result<Option<Scroll>> {
+        self.inner.read_file(path)
+    }
+
     /// Write a scroll, respecting encryption if enabled
     #[cfg(feature = "crypto")]
     fn write_scroll_internal(&self, scroll: Scroll) -> Result<Scroll> {
-        // Get current state before write (decrypted if encrypted)
-        let old = self.read_scroll(&scroll.key)?;
+        // Get current state before write (decrypted if encrypted). An
+        // expired but unswept scroll is still the parent in history.
+        let old = self.read_stored(&scroll.key)?;
 
         // CSP: Derive sequence from filesystem (monotonic counter)
         let new_version = self.next_seq(&scroll.key);
 
-        match self.encryption_key {
+        let staged = self.prepare_write(scroll, old.as_ref(), new_version)?;
+        self.apply_write(&staged)?;
+
+        Ok(staged.scroll)
+    }
+
+    // ========================================================================
+    // Conditional Writes (Compare-and-Swap)
+    // ========================================================================
+
+    /// Conditional write (compare-and-swap)
+    ///
+    /// Writes `data` only if the current scroll satisfies `expected`,
+    /// failing with `Error::Conflict` otherwise. This turns the hash chain
+    /// from conflict *detection* into conflict *prevention*: two instances
+    /// sharing a `NINE_S_ROOT` cannot silently clobber each other.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let current = store.read("/shared/doc")?.unwrap();
+    /// let expected = Precondition::Hash(current.metadata.hash.clone().unwrap());
+    ///
+    /// match store.write_if("/shared/doc", new_data, &expected) {
+    ///     Ok(scroll) => { /* we won */ }
+    ///     Err(Error::Conflict(_)) => { /* re-read, merge, retry */ }
+    ///     Err(e) => return Err(e),
+    /// }
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn write_if(&self, path: &str, data: Value, expected: &Precondition) -> Result<Scroll> {
+        self.write_scroll_if(Scroll::new(path, data), expected)
+    }
+
+    /// Conditional write of a full scroll (preserves type and metadata)
+    ///
+    /// The check, the sealed write and the history patch all happen under
+    /// the path's cross-process lock.
+    #[cfg(feature = "crypto")]
+    pub fn write_scroll_if(&self, scroll: Scroll, expected: &Precondition) -> Result<Scroll> {
+        validate_path(&scroll.key)?;
+        let _lock = self.inner.lock_path(&self.disk_path(&scroll.key))?;
+
+        let current = self.read_scroll(&scroll.key)?;
+        expected.check(&scroll.key, current.as_ref())?;
+
+        if scroll.is_deleted() {
+            return Ok(self.delete_locked(&scroll.key)?.unwrap_or(scroll));
+        }
+
+        self.write_scroll_internal(scroll)
+    }
+
+    /// Version, seal and diff a scroll without touching disk
+    ///
+    /// `old` is the state the patch is computed against and `seq` the
+    /// sequence number the write will occupy in history.
+    #[cfg(feature = "crypto")]
+    fn prepare_write(&self, scroll: Scroll, old: Option<&Scroll>, seq: u64) -> Result<StagedWrite> {
+        let mut versioned_scroll = scroll;
+        versioned_scroll.metadata.version = seq;
+
+        let payload = match self.encryption_key {
             Some(ref key) => {
-                let mut versioned_scroll = scroll.clone();
-                versioned_scroll.metadata.version = new_version;
                 versioned_scroll.metadata.hash = Some(versioned_scroll.compute_hash());
                 if versioned_scroll.metadata.created_at.is_none() {
                     versioned_scroll.metadata.created_at = Some(crate::nine_s::current_iso_time());
                 }
                 versioned_scroll.metadata.updated_at = Some(crate::nine_s::current_iso_time());
+                seal_scroll(&versioned_scroll, key)?
+            }
+            None => Value::Null,
+        };
 
-                let plaintext = serde_json::to_vec(&versioned_scroll)
-                    .map_err(|e| Error::Internal(format!("Failed to serialize scroll: {}", e)))?;
-                let sealed = seal(key, &plaintext)
-                    .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
-                let sealed_data = serde_json::to_value(&sealed)
-                    .map_err(|e| Error::Internal(format!("Failed to serialize sealed: {}", e)))?;
-
-                self.inner.write(&scroll.key, sealed_data)?;
+        let mut patch = patch::diff::create(&versioned_scroll.key, old, &versioned_scroll);
+        patch.seq = seq; // Override with filesystem-derived seq
 
-                let mut patch = patch::diff::create(&scroll.key, old.as_ref(), &versioned_scroll);
-                patch.seq = new_version;
-                self.store_patch(&patch)?;
+        Ok(StagedWrite {
+            scroll: versioned_scroll,
+            payload,
+            patch,
+        })
+    }
 
-                Ok(versioned_scroll)
+    /// Land a prepared write: scroll into `_scrolls/`, patch into `_history/`
+    ///
+    /// Idempotent, so journal recovery can replay it safely.
+    #[cfg(feature = "crypto")]
+    fn apply_write(&self, staged: &StagedWrite) -> Result<()> {
+        match self.encryption_key {
+            Some(_) => {
+                let key = &staged.scroll.key;
+                self.note_path(key)?;
+                self.inner.write_scroll(sealed_wrapper(
+                    &self.disk_path(key),
+                    &staged.scroll,
+                    staged.payload.clone(),
+                ))?;
             }
             None => {
-                let mut versioned_scroll = scroll.clone();
-                versioned_scroll.metadata.version = new_version;
-
-                let result = self.inner.write_scroll(versioned_scroll.clone())?;
-
-                let mut patch = patch::diff::create(&scroll.key, old.as_ref(), &versioned_scroll);
-                patch.seq = new_version;
-                self.store_patch(&patch)?;
-
-                Ok(result)
+                self.inner.write_scroll(staged.scroll.clone())?;
             }
         }
+        self.store_patch(&staged.patch)?;
+        self.update_indexes(&staged.scroll)
     }
 
     /// Write a scroll (non-crypto fallback - just use inner)
@@ -429,166 +905,1648 @@ impl Store {
     }
 
     // ========================================================================
-    // History Operations
+    // Transactions (Write-Ahead Journal)
     // ========================================================================
 
-    /// Get the patch history for a scroll
+    /// Begin an atomic multi-scroll transaction
     ///
-    /// Returns all patches in chronological order (oldest first).
-    pub fn history(&self, path: &str) -> Result<Vec<Patch>> {
-        let history_dir = self.history_dir_for_path(path);
+    /// Writes are staged in memory and land together on `commit()`: either
+    /// every scroll and its history patch is written, or none are.
+    ///
+    /// # Dialectics
+    ///
+    /// **Thesis**: Each write is independent (simple, fast)
+    /// **Antithesis**: Related scrolls drift apart on crash (balance without its tx)
+    /// **Synthesis**: Journal first, then apply (all or nothing)
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut tx = store.begin();
+    /// tx.write("/wallet/balance", json!({"sats": 90_000}))?;
+    /// tx.write("/wallet/tx/abc", json!({"amount": 10_000}))?;
+    /// let written = tx.commit()?; // both or neither
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn begin(&self) -> Transaction<'_> {
+        Transaction {
+            store: self,
+            staged: Vec::new(),
+        }
+    }
 
-        if !history_dir.exists() {
+    /// Commit staged scrolls through the journal
+    ///
+    /// 1. Version, seal and diff every scroll (nothing on disk yet)
+    /// 2. Write the sealed journal atomically - **the commit point**
+    /// 3. Apply each write to `_scrolls/` and `_history/`
+    /// 4. Remove the journal
+    ///
+    /// A crash before (2) leaves no trace; a crash after (2) is replayed
+    /// by `recover_journal()` on the next open.
+    #[cfg(feature = "crypto")]
+    fn commit_staged(&self, scrolls: Vec<Scroll>) -> Result<Vec<Scroll>> {
+        if scrolls.is_empty() {
             return Ok(Vec::new());
         }
 
-        let patches_dir = history_dir.join("patches");
-        if !patches_dir.exists() {
-            return Ok(Vec::new());
+        // The locks single writes and lazy upgrades take, in path order so
+        // two commits can't deadlock
+        let paths: BTreeSet<String> = scrolls.iter().map(|s| self.disk_path(&s.key)).collect();
+        let _locks = paths
+            .iter()
+            .map(|p| self.inner.lock_path(p))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Later writes to the same path in one transaction chain off the
+        // staged state, not the on-disk one
+        let mut latest: HashMap<String, (Scroll, u64)> = HashMap::new();
+        let mut writes = Vec::with_capacity(scrolls.len());
+
+        for scroll in scrolls {
+            let key = scroll.key.clone();
+            let (old, seq) = match latest.get(&key) {
+                Some((staged, seq)) => (Some(staged.clone()), seq + 1),
+                None => (self.read_stored(&key)?, self.next_seq(&key)),
+            };
+
+            let staged = self.prepare_write(scroll, old.as_ref(), seq)?;
+            latest.insert(key, (staged.scroll.clone(), seq));
+            writes.push(staged);
         }
 
-        let mut patches = Vec::new();
+        let journal_path = self.write_journal(&writes)?;
 
-        for entry in fs::read_dir(&patches_dir)
-            .map_err(|e| Error::Internal(format!("Failed to read history: {}", e)))?
+        for staged in &writes {
+            self.apply_write(staged)?;
+        }
+
+        fs::remove_file(&journal_path)
+            .map_err(|e| Error::Internal(format!("Failed to remove journal: {}", e)))?;
+
+        Ok(writes.into_iter().map(|w| w.scroll).collect())
+    }
+
+    /// Durably write a transaction journal, returning its path
+    #[cfg(feature = "crypto")]
+    fn write_journal(&self, writes: &[StagedWrite]) -> Result<PathBuf> {
+        use rand::RngCore;
+
+        let journal_dir = self.base_dir.join("_journal");
+        fs::create_dir_all(&journal_dir)
+            .map_err(|e| Error::Internal(format!("Failed to create journal dir: {}", e)))?;
+
+        // Name sorts by commit time so recovery replays in order
+        let mut nonce = [0u8; 4];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let journal_path = journal_dir.join(format!(
+            "{:020}-{}.json",
+            super::current_time_millis(),
+            hex::encode(nonce)
+        ));
+
+        // Synced file and directory: once this returns, the commit is durable
+        self.write_record(&journal_path, &writes)?;
+
+        Ok(journal_path)
+    }
+
+    /// Replay committed journals left behind by a crash
+    ///
+    /// Called on every open. Half-written journals (`*.tmp`) never reached
+    /// the commit point and are discarded.
+    ///
+    /// # Returns
+    /// The number of transactions replayed.
+    #[cfg(feature = "crypto")]
+    fn recover_journal(&self) -> Result<usize> {
+        let journal_dir = self.base_dir.join("_journal");
+        if !journal_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut journals = Vec::new();
+        for entry in fs::read_dir(&journal_dir)
+            .map_err(|e| Error::Internal(format!("Failed to read journal dir: {}", e)))?
         {
             let entry =
                 entry.map_err(|e| Error::Internal(format!("Failed to read entry: {}", e)))?;
-            let file_path = entry.path();
+            let path = entry.path();
 
-            if file_path.extension().map_or(false, |e| e == "json") {
-                let file = File::open(&file_path)
-                    .map_err(|e| Error::Internal(format!("Failed to open patch: {}", e)))?;
-                let patch: Patch = serde_json::from_reader(BufReader::new(file))
-                    .map_err(|e| Error::Internal(format!("Failed to parse patch: {}", e)))?;
-                patches.push(patch);
+            if path.extension().is_some_and(|e| e == "json") {
+                journals.push(path);
+            } else if path.extension().is_some_and(|e| e == "tmp") {
+                let _ = fs::remove_file(&path);
             }
         }
+        journals.sort();
 
-        // Sort by sequence number
-        patches.sort_by_key(|p| p.seq);
-
-        Ok(patches)
-    }
+        for journal_path in &journals {
+            let writes: Vec<StagedWrite> = self.read_record(journal_path).map_err(|e| {
+                Error::Internal(format!("Failed to recover journal {:?}: {}", journal_path, e))
+            })?;
 
-    /// Create an anchor (immutable checkpoint) for a scroll
-    ///
-    /// Anchors freeze the current state with an optional label.
-    /// Use `restore()` to return to an anchored state.
-    pub fn anchor(&self, path: &str, label: Option<&str>) -> Result<Anchor> {
-        // Use self.read_scroll() which respects encryption when crypto feature is enabled
-        let scroll = self.read_scroll(path)?
-            .ok_or_else(|| Error::NotFound(path.to_string()))?;
+            for staged in &writes {
+                self.apply_write(staged)?;
+            }
 
-        let anchor = anchor::create(&scroll, label);
+            fs::remove_file(journal_path)
+                .map_err(|e| Error::Internal(format!("Failed to remove journal: {}", e)))?;
+        }
 
-        // Store anchor
-        let anchors_dir = self.history_dir_for_path(path).join("anchors");
-        fs::create_dir_all(&anchors_dir)
-            .map_err(|e| Error::Internal(format!("Failed to create anchors dir: {}", e)))?;
+        Ok(journals.len())
+    }
 
-        let anchor_path = anchors_dir.join(format!("{}.json", anchor.id));
-        let file = File::create(&anchor_path)
-            .map_err(|e| Error::Internal(format!("Failed to create anchor file: {}", e)))?;
+    // ========================================================================
+    // Secondary Indexes
+    // ========================================================================
+
+    /// Declare a secondary index and backfill it from existing scrolls
+    ///
+    /// From then on every write under `spec.prefix` keeps the index
+    /// current. Each index lives in `_index/{name}/`: its spec plus one
+    /// file per shard of entries, so a write re-seals a single shard. All
+    /// of them are sealed with a subkey (`HKDF(store_key, "_index")`), so
+    /// indexed values never hit disk in plaintext. Re-creating an existing
+    /// index rebuilds it.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// store.create_index(IndexSpec::new("tx_by_type", "/wallet/tx", IndexField::Type))?;
+    /// let page = store.query(&Query::on("tx_by_type").eq(json!("wallet/payment@v1")).limit(50))?;
+    /// ```
+    ///
+    /// # Returns
+    /// The number of scrolls indexed.
+    #[cfg(feature = "crypto")]
+    pub fn create_index(&self, spec: IndexSpec) -> Result<usize> {
+        spec.validate()?;
+        let _lock = self.inner.lock_path(&index_lock_path(&spec.name))?;
+
+        let mut index = Index::new(spec);
+        for path in self.list_paths(&index.spec.prefix)? {
+            if let Some(scroll) = self.read_scroll(&path)? {
+                index.update(&scroll);
+            }
+        }
+
+        self.save_index(&index)?;
+        Ok(index.entries.len())
+    }
+
+    /// Remove an index (scrolls are untouched)
+    ///
+    /// Returns false if no such index existed.
+    #[cfg(feature = "crypto")]
+    pub fn drop_index(&self, name: &str) -> Result<bool> {
+        let _lock = self.inner.lock_path(&index_lock_path(name))?;
+        let dir = self.index_dir(name);
+        if !dir.exists() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(&dir)
+            .map_err(|e| Error::Internal(format!("Failed to remove index: {}", e)))?;
+        Ok(true)
+    }
+
+    /// List declared indexes
+    #[cfg(feature = "crypto")]
+    pub fn indexes(&self) -> Result<Vec<IndexSpec>> {
+        let mut specs = Vec::new();
+        for name in self.index_names()? {
+            if let Some(spec) = self.load_index_spec(&name)? {
+                specs.push(spec);
+            }
+        }
+        Ok(specs)
+    }
+
+    /// Run a query against an index
+    ///
+    /// Only the scrolls on the returned page are read and decrypted.
+    #[cfg(feature = "crypto")]
+    pub fn query(&self, query: &Query) -> Result<QueryPage> {
+        let index = self
+            .load_index(&query.index)?
+            .ok_or_else(|| Error::NotFound(format!("index:{}", query.index)))?;
+
+        let (paths, next_cursor) = index.search(query)?;
+
+        let mut scrolls = Vec::with_capacity(paths.len());
+        for path in paths {
+            if let Some(scroll) = self.read_scroll(&path)? {
+                scrolls.push(scroll);
+            }
+        }
+
+        Ok(QueryPage {
+            scrolls,
+            next_cursor,
+        })
+    }
+
+    /// Reflect a written scroll in every index covering its path
+    ///
+    /// Only the shard holding the path is read and re-sealed.
+    #[cfg(feature = "crypto")]
+    fn update_indexes(&self, scroll: &Scroll) -> Result<()> {
+        for name in self.index_names()? {
+            let _lock = self.inner.lock_path(&index_lock_path(&name))?;
+            let Some(spec) = self.load_index_spec(&name)? else {
+                continue;
+            };
+            if spec.covers(&scroll.key) {
+                let shard = index::shard_of(&scroll.key);
+                let mut part = Index::from_entries(spec, self.load_index_shard(&name, shard)?);
+                part.update(scroll);
+                self.save_index_shard(&name, shard, &part.entries)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "crypto")]
+    fn index_dir(&self, name: &str) -> PathBuf {
+        self.base_dir.join("_index").join(name)
+    }
+
+    #[cfg(feature = "crypto")]
+    fn index_shard_file(&self, name: &str, shard: u64) -> PathBuf {
+        self.index_dir(name).join(format!("{:02}.json", shard))
+    }
+
+    /// Names of indexes with a spec (a half-built one has none yet)
+    #[cfg(feature = "crypto")]
+    fn index_names(&self) -> Result<Vec<String>> {
+        let Ok(dir) = fs::read_dir(self.base_dir.join("_index")) else {
+            return Ok(Vec::new());
+        };
+        Ok(dir
+            .flatten()
+            .filter(|entry| entry.path().join(INDEX_SPEC_FILE).exists())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect())
+    }
+
+    #[cfg(feature = "crypto")]
+    fn load_index_spec(&self, name: &str) -> Result<Option<IndexSpec>> {
+        let file_path = self.index_dir(name).join(INDEX_SPEC_FILE);
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        self.read_index_record(&file_path).map(Some)
+    }
+
+    /// Load an index with every shard merged back in order
+    #[cfg(feature = "crypto")]
+    fn load_index(&self, name: &str) -> Result<Option<Index>> {
+        let Some(spec) = self.load_index_spec(name)? else {
+            return Ok(None);
+        };
+        let mut entries = Vec::new();
+        for shard in 0..index::SHARDS {
+            entries.extend(self.load_index_shard(name, shard)?);
+        }
+        Ok(Some(Index::from_entries(spec, entries)))
+    }
+
+    #[cfg(feature = "crypto")]
+    fn load_index_shard(&self, name: &str, shard: u64) -> Result<Vec<IndexEntry>> {
+        let file_path = self.index_shard_file(name, shard);
+        if !file_path.exists() {
+            return Ok(Vec::new());
+        }
+        self.read_index_record(&file_path)
+    }
+
+    /// Write one shard (an empty shard is removed)
+    #[cfg(feature = "crypto")]
+    fn save_index_shard(&self, name: &str, shard: u64, entries: &[IndexEntry]) -> Result<()> {
+        let file_path = self.index_shard_file(name, shard);
+        if !entries.is_empty() {
+            return self.write_index_record(&file_path, &entries);
+        }
+        if file_path.exists() {
+            fs::remove_file(&file_path)
+                .map_err(|e| Error::Internal(format!("Failed to remove index shard: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Write a whole index from scratch
+    ///
+    /// The spec goes last, so an index cut short by a crash isn't listed
+    /// or maintained until it's created again.
+    #[cfg(feature = "crypto")]
+    fn save_index(&self, index: &Index) -> Result<()> {
+        let dir = self.index_dir(&index.spec.name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .map_err(|e| Error::Internal(format!("Failed to clear index: {}", e)))?;
+        }
+        for (shard, entries) in index.shards() {
+            self.save_index_shard(&index.spec.name, shard, &entries)?;
+        }
+        self.write_index_record(&dir.join(INDEX_SPEC_FILE), &index.spec)
+    }
+
+    #[cfg(feature = "crypto")]
+    fn read_index_record<T: DeserializeOwned>(&self, file_path: &Path) -> Result<T> {
+        let plaintext = unseal_record(&read_json(file_path)?, &self.index_key()?)?;
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| Error::Internal(format!("Failed to parse index: {}", e)))
+    }
+
+    #[cfg(feature = "crypto")]
+    fn write_index_record<T: Serialize>(&self, file_path: &Path, record: &T) -> Result<()> {
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| Error::Internal(format!("Failed to create index dir: {}", e)))?;
+        }
+        let plaintext = serde_json::to_vec(record)
+            .map_err(|e| Error::Internal(format!("Failed to serialize index: {}", e)))?;
+        let sealed = seal(&self.index_key()?, &plaintext)
+            .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+        write_json_atomic(file_path, &sealed)
+    }
+
+    /// Derive the index subkey from the store key
+    #[cfg(feature = "crypto")]
+    fn index_key(&self) -> Result<[u8; 32]> {
+        self.encryption_key
+            .as_ref()
+            .map(|key| crate::vault::crypto::derive_app_key(key, INDEX_KEY_INFO))
+            .ok_or_else(|| Error::Permission("Indexes require an encrypted store".to_string()))
+    }
+
+    // ========================================================================
+    // History Operations
+    // ========================================================================
+
+    /// Get the patch history for a scroll
+    ///
+    /// Returns all patches in chronological order (oldest first).
+    pub fn history(&self, path: &str) -> Result<Vec<Patch>> {
+        let history_dir = self.history_dir_for_path(path);
+
+        if !history_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let patches_dir = history_dir.join("patches");
+        if !patches_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut patches = Vec::new();
+
+        for entry in fs::read_dir(&patches_dir)
+            .map_err(|e| Error::Internal(format!("Failed to read history: {}", e)))?
+        {
+            let entry =
+                entry.map_err(|e| Error::Internal(format!("Failed to read entry: {}", e)))?;
+            let file_path = entry.path();
+
+            if file_path.extension().map_or(false, |e| e == "json") {
+                let patch: Patch = self.read_record(&file_path)?;
+                patches.push(patch);
+            }
+        }
+
+        // Sort by sequence number
+        patches.sort_by_key(|p| p.seq);
+
+        Ok(patches)
+    }
+
+    /// Create an anchor (immutable checkpoint) for a scroll
+    ///
+    /// Anchors freeze the current state with an optional label.
+    /// Use `restore()` to return to an anchored state.
+    pub fn anchor(&self, path: &str, label: Option<&str>) -> Result<Anchor> {
+        // Use self.read_scroll() which respects encryption when crypto feature is enabled
+        let scroll = self.read_scroll(path)?
+            .ok_or_else(|| Error::NotFound(path.to_string()))?;
+
+        let anchor = anchor::create(&scroll, label);
+
+        self.store_anchor(path, &anchor)?;
+
+        Ok(anchor)
+    }
+
+    /// Create an anchor signed with the user's Nostr identity key
+    ///
+    /// Like `anchor()`, but the checkpoint carries a BIP-340 signature, so
+    /// `restore_signed()` can tell it apart from one forged by anyone with
+    /// write access to the store directory.
+    #[cfg(feature = "keys")]
+    pub fn anchor_signed(
+        &self,
+        path: &str,
+        label: Option<&str>,
+        signer: &crate::nostr::NostrSigner,
+    ) -> Result<Anchor> {
+        let scroll = self.read_scroll(path)?
+            .ok_or_else(|| Error::NotFound(path.to_string()))?;
+
+        let anchor = anchor::sign(anchor::create(&scroll, label), signer)
+            .map_err(|e| Error::Internal(format!("Failed to sign anchor: {}", e)))?;
+
+        self.store_anchor(path, &anchor)?;
+
+        Ok(anchor)
+    }
+
+    /// List all anchors for a scroll
+    pub fn anchors(&self, path: &str) -> Result<Vec<Anchor>> {
+        let anchors_dir = self.history_dir_for_path(path).join("anchors");
+
+        if !anchors_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut anchors = Vec::new();
+
+        for entry in fs::read_dir(&anchors_dir)
+            .map_err(|e| Error::Internal(format!("Failed to read anchors: {}", e)))?
+        {
+            let entry =
+                entry.map_err(|e| Error::Internal(format!("Failed to read entry: {}", e)))?;
+            let file_path = entry.path();
+
+            if file_path.extension().map_or(false, |e| e == "json") {
+                let anchor: Anchor = self.read_record(&file_path)?;
+                anchors.push(anchor);
+            }
+        }
+
+        // Sort by timestamp
+        anchors.sort_by_key(|a| a.timestamp);
+
+        Ok(anchors)
+    }
+
+    /// Reconstruct the state of a scroll at a specific point in history
+    ///
+    /// Returns the scroll as it existed after the given sequence number.
+    /// This is read-only - it doesn't modify the current state.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let store = Store::at(path)?;
+    /// store.write("/doc", json!({"v": 1}))?;  // seq 1
+    /// store.write("/doc", json!({"v": 2}))?;  // seq 2
+    /// store.write("/doc", json!({"v": 3}))?;  // seq 3
+    ///
+    /// let v2_state = store.state_at("/doc", 2)?;
+    /// assert_eq!(v2_state.data["v"], 2);
+    /// ```
+    pub fn state_at(&self, path: &str, seq: u64) -> Result<Scroll> {
+        let patches = self.history(path)?;
+
+        if patches.is_empty() {
+            return Err(Error::NotFound(path.to_string()));
+        }
+
+        if seq == 0 || seq > patches.len() as u64 {
+            return Err(Error::Internal(format!(
+                "Invalid sequence number {}. Valid range: 1-{}",
+                seq,
+                patches.len()
+            )));
+        }
+
+        // Apply patches up to the requested sequence
+        let mut current = Scroll::new(path, serde_json::json!({}));
+
+        for patch in patches.iter().take(seq as usize) {
+            current = patch::diff::apply(&current, patch)
+                .map_err(|e| Error::Internal(format!("Failed to apply patch: {}", e)))?;
+        }
+
+        Ok(current)
+    }
+
+    /// Reconstruct every scroll under a prefix as it was at a timestamp
+    ///
+    /// Replays each scroll's patches up to and including `timestamp`
+    /// (unix millis, compared against patch timestamps). Scrolls first
+    /// written after that moment are absent from the snapshot. History
+    /// removed by `compact()` can't be replayed, so snapshots older than
+    /// the retention window reflect only the surviving patches.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // "What did the wallet look like at the end of August?"
+    /// let snapshot = store.snapshot_at("/wallet", cutoff_millis)?;
+    /// for path in snapshot.list("/wallet/tx")? {
+    ///     let tx = snapshot.read(&path)?.unwrap();
+    /// }
+    /// ```
+    pub fn snapshot_at(&self, prefix: &str, timestamp: i64) -> Result<Snapshot> {
+        let paths = self.history_paths(prefix)?;
+
+        let mut scrolls = BTreeMap::new();
+        for path in paths {
+            let mut current: Option<Scroll> = None;
+
+            for patch in self.history(&path)?.iter().filter(|p| p.timestamp <= timestamp) {
+                let base = current.unwrap_or_else(|| Scroll::new(&path, serde_json::json!({})));
+                let mut next = patch::diff::apply(&base, patch)
+                    .map_err(|e| Error::Internal(format!("Failed to apply patch: {}", e)))?;
+                next.metadata.version = patch.seq;
+                current = Some(next);
+            }
+
+            // Tombstoned (deleted or expired) by then
+            if let Some(scroll) = current.filter(|s| !s.is_deleted()) {
+                scrolls.insert(path, scroll);
+            }
+        }
+
+        Ok(Snapshot {
+            prefix: prefix.to_string(),
+            timestamp,
+            scrolls,
+        })
+    }
+
+    /// Restore a scroll to an anchored state
+    ///
+    /// This creates a new version with the anchor's content.
+    /// The restoration itself is recorded in history.
+    pub fn restore(&self, path: &str, anchor_id: &str) -> Result<Scroll> {
+        let anchor = self.find_anchor(path, anchor_id)?;
+
+        // Verify anchor integrity
+        if !anchor::verify(&anchor) {
+            return Err(Error::Internal("Anchor integrity check failed".to_string()));
+        }
+
+        // Write the restored content (creates new patch in history)
+        // Use self.write_scroll_internal() which respects encryption
+        let _lock = self.inner.lock_path(&self.disk_path(path))?;
+        self.write_scroll_internal(anchor.scroll)
+    }
+
+    /// Restore a scroll, but only to an anchor signed by `expected_pubkey`
+    ///
+    /// `expected_pubkey` may be hex or npub. Unsigned anchors, anchors
+    /// signed by another key and tampered anchors are all refused with
+    /// `Error::Permission`, and nothing is written.
+    #[cfg(feature = "keys")]
+    pub fn restore_signed(&self, path: &str, anchor_id: &str, expected_pubkey: &str) -> Result<Scroll> {
+        let anchor = self.find_anchor(path, anchor_id)?;
+
+        if !anchor::verify_signed(&anchor, expected_pubkey) {
+            return Err(Error::Permission(format!(
+                "Anchor signature check failed: {}",
+                anchor_id
+            )));
+        }
+
+        let _lock = self.inner.lock_path(&self.disk_path(path))?;
+        self.write_scroll_internal(anchor.scroll)
+    }
+
+    /// Look up an anchor of `path` by id
+    fn find_anchor(&self, path: &str, anchor_id: &str) -> Result<Anchor> {
+        self.anchors(path)?
+            .into_iter()
+            .find(|a| a.id == anchor_id)
+            .ok_or_else(|| Error::NotFound(format!("anchor:{}", anchor_id)))
+    }
+
+    /// Three-way merge a divergent state into the current scroll
+    ///
+    /// `base_seq` is the last sequence both sides agree on; the ancestor is
+    /// reconstructed with `state_at`. The merged scroll is written as a new
+    /// version under the path lock, so a concurrent writer can't slip in
+    /// between the read and the write.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // Another instance diverged from seq 3
+    /// let result = store.merge("/doc", 3, their_scroll, &MergePolicy::Ours)?;
+    /// for pointer in &result.conflicts {
+    ///     println!("resolved by policy: {}", pointer);
+    /// }
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn merge(
+        &self,
+        path: &str,
+        base_seq: u64,
+        theirs: Scroll,
+        policy: &MergePolicy,
+    ) -> Result<MergeResult> {
+        validate_path(path)?;
+        let _lock = self.inner.lock_path(&self.disk_path(path))?;
+
+        let ours = self
+            .read_scroll(path)?
+            .ok_or_else(|| Error::NotFound(path.to_string()))?;
+        let base = self.state_at(path, base_seq)?;
+
+        let merged = merge::three_way(&base, &ours, &theirs, policy);
+        let scroll = self.write_scroll_internal(merged.scroll)?;
+
+        Ok(MergeResult {
+            scroll,
+            conflicts: merged.conflicts,
+        })
+    }
+
+    // ========================================================================
+    // Backup & Restore
+    // ========================================================================
+
+    /// Export the store as a single encrypted, authenticated archive
+    ///
+    /// The archive is sealed with a subkey derived from the store key
+    /// (`HKDF(store_key, "_backup")`), so it can only be imported into a
+    /// store opened with the same app key and master key.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let archive = store.export_backup(BackupScope::FullHistory)?;
+    /// std::fs::write("beewallet.backup", &archive)?;
+    ///
+    /// // On the new device
+    /// let store = Store::open("beewallet", &master_key)?;
+    /// let report = store.import_backup(&std::fs::read("beewallet.backup")?)?;
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn export_backup(&self, scope: BackupScope) -> Result<Vec<u8>> {
+        let key = self.backup_key()?;
+
+        let mut scrolls = Vec::new();
+        for path in self.list_paths("/")? {
+            if let Some(scroll) = self.read_scroll(&path)? {
+                scrolls.push(scroll);
+            }
+        }
+        scrolls.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let mut history = Vec::new();
+        if scope == BackupScope::FullHistory {
+            let mut paths = self.history_paths("/")?;
+            paths.sort();
+
+            for path in paths {
+                history.push(BackupHistory {
+                    patches: self.history(&path)?,
+                    anchors: self.anchors(&path)?,
+                    path,
+                });
+            }
+        }
+
+        let header = BackupHeader {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            scope,
+            created_at: crate::nine_s::current_time_millis(),
+        };
+
+        let payload = BackupPayload {
+            header: header.clone(),
+            scrolls,
+            history,
+        };
+        let plaintext = serde_json::to_vec(&payload)
+            .map_err(|e| Error::Internal(format!("Failed to serialize backup: {}", e)))?;
+        let sealed = seal(&key, &plaintext)
+            .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+
+        serde_json::to_vec(&BackupArchive { header, sealed })
+            .map_err(|e| Error::Internal(format!("Failed to serialize backup: {}", e)))
+    }
+
+    /// Restore an archive produced by `export_backup` into an empty store
+    ///
+    /// Everything is validated before the first byte is written:
+    /// 1. Format and version are supported
+    /// 2. The payload authenticates under this store's backup key
+    /// 3. Each scroll's hash matches its content
+    /// 4. Each path's patches form an unbroken hash chain ending at the
+    ///    scroll's hash, and replay to the same data when complete
+    /// 5. Each anchor's hash matches its content
+    ///
+    /// Importing into a store that already holds scrolls or history fails
+    /// with `Error::Conflict` rather than interleaving two timelines.
+    ///
+    /// The import is all or nothing: `_import.json` is written before the
+    /// first file and removed once the last one lands. A failed import is
+    /// rolled back to the empty store, and one cut short by a crash is
+    /// rolled back the next time the store is opened.
+    #[cfg(feature = "crypto")]
+    pub fn import_backup(&self, archive: &[u8]) -> Result<BackupReport> {
+        let key = self.backup_key()?;
+
+        let archive: BackupArchive = serde_json::from_slice(archive)
+            .map_err(|e| Error::InvalidData(format!("Not a backup archive: {}", e)))?;
+        if archive.header.format != BACKUP_FORMAT {
+            return Err(Error::InvalidData(format!(
+                "Unknown backup format: {}",
+                archive.header.format
+            )));
+        }
+        if archive.header.version != BACKUP_VERSION {
+            return Err(Error::InvalidData(format!(
+                "Unsupported backup version: {}",
+                archive.header.version
+            )));
+        }
+
+        let plaintext = unseal(&key, &archive.sealed)
+            .map_err(|e| Error::Permission(format!("Backup authentication failed: {}", e)))?;
+        let payload: BackupPayload = serde_json::from_slice(&plaintext)
+            .map_err(|e| Error::InvalidData(format!("Failed to parse backup: {}", e)))?;
+        if payload.header != archive.header {
+            return Err(Error::InvalidData("Backup header was tampered with".to_string()));
+        }
+
+        validate_backup(&payload)?;
+
+        let mut existing_history = Vec::new();
+        collect_json_files(&self.base_dir.join("_history"), &mut existing_history)?;
+        if !self.inner.list("/")?.is_empty() || !existing_history.is_empty() {
+            return Err(Error::Conflict("import_backup requires an empty store".to_string()));
+        }
+
+        let marker = ImportMarker {
+            audit_seq: self.load_audit_head()?.map_or(0, |head| head.seq),
+        };
+        write_json_atomic(&self.import_marker_file(), &marker)?;
+
+        match self.apply_import(&payload) {
+            Ok(report) => {
+                fs::remove_file(self.import_marker_file())
+                    .map_err(|e| Error::Internal(format!("Failed to commit import: {}", e)))?;
+                Ok(report)
+            }
+            Err(e) => {
+                self.rollback_import(&marker)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Write a validated backup into the (empty) store
+    #[cfg(feature = "crypto")]
+    fn apply_import(&self, payload: &BackupPayload) -> Result<BackupReport> {
+        let mut report = BackupReport {
+            scope: payload.header.scope,
+            scrolls: 0,
+            patches: 0,
+            anchors: 0,
+        };
+
+        for scroll in &payload.scrolls {
+            match self.encryption_key {
+                Some(ref k) => {
+                    self.note_path(&scroll.key)?;
+                    self.inner.write_scroll(sealed_wrapper(
+                        &self.disk_path(&scroll.key),
+                        scroll,
+                        seal_scroll(scroll, k)?,
+                    ))?
+                }
+                None => self.inner.write_scroll(scroll.clone())?,
+            };
+            report.scrolls += 1;
+
+            // Scrolls-only: history restarts at the restored state, keeping
+            // the version counter monotonic
+            if payload.header.scope == BackupScope::ScrollsOnly {
+                let mut birth = patch::diff::create(&scroll.key, None, scroll);
+                birth.seq = scroll.metadata.version.max(1);
+                self.store_patch(&birth)?;
+                report.patches += 1;
+            }
+        }
+
+        for entry in &payload.history {
+            for p in &entry.patches {
+                self.store_patch(p)?;
+                report.patches += 1;
+            }
+            for a in &entry.anchors {
+                self.store_anchor(&entry.path, a)?;
+                report.anchors += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Return the store to its state before an import
+    ///
+    /// The store was empty, so every scroll and history file goes, the
+    /// audit log is cut back to `marker.audit_seq` and a blinded store's
+    /// manifest forgets every path.
+    #[cfg(feature = "crypto")]
+    fn rollback_import(&self, marker: &ImportMarker) -> Result<()> {
+        self.inner.delete_all()?;
+
+        let history_dir = self.base_dir.join("_history");
+        if history_dir.exists() {
+            fs::remove_dir_all(&history_dir)
+                .map_err(|e| Error::Internal(format!("Failed to roll back history: {}", e)))?;
+        }
+        fs::create_dir_all(&history_dir)
+            .map_err(|e| Error::Internal(format!("Failed to create history dir: {}", e)))?;
+
+        {
+            let _lock = self.inner.lock_path(AUDIT_LOCK_PATH)?;
+            for seq in self.audit_entries()?.into_keys().filter(|seq| *seq > marker.audit_seq) {
+                fs::remove_file(self.audit_entry_file(seq))
+                    .map_err(|e| Error::Internal(format!("Failed to roll back audit log: {}", e)))?;
+            }
+            if marker.audit_seq == 0 {
+                if self.audit_head_file().exists() {
+                    fs::remove_file(self.audit_head_file())
+                        .map_err(|e| Error::Internal(format!("Failed to roll back audit log: {}", e)))?;
+                }
+            } else {
+                let head = self.read_audit_entry(marker.audit_seq)?.head();
+                self.write_audit_record(&self.audit_head_file(), &head)?;
+            }
+        }
+
+        if self.blinding.is_some() {
+            let _lock = self.inner.lock_path(MANIFEST_LOCK_PATH)?;
+            if let Some(mut manifest) = self.load_manifest()? {
+                manifest.paths.clear();
+                self.save_manifest(&manifest)?;
+            }
+        }
+
+        fs::remove_file(self.import_marker_file())
+            .map_err(|e| Error::Internal(format!("Failed to remove import marker: {}", e)))
+    }
+
+    /// Roll back an import a crash interrupted
+    #[cfg(feature = "crypto")]
+    fn recover_import(&self) -> Result<()> {
+        let marker_path = self.import_marker_file();
+        if !marker_path.exists() {
+            return Ok(());
+        }
+        let marker: ImportMarker = read_json(&marker_path)?;
+        self.rollback_import(&marker)
+    }
+
+    #[cfg(feature = "crypto")]
+    fn import_marker_file(&self) -> PathBuf {
+        self.base_dir.join("_import.json")
+    }
+
+    /// Derive the backup subkey from the store key
+    #[cfg(feature = "crypto")]
+    fn backup_key(&self) -> Result<[u8; 32]> {
+        self.encryption_key
+            .as_ref()
+            .map(|key| crate::vault::crypto::derive_app_key(key, BACKUP_KEY_INFO))
+            .ok_or_else(|| Error::Permission("Backups require an encrypted store".to_string()))
+    }
+
+    // ========================================================================
+    // Key Rotation
+    // ========================================================================
+
+    /// Re-encrypt the whole store from `old_master` to `new_master`
+    ///
+    /// Pass the same master keys the store was opened with (for
+    /// `Store::open` the app key is derived from them; for `Store::at` they
+    /// are used directly). Call this after `VaultStore::change_passphrase`
+    /// hands back the new master key.
+    ///
+    /// # Crash Safety
+    ///
+    /// 1. Write `_rotation.json` (phase `staging`)
+    /// 2. Re-seal every scroll, history record and index into `_rekey/`
+    /// 3. Verify every staged copy decrypts under the new key to exactly the
+    ///    plaintext of its original
+    /// 4. Flip the marker to `swapping` - **the commit point**
+    /// 5. Rename staged copies over the originals, then drop the marker
+    ///
+    /// A crash during staging leaves the store readable with the old key;
+    /// calling `rotate_key` again resumes, reusing staged copies. A crash
+    /// during swapping is finished automatically the next time the store
+    /// is opened with the new key. No other instance may write to the
+    /// store while a rotation is in flight.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// // The old master key must be unlocked before the passphrase changes
+    /// let old_master = vault.unlock(old_pass)?;
+    /// let new_master = vault.change_passphrase(old_pass, new_pass)?;
+    /// let report = store.rotate_key(&old_master, &new_master)?;
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn rotate_key(&mut self, old_master: &[u8; 32], new_master: &[u8; 32]) -> Result<RotationReport> {
+        let old_key = self.store_key_for(old_master);
+        let new_key = self.store_key_for(new_master);
+
+        if self.encryption_key != Some(old_key) {
+            return Err(Error::Permission(
+                "Old master key does not match this store".to_string(),
+            ));
+        }
+
+        let report = self.stage_rotation(&old_key, &new_key)?;
+        self.write_rotation_marker(&RotationMarker {
+            phase: RotationPhase::Swapping,
+            old_key_id: key_id(&old_key),
+            new_key_id: key_id(&new_key),
+        })?;
+        self.swap_rotation()?;
+
+        self.encryption_key = Some(new_key);
+        Ok(report)
+    }
+
+    /// Derive the store key a master key maps to
+    #[cfg(feature = "crypto")]
+    fn store_key_for(&self, master: &[u8; 32]) -> [u8; 32] {
+        match self.app_key {
+            Some(ref app_key) => crate::vault::crypto::derive_app_key(master, app_key),
+            None => *master,
+        }
+    }
+
+    /// Steps 1-3: stage and verify re-sealed copies in `_rekey/`
+    #[cfg(feature = "crypto")]
+    fn stage_rotation(&self, old_key: &[u8; 32], new_key: &[u8; 32]) -> Result<RotationReport> {
+        let marker = RotationMarker {
+            phase: RotationPhase::Staging,
+            old_key_id: key_id(old_key),
+            new_key_id: key_id(new_key),
+        };
+        match self.read_rotation_marker()? {
+            Some(existing) if existing != marker => {
+                return Err(Error::Conflict(
+                    "A different key rotation is already in progress".to_string(),
+                ));
+            }
+            Some(_) => {}
+            None => self.write_rotation_marker(&marker)?,
+        }
+
+        let mut report = RotationReport::default();
+
+        // Drop copies whose original has since been removed
+        let rekey_dir = self.base_dir.join("_rekey");
+        let mut leftovers = Vec::new();
+        collect_json_files(&rekey_dir, &mut leftovers)?;
+        for file in leftovers {
+            if let Ok(relative) = file.strip_prefix(&rekey_dir) {
+                if !self.base_dir.join(relative).exists() {
+                    let _ = fs::remove_file(&file);
+                }
+            }
+        }
+
+        let mut scroll_files = Vec::new();
+        collect_json_files(&self.base_dir.join("_scrolls"), &mut scroll_files)?;
+        for file in &scroll_files {
+            let staged = self.rekey_path(file)?;
+            let original: Scroll = read_json(file)?;
+            let plain = decrypt_scroll(&original, old_key)?;
+            let expected = serde_json::to_value(&plain).ok();
+
+            let verifies = |path: &Path| {
+                read_json(path)
+                    .and_then(|s: Scroll| decrypt_scroll(&s, new_key))
+                    .ok()
+                    .and_then(|s| serde_json::to_value(s).ok())
+                    == expected
+            };
+
+            if staged.exists() && verifies(&staged) {
+                report.resumed += 1;
+            } else {
+                let mut resealed = original;
+                resealed.data = seal_scroll(&plain, new_key)?;
+                write_staged(&staged, &resealed)?;
+                if !verifies(&staged) {
+                    return Err(Error::Internal(format!("Re-sealed scroll does not verify: {:?}", file)));
+                }
+            }
+            report.scrolls += 1;
+        }
+
+        for (dir, info) in SEALED_RECORD_DIRS {
+            let old_subkey = crate::vault::crypto::derive_app_key(old_key, info);
+            let new_subkey = crate::vault::crypto::derive_app_key(new_key, info);
+
+            let mut record_files = Vec::new();
+            collect_json_files(&self.base_dir.join(dir), &mut record_files)?;
+            for file in &record_files {
+                let raw: Value = read_json(file)?;
+                if !is_sealed_record(&raw) {
+                    continue; // Plaintext legacy record: see migrate_history()
+                }
+
+                let staged = self.rekey_path(file)?;
+                let plain = unseal_record(&raw, &old_subkey)?;
+
+                let verifies = |path: &Path| {
+                    read_json(path)
+                        .and_then(|v: Value| unseal_record(&v, &new_subkey))
+                        .is_ok_and(|bytes| bytes == plain)
+                };
+
+                if staged.exists() && verifies(&staged) {
+                    report.resumed += 1;
+                } else {
+                    let sealed = seal(&new_subkey, &plain)
+                        .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+                    write_staged(&staged, &sealed)?;
+                    if !verifies(&staged) {
+                        return Err(Error::Internal(format!(
+                            "Re-sealed record does not verify: {:?}",
+                            file
+                        )));
+                    }
+                }
+                report.records += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Step 5: rename staged copies over the originals (idempotent)
+    #[cfg(feature = "crypto")]
+    fn swap_rotation(&self) -> Result<()> {
+        let rekey_dir = self.base_dir.join("_rekey");
+        let mut staged = Vec::new();
+        collect_json_files(&rekey_dir, &mut staged)?;
+
+        for file in staged {
+            let relative = file
+                .strip_prefix(&rekey_dir)
+                .map_err(|e| Error::Internal(format!("Bad staged path: {}", e)))?;
+            fs::rename(&file, self.base_dir.join(relative))
+                .map_err(|e| Error::Internal(format!("Failed to swap re-sealed file: {}", e)))?;
+        }
+
+        if rekey_dir.exists() {
+            fs::remove_dir_all(&rekey_dir)
+                .map_err(|e| Error::Internal(format!("Failed to remove rekey dir: {}", e)))?;
+        }
+        fs::remove_file(self.base_dir.join("_rotation.json"))
+            .map_err(|e| Error::Internal(format!("Failed to remove rotation marker: {}", e)))
+    }
+
+    /// Finish or refuse an interrupted rotation on open
+    ///
+    /// A rotation past its commit point is completed if this store holds
+    /// the new key; holding the key the files are *not* (yet) sealed
+    /// under fails with `Error::Permission` instead of returning garbage.
+    #[cfg(feature = "crypto")]
+    fn recover_rotation(&self) -> Result<()> {
+        let (marker, key) = match (self.read_rotation_marker()?, self.encryption_key) {
+            (Some(marker), Some(key)) => (marker, key_id(&key)),
+            _ => return Ok(()),
+        };
+
+        match marker.phase {
+            RotationPhase::Swapping if key == marker.new_key_id => self.swap_rotation(),
+            RotationPhase::Swapping if key == marker.old_key_id => Err(Error::Permission(
+                "Key rotation was committed: open the store with the new key".to_string(),
+            )),
+            RotationPhase::Staging if key == marker.new_key_id => Err(Error::Permission(
+                "Key rotation is incomplete: open with the old key and call rotate_key to resume"
+                    .to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Map a file under the store to its staged copy under `_rekey/`
+    #[cfg(feature = "crypto")]
+    fn rekey_path(&self, file: &Path) -> Result<PathBuf> {
+        let relative = file
+            .strip_prefix(&self.base_dir)
+            .map_err(|e| Error::Internal(format!("Bad store path: {}", e)))?;
+        Ok(self.base_dir.join("_rekey").join(relative))
+    }
+
+    #[cfg(feature = "crypto")]
+    fn read_rotation_marker(&self) -> Result<Option<RotationMarker>> {
+        let marker_path = self.base_dir.join("_rotation.json");
+        if !marker_path.exists() {
+            return Ok(None);
+        }
+        read_json(&marker_path).map(Some)
+    }
+
+    #[cfg(feature = "crypto")]
+    fn write_rotation_marker(&self, marker: &RotationMarker) -> Result<()> {
+        write_json_atomic(&self.base_dir.join("_rotation.json"), marker)
+    }
+
+    // ========================================================================
+    // Path Blinding
+    // ========================================================================
+
+    /// Hide scroll paths on disk
+    ///
+    /// By default `/wallet/tx/{txid}` lives at `_scrolls/wallet/tx/{txid}.json`,
+    /// so anyone with disk access learns every payment id and contact name
+    /// without decrypting anything. Once blinded, every scroll file and
+    /// history directory is named by a keyed HMAC of its path instead:
+    ///
+    /// ```text
+    /// _scrolls/3f9a...c2.json        <- /wallet/tx/abc
+    /// _history/3f9a...c2/patches/    <- its history
+    /// _manifest/paths.json           <- sealed: HMAC key + every path
+    /// ```
+    ///
+    /// `list`, history, snapshots and backups read the paths from the
+    /// sealed manifest. The HMAC key is random and lives in the manifest,
+    /// so `rotate_key` never renames files.
+    ///
+    /// Existing files are moved to their blinded names. The manifest is
+    /// written first and is the commit point: if the move is interrupted,
+    /// the next open finishes it. Blinding is one-way and persists; the
+    /// store opens blinded from then on. No other instance may use the
+    /// store while this runs.
+    ///
+    /// With blinding on, `watch()` sees on-disk names only; use
+    /// `watch_decrypted()`.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let mut store = Store::open("beewallet", &master_key)?;
+    /// let moved = store.enable_path_blinding()?;
+    /// ```
+    ///
+    /// # Returns
+    /// The number of scroll paths whose files were moved (0 if the store
+    /// was already blinded).
+    #[cfg(feature = "crypto")]
+    pub fn enable_path_blinding(&mut self) -> Result<usize> {
+        if self.blinding.is_some() {
+            return Ok(0);
+        }
+        self.manifest_key()?;
+
+        let mut paths: BTreeSet<String> = self.inner.list("/")?.into_iter().collect();
+        let mut history_paths = Vec::new();
+        collect_history_paths(&self.base_dir.join("_history"), "", &mut history_paths)?;
+        paths.extend(history_paths);
+
+        use rand::RngCore;
+        let mut name_key = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut name_key);
+
+        let manifest = Manifest {
+            name_key: hex::encode(name_key),
+            migrated: false,
+            paths,
+        };
+        self.save_manifest(&manifest)?;
+
+        self.blinding = Some(Blinding::from_manifest(&manifest)?);
+        self.finish_blinding()
+    }
+
+    /// Check if on-disk names are blinded
+    #[cfg(feature = "crypto")]
+    pub fn is_path_blinded(&self) -> bool {
+        self.blinding.is_some()
+    }
+
+    /// Move every manifest path's files to their blinded names
+    ///
+    /// Idempotent: already-moved files are skipped.
+    #[cfg(feature = "crypto")]
+    fn finish_blinding(&self) -> Result<usize> {
+        let mut manifest = self
+            .load_manifest()?
+            .ok_or_else(|| Error::Internal("Path manifest missing".to_string()))?;
+
+        let mut moved = 0;
+        for path in &manifest.paths {
+            let disk = self.disk_path(path);
+            let mut relocated = self.inner.relocate(path, &disk)?;
+
+            let from = self.base_dir.join("_history").join(path.trim_start_matches('/'));
+            let to = self.history_dir_for_path(path);
+            for sub in ["patches", "anchors"] {
+                let Ok(entries) = fs::read_dir(from.join(sub)) else {
+                    continue;
+                };
+                fs::create_dir_all(to.join(sub))
+                    .map_err(|e| Error::Internal(format!("Failed to create history dir: {}", e)))?;
+                for entry in entries.flatten() {
+                    let target = to.join(sub).join(entry.file_name());
+                    if target.exists() {
+                        let _ = fs::remove_file(entry.path());
+                    } else {
+                        fs::rename(entry.path(), &target)
+                            .map_err(|e| Error::Internal(format!("Failed to move history: {}", e)))?;
+                    }
+                    relocated = true;
+                }
+            }
+
+            if relocated {
+                moved += 1;
+            }
+        }
+
+        // The directory tree itself spells out paths
+        remove_empty_dirs(&self.base_dir.join("_scrolls"))?;
+        remove_empty_dirs(&self.base_dir.join("_history"))?;
+
+        manifest.migrated = true;
+        self.save_manifest(&manifest)?;
+        Ok(moved)
+    }
+
+    /// On-disk name for a scroll path: the path itself, or `/{hmac}` when blinded
+    fn disk_path(&self, path: &str) -> String {
+        #[cfg(feature = "crypto")]
+        if let Some(ref blinding) = self.blinding {
+            return format!("/{}", blinding.blind(path));
+        }
+        path.to_string()
+    }
+
+    /// Record a path in the manifest before its first file is written
+    #[cfg(feature = "crypto")]
+    fn note_path(&self, path: &str) -> Result<()> {
+        let Some(ref blinding) = self.blinding else {
+            return Ok(());
+        };
+        let name = blinding.blind(path);
+        if blinding.names.read().unwrap().contains_key(&name) {
+            return Ok(());
+        }
+
+        let _lock = self.inner.lock_path(MANIFEST_LOCK_PATH)?;
+        let mut manifest = self
+            .load_manifest()?
+            .ok_or_else(|| Error::Internal("Path manifest missing".to_string()))?;
+        if manifest.paths.insert(path.to_string()) {
+            self.save_manifest(&manifest)?;
+        }
+        blinding.names.write().unwrap().insert(name, path.to_string());
+        Ok(())
+    }
+
+    /// Drop a purged path from the manifest
+    #[cfg(feature = "crypto")]
+    fn forget_path(&self, path: &str) -> Result<()> {
+        if self.blinding.is_none() {
+            return Ok(());
+        }
+
+        let _lock = self.inner.lock_path(MANIFEST_LOCK_PATH)?;
+        if let Some(mut manifest) = self.load_manifest()? {
+            if manifest.paths.remove(path) {
+                self.save_manifest(&manifest)?;
+            }
+        }
+        // `names` keeps the entry so in-flight watch events still resolve
+        Ok(())
+    }
+
+    /// List scroll paths under a prefix, resolving blinded names
+    fn list_paths(&self, prefix: &str) -> Result<Vec<String>> {
+        #[cfg(feature = "crypto")]
+        if self.blinding.is_some() {
+            return self.list_blinded(prefix);
+        }
+        self.inner.list(prefix)
+    }
+
+    /// `list` for a blinded store: manifest paths that have a live file
+    #[cfg(feature = "crypto")]
+    fn list_blinded(&self, prefix: &str) -> Result<Vec<String>> {
+        validate_path(prefix)?;
+        let live: HashSet<String> = self.inner.list("/")?.into_iter().collect();
+        let manifest = self.load_manifest()?.map(|m| m.paths).unwrap_or_default();
+        Ok(manifest
+            .into_iter()
+            .filter(|p| is_path_under_prefix(p, prefix) && live.contains(&self.disk_path(p)))
+            .collect())
+    }
+
+    /// Every scroll path under `prefix` that has recorded history
+    fn history_paths(&self, prefix: &str) -> Result<Vec<String>> {
+        #[cfg(feature = "crypto")]
+        if self.blinding.is_some() {
+            let manifest = self.load_manifest()?.map(|m| m.paths).unwrap_or_default();
+            return Ok(manifest
+                .into_iter()
+                .filter(|p| is_path_under_prefix(p, prefix))
+                .filter(|p| self.history_dir_for_path(p).join("patches").is_dir())
+                .collect());
+        }
+
+        let root = if prefix == "/" {
+            self.base_dir.join("_history")
+        } else {
+            self.history_dir_for_path(prefix)
+        };
+        let mut paths = Vec::new();
+        collect_history_paths(&root, prefix.trim_end_matches('/'), &mut paths)?;
+        Ok(paths)
+    }
+
+    #[cfg(feature = "crypto")]
+    fn manifest_file(&self) -> PathBuf {
+        self.base_dir.join("_manifest").join("paths.json")
+    }
+
+    #[cfg(feature = "crypto")]
+    fn load_manifest(&self) -> Result<Option<Manifest>> {
+        let file_path = self.manifest_file();
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        let plaintext = unseal_record(&read_json(&file_path)?, &self.manifest_key()?)?;
+        serde_json::from_slice(&plaintext)
+            .map(Some)
+            .map_err(|e| Error::Internal(format!("Failed to parse manifest: {}", e)))
+    }
+
+    #[cfg(feature = "crypto")]
+    fn save_manifest(&self, manifest: &Manifest) -> Result<()> {
+        let file_path = self.manifest_file();
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| Error::Internal(format!("Failed to create manifest dir: {}", e)))?;
+        }
+        let plaintext = serde_json::to_vec(manifest)
+            .map_err(|e| Error::Internal(format!("Failed to serialize manifest: {}", e)))?;
+        let sealed = seal(&self.manifest_key()?, &plaintext)
+            .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+        write_json_atomic(&file_path, &sealed)
+    }
+
+    /// Derive the manifest subkey from the store key
+    #[cfg(feature = "crypto")]
+    fn manifest_key(&self) -> Result<[u8; 32]> {
+        self.encryption_key
+            .as_ref()
+            .map(|key| crate::vault::crypto::derive_app_key(key, MANIFEST_KEY_INFO))
+            .ok_or_else(|| Error::Permission("Path blinding requires an encrypted store".to_string()))
+    }
+
+    // ========================================================================
+    // Audit Log
+    // ========================================================================
+
+    /// Check the audit log and history against each other
+    ///
+    /// Walks the whole log, checking that every entry is intact, in place
+    /// and linked to the one before it, then replays it against
+    /// `_history/`: every logged patch must still be there with the same
+    /// contents, and every patch there must have been logged. Compacted
+    /// and purged patches are expected to be gone.
+    ///
+    /// Without the store key nobody can forge an entry, so an intact report
+    /// means history is exactly what this store wrote. Deleting the log's
+    /// tail together with the newest patches can't be told apart from
+    /// never having written them; pin `report.head` somewhere the store
+    /// can't rewrite (a backup, a relay) to rule that out.
+    ///
+    /// Patches are logged before they're written, so a crash in between
+    /// leaves a path's newest logged patch missing. That one patch is
+    /// listed in `report.interrupted` rather than as an issue.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let report = store.verify_integrity()?;
+    /// assert!(report.is_intact(), "{:?}", report.issues);
+    /// ```
+    #[cfg(feature = "crypto")]
+    pub fn verify_integrity(&self) -> Result<IntegrityReport> {
+        let head = self.load_audit_head()?;
+        let entries = self.audit_entries()?;
+
+        let mut report = IntegrityReport {
+            entries: entries.len() as u64,
+            issues: audit::verify_chain(&entries, head.as_ref()),
+            head,
+            ..Default::default()
+        };
+
+        let mut expected = audit::expected_patches(entries.values().flatten());
+        for path in self.history_paths("/")? {
+            expected.entry(path).or_default();
+        }
+
+        for (path, logged) in expected {
+            let on_disk = self.patch_files(&path)?;
+            report.patches += on_disk.len();
+
+            // Logged last, and nothing at or past it on disk
+            let last_logged = logged.keys().next_back().copied();
+            let unfinished = |seq: u64| {
+                Some(seq) == last_logged && on_disk.range(seq..).next().is_none()
+            };
+
+            for (seq, want) in &logged {
+                match on_disk.get(seq) {
+                    None if want.optional => {}
+                    None if unfinished(*seq) => report.interrupted.push((path.clone(), *seq)),
+                    None => report.issues.push(IntegrityIssue::MissingPatch {
+                        path: path.clone(),
+                        seq: *seq,
+                    }),
+                    Some(patch) => {
+                        let intact = patch
+                            .as_ref()
+                            .is_some_and(|p| p.key == path && audit::patch_digest(p) == want.digest);
+                        if !intact {
+                            report.issues.push(IntegrityIssue::RewrittenPatch {
+                                path: path.clone(),
+                                seq: *seq,
+                            });
+                        }
+                    }
+                }
+            }
 
-        serde_json::to_writer_pretty(BufWriter::new(file), &anchor)
-            .map_err(|e| Error::Internal(format!("Failed to write anchor: {}", e)))?;
+            for seq in on_disk.keys().filter(|seq| !logged.contains_key(seq)) {
+                report.issues.push(IntegrityIssue::UnloggedPatch {
+                    path: path.clone(),
+                    seq: *seq,
+                });
+            }
+        }
 
-        Ok(anchor)
+        Ok(report)
     }
 
-    /// List all anchors for a scroll
-    pub fn anchors(&self, path: &str) -> Result<Vec<Anchor>> {
-        let anchors_dir = self.history_dir_for_path(path).join("anchors");
-
-        if !anchors_dir.exists() {
-            return Ok(Vec::new());
+    /// Log every patch already in history that the audit log doesn't cover
+    ///
+    /// Stores created before the audit log existed start out with all of
+    /// their history unlogged. Call this once after upgrading: it trusts
+    /// whatever is on disk at that moment, so only do it on a store you
+    /// have no reason to doubt.
+    ///
+    /// # Returns
+    /// The number of patches added to the log.
+    #[cfg(feature = "crypto")]
+    pub fn adopt_history(&self) -> Result<usize> {
+        let entries = self.audit_entries()?;
+        let logged = audit::expected_patches(entries.values().flatten());
+
+        let mut adopted = 0;
+        for path in self.history_paths("/")? {
+            for (seq, patch) in self.patch_files(&path)? {
+                if logged.get(&path).is_some_and(|l| l.contains_key(&seq)) {
+                    continue;
+                }
+                let patch = patch.ok_or_else(|| {
+                    Error::Internal(format!("Unreadable patch {} of {}", seq, path))
+                })?;
+                self.append_audit(
+                    &path,
+                    AuditAction::Patch {
+                        seq,
+                        digest: audit::patch_digest(&patch),
+                    },
+                )?;
+                adopted += 1;
+            }
         }
+        Ok(adopted)
+    }
 
-        let mut anchors = Vec::new();
+    /// Append one entry to the audit log
+    #[cfg(feature = "crypto")]
+    fn append_audit(&self, path: &str, action: AuditAction) -> Result<()> {
+        let _lock = self.inner.lock_path(AUDIT_LOCK_PATH)?;
 
-        for entry in fs::read_dir(&anchors_dir)
-            .map_err(|e| Error::Internal(format!("Failed to read anchors: {}", e)))?
-        {
-            let entry =
-                entry.map_err(|e| Error::Internal(format!("Failed to read entry: {}", e)))?;
-            let file_path = entry.path();
+        let mut head = match self.load_audit_head()? {
+            Some(head) => Some(head),
+            // Head lost: carry on from the last entry on disk
+            None => self.audit_entries()?.into_values().flatten().last().map(|e| e.head()),
+        };
 
-            if file_path.extension().map_or(false, |e| e == "json") {
-                let file = File::open(&file_path)
-                    .map_err(|e| Error::Internal(format!("Failed to open anchor: {}", e)))?;
-                let anchor: Anchor = serde_json::from_reader(BufReader::new(file))
-                    .map_err(|e| Error::Internal(format!("Failed to parse anchor: {}", e)))?;
-                anchors.push(anchor);
+        // An entry written just before a crash, ahead of its head update
+        let next = head.as_ref().map_or(1, |h| h.seq + 1);
+        if let Ok(orphan) = self.read_audit_entry(next) {
+            if orphan.verify() && orphan.prev == head.as_ref().map(|h| h.hash.clone()) {
+                head = Some(orphan.head());
             }
         }
 
-        // Sort by timestamp
-        anchors.sort_by_key(|a| a.timestamp);
-
-        Ok(anchors)
+        let entry = AuditEntry::new(head.as_ref(), path, action);
+        self.write_audit_record(&self.audit_entry_file(entry.seq), &entry)?;
+        self.write_audit_record(&self.audit_head_file(), &entry.head())
     }
 
-    /// Reconstruct the state of a scroll at a specific point in history
-    ///
-    /// Returns the scroll as it existed after the given sequence number.
-    /// This is read-only - it doesn't modify the current state.
-    ///
-    /// # Example
-    /// ```rust,ignore
-    /// let store = Store::at(path)?;
-    /// store.write("/doc", json!({"v": 1}))?;  // seq 1
-    /// store.write("/doc", json!({"v": 2}))?;  // seq 2
-    /// store.write("/doc", json!({"v": 3}))?;  // seq 3
-    ///
-    /// let v2_state = store.state_at("/doc", 2)?;
-    /// assert_eq!(v2_state.data["v"], 2);
-    /// ```
-    pub fn state_at(&self, path: &str, seq: u64) -> Result<Scroll> {
-        let patches = self.history(path)?;
-
-        if patches.is_empty() {
-            return Err(Error::NotFound(path.to_string()));
+    /// Every entry file in the log by position (None if unreadable)
+    #[cfg(feature = "crypto")]
+    fn audit_entries(&self) -> Result<BTreeMap<u64, Option<AuditEntry>>> {
+        let mut entries = BTreeMap::new();
+        let Ok(dir) = fs::read_dir(self.base_dir.join("_audit")) else {
+            return Ok(entries);
+        };
+        for entry in dir.flatten() {
+            let file_path = entry.path();
+            if !file_path.extension().is_some_and(|e| e == "json") {
+                continue;
+            }
+            let Some(seq) = file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            entries.insert(seq, self.read_audit_entry(seq).ok());
         }
+        Ok(entries)
+    }
 
-        if seq == 0 || seq > patches.len() as u64 {
-            return Err(Error::Internal(format!(
-                "Invalid sequence number {}. Valid range: 1-{}",
-                seq,
-                patches.len()
-            )));
+    /// Every patch file of a path by seq (None if unreadable)
+    #[cfg(feature = "crypto")]
+    fn patch_files(&self, path: &str) -> Result<BTreeMap<u64, Option<Patch>>> {
+        let mut patches = BTreeMap::new();
+        let Ok(dir) = fs::read_dir(self.history_dir_for_path(path).join("patches")) else {
+            return Ok(patches);
+        };
+        for entry in dir.flatten() {
+            let file_path = entry.path();
+            if !file_path.extension().is_some_and(|e| e == "json") {
+                continue;
+            }
+            let Some(seq) = file_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            patches.insert(seq, self.read_record(&file_path).ok());
         }
+        Ok(patches)
+    }
 
-        // Apply patches up to the requested sequence
-        let mut current = Scroll::new(path, serde_json::json!({}));
+    #[cfg(feature = "crypto")]
+    fn audit_entry_file(&self, seq: u64) -> PathBuf {
+        self.base_dir.join("_audit").join(format!("{:012}.json", seq))
+    }
 
-        for patch in patches.iter().take(seq as usize) {
-            current = patch::diff::apply(&current, patch)
-                .map_err(|e| Error::Internal(format!("Failed to apply patch: {}", e)))?;
+    #[cfg(feature = "crypto")]
+    fn audit_head_file(&self) -> PathBuf {
+        self.base_dir.join("_audit").join("HEAD.json")
+    }
+
+    #[cfg(feature = "crypto")]
+    fn load_audit_head(&self) -> Result<Option<AuditHead>> {
+        let file_path = self.audit_head_file();
+        if !file_path.exists() {
+            return Ok(None);
         }
+        self.read_audit_record(&file_path).map(Some)
+    }
 
-        Ok(current)
+    #[cfg(feature = "crypto")]
+    fn read_audit_entry(&self, seq: u64) -> Result<AuditEntry> {
+        self.read_audit_record(&self.audit_entry_file(seq))
     }
 
-    /// Restore a scroll to an anchored state
-    ///
-    /// This creates a new version with the anchor's content.
-    /// The restoration itself is recorded in history.
-    pub fn restore(&self, path: &str, anchor_id: &str) -> Result<Scroll> {
-        let anchors = self.anchors(path)?;
-        let anchor = anchors
-            .into_iter()
-            .find(|a| a.id == anchor_id)
-            .ok_or_else(|| Error::NotFound(format!("anchor:{}", anchor_id)))?;
+    #[cfg(feature = "crypto")]
+    fn read_audit_record<T: DeserializeOwned>(&self, file_path: &Path) -> Result<T> {
+        let plaintext = unseal_record(&read_json(file_path)?, &self.audit_key()?)?;
+        serde_json::from_slice(&plaintext)
+            .map_err(|e| Error::Internal(format!("Failed to parse audit record: {}", e)))
+    }
 
-        // Verify anchor integrity
-        if !anchor::verify(&anchor) {
-            return Err(Error::Internal("Anchor integrity check failed".to_string()));
+    #[cfg(feature = "crypto")]
+    fn write_audit_record<T: Serialize>(&self, file_path: &Path, record: &T) -> Result<()> {
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| Error::Internal(format!("Failed to create audit dir: {}", e)))?;
         }
+        let plaintext = serde_json::to_vec(record)
+            .map_err(|e| Error::Internal(format!("Failed to serialize audit record: {}", e)))?;
+        let sealed = seal(&self.audit_key()?, &plaintext)
+            .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+        write_json_atomic(file_path, &sealed)
+    }
 
-        // Write the restored content (creates new patch in history)
-        // Use self.write_scroll_internal() which respects encryption
-        self.write_scroll_internal(anchor.scroll)
+    /// Derive the audit log subkey from the store key
+    #[cfg(feature = "crypto")]
+    fn audit_key(&self) -> Result<[u8; 32]> {
+        self.encryption_key
+            .as_ref()
+            .map(|key| crate::vault::crypto::derive_app_key(key, AUDIT_KEY_INFO))
+            .ok_or_else(|| Error::Permission("The audit log requires an encrypted store".to_string()))
     }
 
     // ========================================================================
@@ -630,6 +2588,12 @@ impl Store {
             None => self.auto_retention_threshold(path, &patches)?,
         };
 
+        // Log the compaction first so verify_integrity expects the gap
+        #[cfg(feature = "crypto")]
+        if patches.iter().any(|p| p.seq < threshold) {
+            self.append_audit(path, AuditAction::Compact { below: threshold })?;
+        }
+
         // Delete patches older than threshold
         let patches_dir = self.history_dir_for_path(path).join("patches");
         let mut removed = 0;
@@ -656,7 +2620,7 @@ impl Store {
     /// store.compact_all("/wallet")?;
     /// ```
     pub fn compact_all(&self, prefix: &str) -> Result<usize> {
-        let paths = self.inner.list(prefix)?;
+        let paths = self.list_paths(prefix)?;
         let mut total_removed = 0;
 
         for path in paths {
@@ -726,6 +2690,128 @@ impl Store {
         })
     }
 
+    /// Re-seal plaintext history written before history was encrypted
+    ///
+    /// Walks every patch and anchor under `_history/` and rewrites any
+    /// plaintext record as a sealed one, in place. Already-sealed records
+    /// are left untouched, so this is safe to run on every startup.
+    ///
+    /// Each file is replaced atomically (write to a temp file, then rename),
+    /// so an interrupted migration leaves every record readable and can
+    /// simply be run again.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let store = Store::open("beewallet", &master_key)?;
+    /// let resealed = store.migrate_history()?;
+    /// println!("sealed {} legacy history records", resealed);
+    /// ```
+    ///
+    /// # Returns
+    /// The number of records that were re-sealed.
+    #[cfg(feature = "crypto")]
+    pub fn migrate_history(&self) -> Result<usize> {
+        let key = match self.history_key() {
+            Some(key) => key,
+            None => return Ok(0),
+        };
+
+        let mut files = Vec::new();
+        collect_json_files(&self.base_dir.join("_history"), &mut files)?;
+
+        let mut resealed = 0;
+        for file_path in files {
+            let file = File::open(&file_path)
+                .map_err(|e| Error::Internal(format!("Failed to open history file: {}", e)))?;
+            let raw: Value = serde_json::from_reader(BufReader::new(file))
+                .map_err(|e| Error::Internal(format!("Failed to parse history file: {}", e)))?;
+
+            if is_sealed_record(&raw) {
+                continue;
+            }
+
+            let plaintext = serde_json::to_vec(&raw)
+                .map_err(|e| Error::Internal(format!("Failed to serialize history: {}", e)))?;
+            let sealed = seal(&key, &plaintext)
+                .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+            write_json_atomic(&file_path, &sealed)?;
+            resealed += 1;
+        }
+
+        Ok(resealed)
+    }
+
+    // ========================================================================
+    // Schema Migrations
+    // ========================================================================
+
+    /// Upgrade every scroll under `prefix` to the latest version of its type
+    ///
+    /// Reads already upgrade lazily; run this to finish the job up front,
+    /// e.g. before exporting a backup. Scrolls that are current are left
+    /// alone, so it is safe to run on every startup.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let store = Store::open("beewallet", &master_key)?
+    ///     .with_migrations(Arc::new(wallet_migrations()));
+    /// let upgraded = store.migrate("/wallet")?;
+    /// ```
+    ///
+    /// # Returns
+    /// The number of scrolls upgraded.
+    ///
+    /// # Errors
+    /// Stops at the first step that fails (`InvalidData`); scrolls upgraded
+    /// before it stay upgraded.
+    #[cfg(feature = "crypto")]
+    pub fn migrate(&self, prefix: &str) -> Result<usize> {
+        let Some(migrations) = &self.migrations else {
+            return Ok(0);
+        };
+
+        let mut upgraded = 0;
+        for path in self.list_paths(prefix)? {
+            let stale = self
+                .read_scroll(&path)?
+                .is_some_and(|scroll| migrations.applies_to(&scroll));
+            if stale {
+                self.migrate_path(&path)?;
+                upgraded += 1;
+            }
+        }
+        Ok(upgraded)
+    }
+
+    /// Upgrade one scroll under its path lock and write it back
+    ///
+    /// Re-reads under the lock, so a concurrent upgrade is not repeated.
+    #[cfg(feature = "crypto")]
+    fn migrate_path(&self, path: &str) -> Result<Option<Scroll>> {
+        let _lock = self.inner.lock_path(&self.disk_path(path))?;
+
+        let Some(current) = self.read_scroll(path)? else {
+            return Ok(None);
+        };
+        let upgraded = match &self.migrations {
+            Some(migrations) => migrations.upgrade(&current)?,
+            None => None,
+        };
+        let Some(upgraded) = upgraded else {
+            return Ok(Some(current));
+        };
+
+        let seq = self.next_seq(path);
+        let mut staged = self.prepare_write(upgraded, Some(&current), seq)?;
+        staged.patch.migration = Some(patch::TypeChange {
+            from: current.type_.clone(),
+            to: staged.scroll.type_.clone(),
+        });
+        self.apply_write(&staged)?;
+
+        Ok(Some(staged.scroll))
+    }
+
     // ========================================================================
     // Signal/Event Pruning (Storage Backpressure)
     // ========================================================================
@@ -755,7 +2841,7 @@ impl Store {
     /// # Returns
     /// The number of scrolls removed.
     pub fn prune(&self, prefix: &str, keep_count: usize) -> Result<usize> {
-        let paths = self.inner.list(prefix)?;
+        let paths = self.list_paths(prefix)?;
 
         if paths.len() <= keep_count {
             return Ok(0);
@@ -764,7 +2850,7 @@ impl Store {
         // Get scrolls with their timestamps for sorting
         let mut scrolls_with_time: Vec<(String, i64)> = Vec::new();
         for path in &paths {
-            if let Ok(Some(scroll)) = self.inner.read(path) {
+            if let Ok(Some(scroll)) = self.inner.read(&self.disk_path(path)) {
                 // Use updated_at or created_at timestamp (stored as Unix millis string)
                 let timestamp = scroll
                     .metadata
@@ -810,12 +2896,12 @@ impl Store {
         prefix: &str,
         max_age: std::time::Duration,
     ) -> Result<usize> {
-        let paths = self.inner.list(prefix)?;
+        let paths = self.list_paths(prefix)?;
         let cutoff = super::current_time_millis() - (max_age.as_millis() as i64);
         let mut removed = 0;
 
         for path in paths {
-            if let Ok(Some(scroll)) = self.inner.read(&path) {
+            if let Ok(Some(scroll)) = self.inner.read(&self.disk_path(&path)) {
                 // Timestamps stored as Unix millis strings
                 let timestamp = scroll
                     .metadata
@@ -838,30 +2924,147 @@ impl Store {
 
     /// Delete a scroll at the given path
     ///
-    /// Removes the scroll file. Does NOT remove history (use compact for that).
+    /// Removes the scroll file and records a tombstone patch, so history
+    /// shows when it went away and `state_at` replays to a deleted scroll.
+    /// `watch_decrypted` subscribers receive the last state marked deleted.
+    /// History is kept (use `purge` to erase it too).
+    ///
+    /// Writing a scroll marked deleted through `write_scroll` does the same.
+    /// Deleting a path with no scroll is a no-op.
     pub fn delete(&self, path: &str) -> Result<()> {
-        // Delegate to inner namespace
-        // FileNamespace stores at: _scrolls/{path}.json
-        let clean_path = path.trim_start_matches('/');
-        let file_path = self.base_dir.join("_scrolls").join(format!("{}.json", clean_path));
+        self.remove(path).map(|_| ())
+    }
 
-        if file_path.exists() {
-            fs::remove_file(&file_path)
-                .map_err(|e| Error::Internal(format!("Failed to delete scroll: {}", e)))?;
+    /// `delete` that hands back what it removed
+    ///
+    /// # Returns
+    /// The removed scroll marked deleted, or None if nothing was there.
+    pub fn remove(&self, path: &str) -> Result<Option<Scroll>> {
+        validate_path(path)?;
+        let _lock = self.inner.lock_path(&self.disk_path(path))?;
+        self.delete_locked(path)
+    }
+
+    /// `delete` for a caller already holding the path lock
+    fn delete_locked(&self, path: &str) -> Result<Option<Scroll>> {
+        match self.read_stored(path)? {
+            Some(current) => {
+                self.remove_with_tombstone(&current)?;
+                Ok(Some(current.mark_deleted()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Erase a scroll and its entire history
+    ///
+    /// For "forget this contact" requests: the ciphertext, every patch and
+    /// every anchor for the path are overwritten before being unlinked, and
+    /// the path is dropped from indexes. No tombstone is recorded. Watchers
+    /// receive a bare tombstone with `null` data. Scrolls nested under the
+    /// path keep their own history, and existing backups are untouched.
+    ///
+    /// Overwriting is best effort: journaling filesystems and flash storage
+    /// may keep stale blocks, which is why data at rest is encrypted.
+    ///
+    /// # Returns
+    /// False if there was neither a scroll nor history at the path.
+    pub fn purge(&self, path: &str) -> Result<bool> {
+        validate_path(path)?;
+        let disk_path = self.disk_path(path);
+        let _lock = self.inner.lock_path(&disk_path)?;
+
+        let had_scroll = self.inner.purge(&disk_path)?;
+
+        let history_dir = self.history_dir_for_path(path);
+
+        #[cfg(feature = "crypto")]
+        if history_dir.join("patches").is_dir() {
+            self.append_audit(path, AuditAction::Purge)?;
+        }
+
+        let mut erased = 0;
+        for sub in ["patches", "anchors"] {
+            let dir = history_dir.join(sub);
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                shred_file(&entry.path())
+                    .map_err(|e| Error::Internal(format!("Failed to erase history: {}", e)))?;
+                erased += 1;
+            }
+            let _ = fs::remove_dir(&dir);
+        }
+        // Only succeeds if no nested scroll has history underneath
+        let _ = fs::remove_dir(&history_dir);
+
+        #[cfg(feature = "crypto")]
+        {
+            self.update_indexes(&Scroll::new(path, Value::Null).mark_deleted())?;
+            self.forget_path(path)?;
+        }
+
+        Ok(had_scroll || erased > 0)
+    }
+
+    /// Remove every scroll whose `expires_at` has passed
+    ///
+    /// Each removal records a tombstone patch in history (so `state_at` and
+    /// snapshots see when the scroll went away), drops it from indexes, and
+    /// sends `watch_decrypted` subscribers the last state marked deleted.
+    /// Expired scrolls already read as absent; sweeping reclaims the space
+    /// and tells UIs to drop them.
+    ///
+    /// # Returns
+    /// The paths that were removed.
+    #[cfg(feature = "crypto")]
+    pub fn sweep_expired(&self) -> Result<Vec<String>> {
+        let mut swept = Vec::new();
+        for disk_path in self.inner.expired_paths()? {
+            let _lock = self.inner.lock_path(&disk_path)?;
+
+            // Re-check under the lock: it may have been rewritten since
+            match self.read_disk(&disk_path)? {
+                Some(scroll) if scroll.is_expired() => {
+                    self.remove_with_tombstone(&scroll)?;
+                    swept.push(scroll.key);
+                }
+                Some(_) => {}
+                // Only a stale TTL sidecar left
+                None => {
+                    self.inner.delete(&disk_path)?;
+                }
+            }
         }
+        Ok(swept)
+    }
+
+    /// Delete a scroll, recording a tombstone patch in its history
+    ///
+    /// Caller holds the path lock.
+    fn remove_with_tombstone(&self, current: &Scroll) -> Result<()> {
+        let mut tombstone = patch::diff::delete(&current.key, current);
+        tombstone.seq = self.next_seq(&current.key);
+        self.store_patch(&tombstone)?;
+
+        self.inner.delete(&self.disk_path(&current.key))?;
+
+        #[cfg(feature = "crypto")]
+        self.update_indexes(&current.clone().mark_deleted())?;
 
         Ok(())
     }
 
     /// Get statistics about a prefix (for monitoring)
     pub fn prefix_stats(&self, prefix: &str) -> Result<PrefixStats> {
-        let paths = self.inner.list(prefix)?;
+        let paths = self.list_paths(prefix)?;
         let mut total_bytes: u64 = 0;
         let mut oldest_timestamp: Option<i64> = None;
         let mut newest_timestamp: Option<i64> = None;
 
         for path in &paths {
-            if let Ok(Some(scroll)) = self.inner.read(path) {
+            if let Ok(Some(scroll)) = self.inner.read(&self.disk_path(path)) {
                 // Estimate size from JSON
                 if let Ok(json) = serde_json::to_string(&scroll) {
                     total_bytes += json.len() as u64;
@@ -896,6 +3099,7 @@ impl Store {
     /// - `/signals/task-failure/*` - keep last 100
     /// - `/ln/events/*` - keep last 1000
     /// - `/hive/events/*` - keep last 500
+    /// - expired scrolls anywhere (crypto builds, see `sweep_expired`)
     ///
     /// Call periodically (e.g., on app startup or daily).
     pub fn auto_maintenance(&self) -> Result<MaintenanceReport> {
@@ -913,6 +3117,12 @@ impl Store {
         // Compact wallet history
         report.wallet_patches_compacted = self.compact_all("/wallet").unwrap_or(0);
 
+        // Sweep expired scrolls (invoices, offers, ...)
+        #[cfg(feature = "crypto")]
+        {
+            report.scrolls_expired = self.sweep_expired().map(|p| p.len()).unwrap_or(0);
+        }
+
         Ok(report)
     }
 
@@ -922,26 +3132,100 @@ impl Store {
 
     /// Get the history directory for a scroll path
     fn history_dir_for_path(&self, scroll_path: &str) -> PathBuf {
-        // /foo/bar -> _history/foo/bar/
-        let clean_path = scroll_path.trim_start_matches('/');
+        // /foo/bar -> _history/foo/bar/ (or _history/{hmac}/ when blinded)
+        let disk_path = self.disk_path(scroll_path);
+        let clean_path = disk_path.trim_start_matches('/');
         self.base_dir.join("_history").join(clean_path)
     }
 
     /// Store a patch in history
     fn store_patch(&self, patch: &Patch) -> Result<()> {
+        #[cfg(feature = "crypto")]
+        self.note_path(&patch.key)?;
+
+        // Log first: a crash before the patch lands leaves an entry the
+        // next write at this seq supersedes, never a patch the log missed
+        #[cfg(feature = "crypto")]
+        self.append_audit(
+            &patch.key,
+            AuditAction::Patch {
+                seq: patch.seq,
+                digest: audit::patch_digest(patch),
+            },
+        )?;
+
         let patches_dir = self.history_dir_for_path(&patch.key).join("patches");
         fs::create_dir_all(&patches_dir)
             .map_err(|e| Error::Internal(format!("Failed to create patches dir: {}", e)))?;
 
         // Use seq number for ordering
         let patch_path = patches_dir.join(format!("{:08}.json", patch.seq));
-        let file = File::create(&patch_path)
-            .map_err(|e| Error::Internal(format!("Failed to create patch file: {}", e)))?;
+        self.write_record(&patch_path, patch)
+    }
 
-        serde_json::to_writer_pretty(BufWriter::new(file), patch)
-            .map_err(|e| Error::Internal(format!("Failed to write patch: {}", e)))?;
+    /// Store an anchor in history
+    fn store_anchor(&self, path: &str, anchor: &Anchor) -> Result<()> {
+        #[cfg(feature = "crypto")]
+        self.note_path(path)?;
 
-        Ok(())
+        let anchors_dir = self.history_dir_for_path(path).join("anchors");
+        fs::create_dir_all(&anchors_dir)
+            .map_err(|e| Error::Internal(format!("Failed to create anchors dir: {}", e)))?;
+
+        let anchor_path = anchors_dir.join(format!("{}.json", anchor.id));
+        self.write_record(&anchor_path, anchor)
+    }
+
+    /// Write a history or journal record, sealed when encrypted
+    ///
+    /// Encrypted stores write a bare `SealedValue` envelope; the record's
+    /// key, ops and timestamps are only visible after unsealing.
+    fn write_record<T: Serialize>(&self, file_path: &Path, record: &T) -> Result<()> {
+        #[cfg(feature = "crypto")]
+        if let Some(key) = self.history_key() {
+            let plaintext = serde_json::to_vec(record)
+                .map_err(|e| Error::Internal(format!("Failed to serialize history: {}", e)))?;
+            let sealed = seal(&key, &plaintext)
+                .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+            return write_json_atomic(file_path, &sealed);
+        }
+
+        write_json_atomic(file_path, record)
+    }
+
+    /// Read a history or journal record, unsealing it if necessary
+    ///
+    /// Plaintext records (written before history was encrypted) are still
+    /// accepted so that un-migrated stores keep working.
+    fn read_record<T: DeserializeOwned>(&self, file_path: &Path) -> Result<T> {
+        let file = File::open(file_path)
+            .map_err(|e| Error::Internal(format!("Failed to open history file: {}", e)))?;
+        let raw: Value = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| Error::Internal(format!("Failed to parse history file: {}", e)))?;
+
+        #[cfg(feature = "crypto")]
+        if is_sealed_record(&raw) {
+            let key = self
+                .history_key()
+                .ok_or_else(|| Error::Internal("History is sealed but store has no key".to_string()))?;
+            let sealed: SealedValue = serde_json::from_value(raw)
+                .map_err(|e| Error::Internal(format!("Failed to parse sealed history: {}", e)))?;
+            let plaintext = unseal(&key, &sealed)
+                .map_err(|e| Error::Internal(format!("Decryption failed: {}", e)))?;
+            return serde_json::from_slice(&plaintext)
+                .map_err(|e| Error::Internal(format!("Failed to parse history: {}", e)));
+        }
+
+        serde_json::from_value(raw)
+            .map_err(|e| Error::Internal(format!("Failed to parse history: {}", e)))
+    }
+
+    /// Derive the history subkey from the store key
+    #[cfg(feature = "crypto")]
+    fn history_key(&self) -> Option<[u8; 32]> {
+        self.encryption_key
+            .as_ref()
+            .map(|key| crate::vault::crypto::derive_app_key(key, HISTORY_KEY_INFO))
     }
 
     /// Get the next sequence number for a path (CSP-style monotonic counter)
@@ -978,21 +3262,128 @@ impl Store {
     }
 }
 
-/// Get the 9S root directory
-///
-/// Checks `NINE_S_ROOT` environment variable first, then falls back to `~/.nine_s`.
+/// Get the 9S root directory
+///
+/// Checks `NINE_S_ROOT` environment variable first, then falls back to `~/.nine_s`.
+///
+/// # Security
+/// The env var allows container/sandbox environments to redirect storage
+/// without affecting app logic.
+#[cfg(feature = "crypto")]
+fn nine_s_root() -> Result<PathBuf> {
+    if let Ok(root) = std::env::var("NINE_S_ROOT") {
+        return Ok(PathBuf::from(root));
+    }
+    dirs::home_dir()
+        .map(|h| h.join(".nine_s"))
+        .ok_or_else(|| Error::Internal("Could not determine home directory".to_string()))
+}
+
+/// Check if path is under prefix with proper segment boundary
+fn is_path_under_prefix(path: &str, prefix: &str) -> bool {
+    if prefix == "/" {
+        return path.starts_with('/');
+    }
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Collect every scroll path with recorded patches under a history dir
+///
+/// `_history/{path}/patches/` marks a scroll; `anchors/` is skipped.
+fn collect_history_paths(dir: &Path, scroll_path: &str, out: &mut Vec<String>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)
+        .map_err(|e| Error::Internal(format!("Failed to read history dir: {}", e)))?
+    {
+        let entry = entry.map_err(|e| Error::Internal(format!("Failed to read entry: {}", e)))?;
+        if !entry.path().is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().into_owned();
+        match name.as_str() {
+            "patches" if !scroll_path.is_empty() => out.push(scroll_path.to_string()),
+            "patches" | "anchors" => {}
+            _ => collect_history_paths(&entry.path(), &format!("{}/{}", scroll_path, name), out)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a JSON record atomically and durably (temp file + fsync + rename)
 ///
-/// # Security
-/// The env var allows container/sandbox environments to redirect storage
-/// without affecting app logic.
+/// A crash mid-write leaves either the old file or the new one, never a
+/// truncated record. The temp file is synced before the rename and the
+/// directory after it, so a record that was written survives power loss.
+fn write_json_atomic<T: Serialize + ?Sized>(file_path: &Path, value: &T) -> Result<()> {
+    let tmp_path = file_path.with_extension("json.tmp");
+    let file = File::create(&tmp_path)
+        .map_err(|e| Error::Internal(format!("Failed to create history file: {}", e)))?;
+
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, value)
+        .map_err(|e| Error::Internal(format!("Failed to write history file: {}", e)))?;
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())
+        .and_then(|f| f.sync_all())
+        .map_err(|e| Error::Internal(format!("Failed to sync history file: {}", e)))?;
+
+    fs::rename(&tmp_path, file_path)
+        .map_err(|e| Error::Internal(format!("Failed to commit history file: {}", e)))?;
+    sync_parent_dir(file_path)
+}
+
+/// Flush a directory entry change (create, rename, unlink) to disk
+#[cfg(unix)]
+fn sync_parent_dir(file_path: &Path) -> Result<()> {
+    let Some(dir) = file_path.parent() else {
+        return Ok(());
+    };
+    File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(|e| Error::Internal(format!("Failed to sync {:?}: {}", dir, e)))
+}
+
+/// Directories can't be opened for syncing here; the rename is still atomic
+#[cfg(not(unix))]
+fn sync_parent_dir(_file_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Check whether a history record on disk is a sealed envelope
 #[cfg(feature = "crypto")]
-fn nine_s_root() -> Result<PathBuf> {
-    if let Ok(root) = std::env::var("NINE_S_ROOT") {
-        return Ok(PathBuf::from(root));
+fn is_sealed_record(raw: &Value) -> bool {
+    raw.get("ciphertext").is_some() && raw.get("nonce").is_some()
+}
+
+/// Recursively collect `.json` files under a directory
+#[cfg(feature = "crypto")]
+fn collect_json_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
     }
-    dirs::home_dir()
-        .map(|h| h.join(".nine_s"))
-        .ok_or_else(|| Error::Internal("Could not determine home directory".to_string()))
+
+    for entry in fs::read_dir(dir)
+        .map_err(|e| Error::Internal(format!("Failed to read dir: {}", e)))?
+    {
+        let entry = entry.map_err(|e| Error::Internal(format!("Failed to read entry: {}", e)))?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_json_files(&path, out)?;
+        } else if path.extension().is_some_and(|e| e == "json") {
+            out.push(path);
+        }
+    }
+
+    Ok(())
 }
 
 /// Validate app_key for security
@@ -1049,30 +3440,12 @@ fn validate_app_key(app_key: &str) -> Result<()> {
 #[cfg(feature = "crypto")]
 impl Namespace for Store {
     fn read(&self, path: &str) -> Result<Option<Scroll>> {
-        match self.encryption_key {
-            Some(ref key) => {
-                // Read encrypted scroll
-                let sealed_opt = self.inner.read(path)?;
-                match sealed_opt {
-                    Some(sealed_scroll) => {
-                        // The data field contains the SealedValue
-                        let sealed: SealedValue = serde_json::from_value(sealed_scroll.data)
-                            .map_err(|e| Error::Internal(format!("Failed to parse sealed data: {}", e)))?;
-
-                        // Decrypt
-                        let plaintext = unseal(key, &sealed)
-                            .map_err(|e| Error::Internal(format!("Decryption failed: {}", e)))?;
-
-                        // Deserialize scroll
-                        let scroll: Scroll = serde_json::from_slice(&plaintext)
-                            .map_err(|e| Error::Internal(format!("Failed to parse scroll: {}", e)))?;
-
-                        Ok(Some(scroll))
-                    }
-                    None => Ok(None),
-                }
+        match self.read_scroll(path)? {
+            Some(scroll) if self.migrations.as_ref().is_some_and(|m| m.applies_to(&scroll)) => {
+                // The stored version is still readable; `migrate` reports the failure
+                self.migrate_path(path).or(Ok(Some(scroll)))
             }
-            None => self.inner.read(path),
+            other => Ok(other),
         }
     }
 
@@ -1082,72 +3455,28 @@ impl Namespace for Store {
     }
 
     fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
-        // Get current state before write (decrypted if encrypted)
-        let old = self.read(&scroll.key)?;
-
-        // CSP: Derive sequence from filesystem (monotonic counter)
-        // This is the source of truth, like Bitcoin's block height
-        let new_version = self.next_seq(&scroll.key);
-
-        match self.encryption_key {
-            Some(ref key) => {
-                // Update scroll with version from filesystem
-                let mut versioned_scroll = scroll.clone();
-                versioned_scroll.metadata.version = new_version;
-                versioned_scroll.metadata.hash = Some(versioned_scroll.compute_hash());
-                if versioned_scroll.metadata.created_at.is_none() {
-                    versioned_scroll.metadata.created_at = Some(crate::nine_s::current_iso_time());
-                }
-                versioned_scroll.metadata.updated_at = Some(crate::nine_s::current_iso_time());
-
-                // Serialize versioned scroll
-                let plaintext = serde_json::to_vec(&versioned_scroll)
-                    .map_err(|e| Error::Internal(format!("Failed to serialize scroll: {}", e)))?;
-
-                // Encrypt
-                let sealed = seal(key, &plaintext)
-                    .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
-
-                // Store as a scroll with SealedValue as data
-                let sealed_data = serde_json::to_value(&sealed)
-                    .map_err(|e| Error::Internal(format!("Failed to serialize sealed: {}", e)))?;
-
-                // Write encrypted data to inner store
-                self.inner.write(&scroll.key, sealed_data)?;
-
-                // Create patch with CSP-derived sequence
-                let mut patch = patch::diff::create(&scroll.key, old.as_ref(), &versioned_scroll);
-                patch.seq = new_version; // Override with filesystem-derived seq
-                self.store_patch(&patch)?;
-
-                // Return the versioned scroll
-                Ok(versioned_scroll)
-            }
-            None => {
-                // Update scroll with version from filesystem
-                let mut versioned_scroll = scroll.clone();
-                versioned_scroll.metadata.version = new_version;
-
-                // Write to inner store
-                let result = self.inner.write_scroll(versioned_scroll.clone())?;
+        // A tombstone deletes (and is recorded in history)
+        if scroll.is_deleted() {
+            return Ok(self.remove(&scroll.key)?.unwrap_or(scroll));
+        }
 
-                // Create patch with CSP-derived sequence
-                let mut patch = patch::diff::create(&scroll.key, old.as_ref(), &versioned_scroll);
-                patch.seq = new_version; // Override with filesystem-derived seq
-                self.store_patch(&patch)?;
+        // Same lock as write_if, so the next seq and the parent the patch
+        // diffs against can't change underneath us
+        validate_path(&scroll.key)?;
+        let _lock = self.inner.lock_path(&self.disk_path(&scroll.key))?;
 
-                Ok(result)
-            }
-        }
+        // Versioned, sealed, and recorded in history
+        self.write_scroll_internal(scroll)
     }
 
     fn list(&self, prefix: &str) -> Result<Vec<String>> {
-        self.inner.list(prefix)
+        self.list_paths(prefix)
     }
 
     fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
         // For encrypted stores, use watch_decrypted() instead
-        // This returns raw (encrypted) scrolls for backwards compatibility
+        // This returns raw (encrypted) scrolls for backwards compatibility;
+        // with path blinding only on-disk names match
         self.inner.watch(pattern)
     }
 
@@ -1184,7 +3513,17 @@ impl Store {
 
         match &self.encryption_key {
             Some(key) => {
-                let mut inner_rx = self.inner.watch(pattern)?;
+                // Blinded files all sit at the top level, so watch them all
+                // and match the pattern against decrypted paths instead
+                let names = self.blinding.as_ref().map(|b| Arc::clone(&b.names));
+                let mut inner_rx = match names {
+                    Some(_) => {
+                        validate_path(pattern)?;
+                        self.inner.watch("/*")?
+                    }
+                    None => self.inner.watch(pattern)?,
+                };
+                let pattern = pattern.to_string();
                 let key = *key;
 
                 // Create a new channel for decrypted scrolls
@@ -1193,10 +3532,37 @@ impl Store {
                 // Spawn a thread to decrypt scrolls
                 // (Using thread because Receiver is sync, not async)
                 std::thread::spawn(move || {
-                    while let Some(sealed_scroll) = inner_rx.recv() {
+                    while let Some(mut sealed_scroll) = inner_rx.recv() {
+                        // Purged scrolls arrive as bare tombstones
+                        if sealed_scroll.is_deleted() && sealed_scroll.data.is_null() {
+                            if let Some(ref names) = names {
+                                let name = sealed_scroll.key.trim_start_matches('/');
+                                match names.read().unwrap().get(name) {
+                                    Some(path) if path_matches(path, &pattern) => {
+                                        sealed_scroll.key = path.clone()
+                                    }
+                                    _ => continue,
+                                }
+                            }
+                            if tx.send(sealed_scroll).is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+
                         // Attempt to decrypt
                         match decrypt_scroll(&sealed_scroll, &key) {
                             Ok(scroll) => {
+                                if names.is_some() && !path_matches(&scroll.key, &pattern) {
+                                    continue;
+                                }
+
+                                // Deletions (and expiry) carry the last state
+                                let scroll = if sealed_scroll.is_deleted() {
+                                    scroll.mark_deleted()
+                                } else {
+                                    scroll
+                                };
                                 if tx.send(scroll).is_err() {
                                     // Receiver dropped, stop processing
                                     break;
@@ -1223,6 +3589,184 @@ impl Store {
     }
 }
 
+/// Seal a scroll into the `SealedValue` JSON stored in `_scrolls/`
+#[cfg(feature = "crypto")]
+fn seal_scroll(scroll: &Scroll, key: &[u8; 32]) -> Result<Value> {
+    let plaintext = serde_json::to_vec(scroll)
+        .map_err(|e| Error::Internal(format!("Failed to serialize scroll: {}", e)))?;
+    let sealed = seal(key, &plaintext)
+        .map_err(|e| Error::Internal(format!("Encryption failed: {}", e)))?;
+    serde_json::to_value(&sealed)
+        .map_err(|e| Error::Internal(format!("Failed to serialize sealed: {}", e)))
+}
+
+/// Wrap a sealed payload for `_scrolls/` under its on-disk name
+///
+/// Only `expires_at` is copied out of the ciphertext, so `FileNamespace`
+/// can hide and sweep expired scrolls without the key.
+#[cfg(feature = "crypto")]
+fn sealed_wrapper(disk_path: &str, scroll: &Scroll, payload: Value) -> Scroll {
+    let mut wrapper = Scroll::new(disk_path, payload);
+    wrapper.metadata.expires_at = scroll.metadata.expires_at.clone();
+    wrapper
+}
+
+/// Lock path guarding the manifest's read-modify-write
+#[cfg(feature = "crypto")]
+const MANIFEST_LOCK_PATH: &str = "/_manifest";
+
+#[cfg(feature = "crypto")]
+impl Blinding {
+    fn from_manifest(manifest: &Manifest) -> Result<Self> {
+        let name_key: [u8; 32] = hex::decode(&manifest.name_key)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| Error::InvalidData("Bad name key in path manifest".to_string()))?;
+
+        let blinding = Self {
+            name_key,
+            names: Arc::new(RwLock::new(HashMap::new())),
+        };
+        let names = manifest
+            .paths
+            .iter()
+            .map(|p| (blinding.blind(p), p.clone()))
+            .collect();
+        *blinding.names.write().unwrap() = names;
+        Ok(blinding)
+    }
+
+    /// HMAC of a scroll path, hex encoded
+    fn blind(&self, path: &str) -> String {
+        hex::encode(crate::vault::crypto::hmac_sha256(&self.name_key, path.as_bytes()))
+    }
+}
+
+/// Remove empty directories below `dir` (keeping `dir` itself)
+#[cfg(feature = "crypto")]
+fn remove_empty_dirs(dir: &Path) -> Result<()> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_empty_dirs(&path)?;
+            // Fails (and is ignored) unless now empty
+            let _ = fs::remove_dir(&path);
+        }
+    }
+    Ok(())
+}
+
+/// Lock path guarding an index file's read-modify-write
+#[cfg(feature = "crypto")]
+fn index_lock_path(name: &str) -> String {
+    format!("/_index/{}", name)
+}
+
+/// Fingerprint a key for the rotation marker (HKDF, not reversible)
+#[cfg(feature = "crypto")]
+fn key_id(key: &[u8; 32]) -> String {
+    hex::encode(&crate::vault::crypto::derive_app_key(key, KEY_ID_INFO)[..8])
+}
+
+/// Unseal a bare `SealedValue` record
+#[cfg(feature = "crypto")]
+fn unseal_record(raw: &Value, key: &[u8; 32]) -> Result<Vec<u8>> {
+    let sealed: SealedValue = serde_json::from_value(raw.clone())
+        .map_err(|e| Error::Internal(format!("Failed to parse sealed record: {}", e)))?;
+    unseal(key, &sealed).map_err(|e| Error::Internal(format!("Decryption failed: {}", e)))
+}
+
+/// Read and parse a JSON file
+#[cfg(feature = "crypto")]
+fn read_json<T: DeserializeOwned>(file_path: &Path) -> Result<T> {
+    let file = File::open(file_path)
+        .map_err(|e| Error::Internal(format!("Failed to open {:?}: {}", file_path, e)))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| Error::Internal(format!("Failed to parse {:?}: {}", file_path, e)))
+}
+
+/// Write a staged copy durably, creating parent directories
+#[cfg(feature = "crypto")]
+fn write_staged<T: Serialize>(file_path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| Error::Internal(format!("Failed to create rekey dir: {}", e)))?;
+    }
+    write_json_atomic(file_path, value)
+}
+
+/// Validate a decrypted backup before anything touches disk
+#[cfg(feature = "crypto")]
+fn validate_backup(payload: &BackupPayload) -> Result<()> {
+    let mut current: HashMap<&str, &Scroll> = HashMap::new();
+    for scroll in &payload.scrolls {
+        validate_path(&scroll.key)?;
+        if let Some(ref hash) = scroll.metadata.hash {
+            if *hash != scroll.compute_hash() {
+                return Err(Error::InvalidData(format!("Scroll hash mismatch: {}", scroll.key)));
+            }
+        }
+        current.insert(&scroll.key, scroll);
+    }
+
+    for entry in &payload.history {
+        validate_path(&entry.path)?;
+
+        // Hash chain: each patch's parent is the previous patch's result,
+        // except a re-creation after a tombstone, which starts afresh
+        for pair in entry.patches.windows(2) {
+            let rebirth = pair[0].deleted && pair[1].parent.is_none();
+            if pair[1].seq <= pair[0].seq || (!rebirth && pair[1].parent.as_ref() != Some(&pair[0].hash)) {
+                return Err(Error::InvalidData(format!(
+                    "Broken hash chain at {} seq {}",
+                    entry.path, pair[1].seq
+                )));
+            }
+        }
+        if entry.patches.iter().any(|p| p.key != entry.path) {
+            return Err(Error::InvalidData(format!("Patch key mismatch: {}", entry.path)));
+        }
+
+        if let (Some(last), Some(scroll)) = (entry.patches.last(), current.get(entry.path.as_str())) {
+            if last.hash != scroll.compute_hash() {
+                return Err(Error::InvalidData(format!(
+                    "History does not end at current state: {}",
+                    entry.path
+                )));
+            }
+
+            // Complete history (from the latest birth) must replay to the same data
+            if let Some(birth) = entry.patches.iter().rposition(|p| p.parent.is_none()) {
+                let mut replayed = Scroll::new(&entry.path, serde_json::json!({}));
+                for p in &entry.patches[birth..] {
+                    replayed = patch::diff::apply(&replayed, p)
+                        .map_err(|e| Error::InvalidData(format!("Failed to replay patch: {}", e)))?;
+                }
+                if replayed.data != scroll.data {
+                    return Err(Error::InvalidData(format!(
+                        "History replay diverges from current state: {}",
+                        entry.path
+                    )));
+                }
+            }
+        }
+
+        for a in &entry.anchors {
+            if a.scroll.key != entry.path || !anchor::verify(a) {
+                return Err(Error::InvalidData(format!(
+                    "Anchor integrity check failed: {}",
+                    a.id
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Decrypt a sealed scroll
 #[cfg(feature = "crypto")]
 fn decrypt_scroll(sealed_scroll: &Scroll, key: &[u8; 32]) -> Result<Scroll> {
@@ -1250,6 +3794,7 @@ fn decrypt_scroll(sealed_scroll: &Scroll, key: &[u8; 32]) -> Result<Scroll> {
 #[cfg(all(test, feature = "crypto"))]
 mod tests {
     use super::*;
+    use crate::nine_s::scroll::current_time_millis;
     use serde_json::json;
     use tempfile::tempdir;
 
@@ -1360,10 +3905,12 @@ mod tests {
     #[test]
     fn store_history_preserved_across_instances() {
         let dir = tempdir().unwrap();
+        // History is sealed, so every instance must share the key
+        let key = Store::test_key();
 
         // Write with first instance
         {
-            let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+            let store = Store::at(dir.path(), &key).unwrap();
             store.write("/test", json!(1)).unwrap();
             store.write("/test", json!(2)).unwrap();
             store.anchor("/test", Some("v2")).unwrap();
@@ -1371,7 +3918,7 @@ mod tests {
 
         // Read history with second instance
         {
-            let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+            let store = Store::at(dir.path(), &key).unwrap();
             let patches = store.history("/test").unwrap();
             assert_eq!(patches.len(), 2);
 
@@ -1466,6 +4013,342 @@ mod tests {
         assert_eq!(restored.data["badge"], "pro");
     }
 
+    #[test]
+    fn snapshot_at_reconstructs_prefix() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+
+        store.write("/wallet/balance", json!({"sats": 100})).unwrap();
+        store.write("/wallet/tx/a", json!({"amount": 100})).unwrap();
+        store.write("/other", json!({"x": 1})).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let cutoff = current_time_millis();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        store.write("/wallet/balance", json!({"sats": 60})).unwrap();
+        store.write("/wallet/tx/b", json!({"amount": -40})).unwrap();
+
+        let snapshot = store.snapshot_at("/wallet", cutoff).unwrap();
+        assert_eq!(snapshot.len(), 2);
+
+        let balance = snapshot.read("/wallet/balance").unwrap().unwrap();
+        assert_eq!(balance.data["sats"], 100);
+        assert_eq!(balance.metadata.version, 1);
+
+        let mut txs = snapshot.list("/wallet/tx").unwrap();
+        txs.sort();
+        assert_eq!(txs, vec!["/wallet/tx/a".to_string()]);
+        assert!(snapshot.read("/wallet/tx/b").unwrap().is_none());
+        assert!(snapshot.read("/other").unwrap().is_none());
+
+        // Current state is untouched
+        assert_eq!(store.read("/wallet/balance").unwrap().unwrap().data["sats"], 60);
+
+        // Whole store, now
+        let now = store.snapshot_at("/", current_time_millis()).unwrap();
+        assert_eq!(now.len(), 4);
+        assert_eq!(now.read("/wallet/balance").unwrap().unwrap().data["sats"], 60);
+    }
+
+    #[test]
+    fn snapshot_is_read_only() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/doc", json!({"v": 1})).unwrap();
+
+        let snapshot = store.snapshot_at("/", current_time_millis()).unwrap();
+        assert!(matches!(snapshot.write("/doc", json!({"v": 2})), Err(Error::Permission(_))));
+        assert!(snapshot.watch("/doc").is_err());
+
+        // Before anything was written
+        assert!(store.snapshot_at("/", 0).unwrap().is_empty());
+    }
+
+    // ========================================================================
+    // Deletion Tests
+    // ========================================================================
+
+    #[test]
+    fn delete_records_tombstone_and_notifies() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/contacts/alice", json!({"npub": "npub1"})).unwrap();
+        store.write("/contacts/bob", json!({"npub": "npub2"})).unwrap();
+        let mut rx = store.watch_decrypted("/contacts/*").unwrap();
+
+        let removed = store.remove("/contacts/alice").unwrap().unwrap();
+        assert!(removed.is_deleted());
+        assert!(store.remove("/contacts/alice").unwrap().is_none());
+        store.delete("/contacts/alice").unwrap();
+
+        assert!(store.read("/contacts/alice").unwrap().is_none());
+        assert_eq!(store.list("/contacts").unwrap(), vec!["/contacts/bob".to_string()]);
+
+        let event = rx.recv().unwrap();
+        assert!(event.is_deleted());
+        assert_eq!(event.data["npub"], "npub1");
+
+        let history = store.history("/contacts/alice").unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history[1].deleted);
+
+        // A tombstone written through the Namespace ops deletes too
+        store
+            .write_scroll(Scroll::new("/contacts/bob", Value::Null).mark_deleted())
+            .unwrap();
+        assert!(store.list("/contacts").unwrap().is_empty());
+        assert!(store.history("/contacts/bob").unwrap()[1].deleted);
+    }
+
+    #[test]
+    fn purge_erases_scroll_and_history() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/contacts/alice", json!({"npub": "npub1"})).unwrap();
+        store.write("/contacts/alice", json!({"npub": "npub2"})).unwrap();
+        store.anchor("/contacts/alice", Some("met")).unwrap();
+        store.write("/contacts/alice/notes", json!("kept")).unwrap();
+        let mut rx = store.watch_decrypted("/contacts/alice").unwrap();
+
+        assert!(store.purge("/contacts/alice").unwrap());
+        assert!(!store.purge("/contacts/alice").unwrap());
+
+        assert!(store.read("/contacts/alice").unwrap().is_none());
+        assert!(store.history("/contacts/alice").unwrap().is_empty());
+        assert!(store.anchors("/contacts/alice").unwrap().is_empty());
+        assert!(!dir.path().join("_history/contacts/alice/patches").exists());
+
+        // Watchers learn it's gone, not what it was
+        let event = rx.recv().unwrap();
+        assert!(event.is_deleted());
+        assert!(event.data.is_null());
+
+        // Nested scrolls are separate paths
+        assert_eq!(store.history("/contacts/alice/notes").unwrap().len(), 1);
+    }
+
+    #[cfg(feature = "keys")]
+    #[test]
+    fn restore_signed_requires_the_expected_signer() {
+        use crate::keys::MasterKey;
+        use crate::nostr::NostrSigner;
+
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        let master = MasterKey::from_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        let signer = NostrSigner::from_master_key(&master).unwrap();
+        let stranger = NostrSigner::from_master_key_with_account(&master, 1).unwrap();
+        let me = signer.public_key_hex();
+
+        store.write("/wallet/balance", json!({"sats": 100})).unwrap();
+        let signed = store.anchor_signed("/wallet/balance", Some("ok"), &signer).unwrap();
+        let unsigned = store.anchor("/wallet/balance", None).unwrap();
+        let foreign = store.anchor_signed("/wallet/balance", None, &stranger).unwrap();
+        store.write("/wallet/balance", json!({"sats": 0})).unwrap();
+
+        assert!(matches!(
+            store.restore_signed("/wallet/balance", &unsigned.id, &me),
+            Err(Error::Permission(_))
+        ));
+        assert!(matches!(
+            store.restore_signed("/wallet/balance", &foreign.id, &me),
+            Err(Error::Permission(_))
+        ));
+        assert_eq!(store.read("/wallet/balance").unwrap().unwrap().data["sats"], 0);
+
+        // Signatures survive being sealed to disk and read back
+        let restored = store.restore_signed("/wallet/balance", &signed.id, &me).unwrap();
+        assert_eq!(restored.data["sats"], 100);
+    }
+
+    // ========================================================================
+    // Audit Log Tests
+    // ========================================================================
+
+    #[test]
+    fn audit_log_covers_writes_deletes_compaction_and_purge() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+        let store = Store::at(dir.path(), &key).unwrap();
+
+        for i in 0..4 {
+            store.write("/wallet/balance", json!({"sats": i})).unwrap();
+        }
+        store.write("/contacts/alice", json!({"npub": "npub1"})).unwrap();
+        store.write("/contacts/bob", json!({"npub": "npub2"})).unwrap();
+        store.delete("/contacts/bob").unwrap();
+        store.compact("/wallet/balance", Some(3)).unwrap();
+        store.purge("/contacts/alice").unwrap();
+        store.write("/contacts/alice", json!({"npub": "npub3"})).unwrap();
+
+        let report = store.verify_integrity().unwrap();
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!(report.entries, 10); // 7 patches, compact, purge, 1 more patch
+        assert_eq!(report.head.as_ref().unwrap().seq, 10);
+
+        // Entries are sealed: no paths or digests on disk
+        let raw = fs::read_to_string(dir.path().join("_audit/000000000001.json")).unwrap();
+        assert!(!raw.contains("wallet"));
+
+        // Survives key rotation
+        let new_key = Store::test_key();
+        let mut store = store;
+        store.rotate_key(&key, &new_key).unwrap();
+        assert!(store.verify_integrity().unwrap().is_intact());
+    }
+
+    #[test]
+    fn verify_integrity_detects_tampered_history() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        for i in 0..3 {
+            store.write("/wallet/tx/a", json!({"amount": i})).unwrap();
+        }
+        store.write("/wallet/tx/b", json!({"amount": 9})).unwrap();
+        let patches = dir.path().join("_history/wallet/tx/a/patches");
+
+        // A deleted patch file
+        let saved = fs::read(patches.join("00000002.json")).unwrap();
+        fs::remove_file(patches.join("00000002.json")).unwrap();
+        assert_eq!(
+            store.verify_integrity().unwrap().issues,
+            vec![IntegrityIssue::MissingPatch { path: "/wallet/tx/a".into(), seq: 2 }]
+        );
+
+        // Two patches swapped
+        fs::rename(patches.join("00000003.json"), patches.join("00000002.json")).unwrap();
+        fs::write(patches.join("00000003.json"), &saved).unwrap();
+        let issues = store.verify_integrity().unwrap().issues;
+        assert!(issues.contains(&IntegrityIssue::RewrittenPatch { path: "/wallet/tx/a".into(), seq: 2 }));
+        assert!(issues.contains(&IntegrityIssue::RewrittenPatch { path: "/wallet/tx/a".into(), seq: 3 }));
+
+        // A patch from another scroll spliced in
+        fs::rename(patches.join("00000002.json"), patches.join("00000004.json")).unwrap();
+        fs::write(patches.join("00000002.json"), &saved).unwrap();
+        let b_patch = dir.path().join("_history/wallet/tx/b/patches/00000001.json");
+        fs::copy(&b_patch, patches.join("00000005.json")).unwrap();
+        let issues = store.verify_integrity().unwrap().issues;
+        assert!(issues.contains(&IntegrityIssue::RewrittenPatch { path: "/wallet/tx/a".into(), seq: 3 }));
+        assert!(issues.contains(&IntegrityIssue::UnloggedPatch { path: "/wallet/tx/a".into(), seq: 4 }));
+        assert!(issues.contains(&IntegrityIssue::UnloggedPatch { path: "/wallet/tx/a".into(), seq: 5 }));
+
+        // A gap in the log itself
+        fs::remove_file(dir.path().join("_audit/000000000002.json")).unwrap();
+        let issues = store.verify_integrity().unwrap().issues;
+        assert!(issues.contains(&IntegrityIssue::MissingEntry { seq: 2 }));
+    }
+
+    #[test]
+    fn patch_logged_before_a_crash_is_recoverable() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/wallet/balance", json!({"sats": 1})).unwrap();
+        store.write("/wallet/balance", json!({"sats": 2})).unwrap();
+        let patches = dir.path().join("_history/wallet/balance/patches");
+
+        // Logged, then the process died before the patch landed
+        fs::remove_file(patches.join("00000002.json")).unwrap();
+        let report = store.verify_integrity().unwrap();
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!(report.interrupted, vec![("/wallet/balance".to_string(), 2)]);
+
+        // The next write takes the same seq and settles the log
+        store.write("/wallet/balance", json!({"sats": 3})).unwrap();
+        let report = store.verify_integrity().unwrap();
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert!(report.interrupted.is_empty());
+
+        // Only the newest patch gets the benefit of the doubt
+        fs::remove_file(patches.join("00000001.json")).unwrap();
+        assert_eq!(
+            store.verify_integrity().unwrap().issues,
+            vec![IntegrityIssue::MissingPatch { path: "/wallet/balance".into(), seq: 1 }]
+        );
+    }
+
+    #[test]
+    fn adopt_history_logs_patches_written_before_the_log() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/wallet/balance", json!({"sats": 1})).unwrap();
+        store.write("/wallet/balance", json!({"sats": 2})).unwrap();
+
+        // As if the store predated the audit log
+        fs::remove_dir_all(dir.path().join("_audit")).unwrap();
+        let report = store.verify_integrity().unwrap();
+        assert_eq!(report.issues.len(), 2);
+        assert!(report.head.is_none());
+
+        assert_eq!(store.adopt_history().unwrap(), 2);
+        assert_eq!(store.adopt_history().unwrap(), 0);
+        store.write("/wallet/balance", json!({"sats": 3})).unwrap();
+
+        let report = store.verify_integrity().unwrap();
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!(report.entries, 3);
+    }
+
+    // ========================================================================
+    // Expiry Tests
+    // ========================================================================
+
+    #[test]
+    fn expired_scrolls_read_as_absent() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        let past = (current_time_millis() - 1).to_string();
+
+        store
+            .write_scroll(Scroll::new("/invoice/1", json!({"bolt11": "lnbc1"})).with_expires_at(past))
+            .unwrap();
+        store.write("/invoice/2", json!({"bolt11": "lnbc2"})).unwrap();
+
+        assert!(store.read("/invoice/1").unwrap().is_none());
+        assert_eq!(store.list("/invoice").unwrap(), vec!["/invoice/2".to_string()]);
+        assert!(store
+            .write_if("/invoice/1", json!({}), &Precondition::Absent)
+            .is_ok());
+    }
+
+    #[test]
+    fn sweep_expired_records_tombstone_and_notifies() {
+        let key = Store::test_key();
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &key).unwrap();
+        let past = (current_time_millis() - 1).to_string();
+
+        store
+            .write_scroll(Scroll::new("/invoice/1", json!({"amount": 1000})).with_expires_at(past))
+            .unwrap();
+        let mut rx = store.watch_decrypted("/invoice/*").unwrap();
+
+        assert_eq!(store.sweep_expired().unwrap(), vec!["/invoice/1".to_string()]);
+        assert!(store.sweep_expired().unwrap().is_empty());
+        assert!(!dir.path().join("_scrolls/invoice/1.json").exists());
+
+        // Watchers see the last state, marked deleted
+        let event = rx.recv().unwrap();
+        assert!(event.is_deleted());
+        assert_eq!(event.data["amount"], 1000);
+
+        // History ends in a tombstone
+        let history = store.history("/invoice/1").unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history[1].deleted);
+        assert!(store.state_at("/invoice/1", 2).unwrap().is_deleted());
+        assert!(store.snapshot_at("/", current_time_millis()).unwrap().is_empty());
+
+        // Re-creating starts a fresh chain that still exports cleanly
+        store.write("/invoice/1", json!({"amount": 2000})).unwrap();
+        let backup = store.export_backup(BackupScope::FullHistory).unwrap();
+        let restored = tempdir().unwrap();
+        let target = Store::at(restored.path(), &key).unwrap();
+        assert_eq!(target.import_backup(&backup).unwrap().patches, 3);
+    }
+
     // ========================================================================
     // Conflict Detection Tests
     // ========================================================================
@@ -1524,13 +4407,81 @@ mod tests {
         store.write("/counter", json!({"value": 1})).unwrap(); // Thread A
         store.write("/counter", json!({"value": 1})).unwrap(); // Thread B (same value)
 
-        // Both changes are recorded
-        let patches = store.history("/counter").unwrap();
-        assert_eq!(patches.len(), 3); // Initial + 2 writes
+        // Both changes are recorded
+        let patches = store.history("/counter").unwrap();
+        assert_eq!(patches.len(), 3); // Initial + 2 writes
+
+        // Final state reflects last write
+        let final_state = store.read("/counter").unwrap().unwrap();
+        assert_eq!(final_state.data["value"], 1);
+    }
+
+    #[test]
+    fn conflict_prevented_by_conditional_write() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+        let store_a = Store::at(dir.path(), &key).unwrap();
+        let store_b = Store::at(dir.path(), &key).unwrap();
+
+        store_a.write("/shared", json!({"version": 1})).unwrap();
+
+        // Both instances read the same state
+        let seen_a = store_a.read("/shared").unwrap().unwrap();
+        let seen_b = store_b.read("/shared").unwrap().unwrap();
+
+        // A wins the race
+        store_a
+            .write_if("/shared", json!({"version": 2, "author": "A"}),
+                      &Precondition::Hash(seen_a.metadata.hash.unwrap()))
+            .unwrap();
+
+        // B's write is rejected instead of silently clobbering A
+        let result = store_b.write_if(
+            "/shared",
+            json!({"version": 2, "author": "B"}),
+            &Precondition::Version(seen_b.metadata.version),
+        );
+        assert!(matches!(result, Err(Error::Conflict(_))));
+
+        let current = store_b.read("/shared").unwrap().unwrap();
+        assert_eq!(current.data["author"], "A");
+        assert_eq!(store_b.history("/shared").unwrap().len(), 2, "no patch for rejected write");
+    }
+
+    #[test]
+    fn concurrent_plain_writes_keep_every_patch() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+
+        std::thread::scope(|scope| {
+            for writer in 0..4 {
+                let store = Store::at(dir.path(), &key).unwrap();
+                scope.spawn(move || {
+                    for i in 0..10 {
+                        store.write("/shared", json!({"writer": writer, "i": i})).unwrap();
+                    }
+                });
+            }
+        });
+
+        // Unlocked writers would share seqs and overwrite each other's patches
+        let store = Store::at(dir.path(), &key).unwrap();
+        let seqs: Vec<u64> = store.history("/shared").unwrap().iter().map(|p| p.seq).collect();
+        assert_eq!(seqs, (1..=40).collect::<Vec<u64>>());
+        assert_eq!(store.read("/shared").unwrap().unwrap().metadata.version, 40);
+    }
+
+    #[test]
+    fn conditional_write_absent_creates_once() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+
+        let created = store.write_if("/config", json!({"theme": "dark"}), &Precondition::Absent).unwrap();
+        assert_eq!(created.metadata.version, 1);
 
-        // Final state reflects last write
-        let final_state = store.read("/counter").unwrap().unwrap();
-        assert_eq!(final_state.data["value"], 1);
+        let again = store.write_if("/config", json!({"theme": "light"}), &Precondition::Absent);
+        assert!(matches!(again, Err(Error::Conflict(_))));
+        assert_eq!(store.read("/config").unwrap().unwrap().data["theme"], "dark");
     }
 
     // ========================================================================
@@ -1600,24 +4551,26 @@ mod tests {
     #[test]
     fn collaboration_independent_paths() {
         let dir = tempdir().unwrap();
+        // History is sealed, so every process must share the key
+        let key = Store::test_key();
 
         // Process A: Works on wallet
         {
-            let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+            let store = Store::at(dir.path(), &key).unwrap();
             store.write("/wallet/balance", json!({"sats": 100000})).unwrap();
             store.write("/wallet/balance", json!({"sats": 150000})).unwrap();
         }
 
         // Process B: Works on lightning (completely independent)
         {
-            let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+            let store = Store::at(dir.path(), &key).unwrap();
             store.write("/ln/channels", json!({"count": 2})).unwrap();
             store.write("/ln/balance", json!({"sats": 50000})).unwrap();
         }
 
         // Both namespaces have independent histories
         {
-            let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+            let store = Store::at(dir.path(), &key).unwrap();
 
             let wallet_patches = store.history("/wallet/balance").unwrap();
             assert_eq!(wallet_patches.len(), 2);
@@ -2154,11 +5107,87 @@ mod tests {
         store.write("/doc", json!({"v": 2})).unwrap();
         store.write("/doc", json!({"v": 3})).unwrap();
 
-        // History should work (patches are on plaintext)
+        // History should work (patches are computed on plaintext, sealed on disk)
         let patches = store.history("/doc").unwrap();
         assert_eq!(patches.len(), 3);
     }
 
+    #[test]
+    fn store_history_sealed_on_disk() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+
+        store.write("/wallet/seed", json!({"phrase": "abandon abandon ... about"})).unwrap();
+        store.anchor("/wallet/seed", Some("genesis")).unwrap();
+
+        let history_dir = dir.path().join("_history").join("wallet").join("seed");
+        let mut files = Vec::new();
+        collect_json_files(&history_dir, &mut files).unwrap();
+        assert_eq!(files.len(), 2, "one patch + one anchor");
+
+        for file in files {
+            let raw = std::fs::read_to_string(&file).unwrap();
+            assert!(!raw.contains("abandon"), "plaintext leaked into {:?}", file);
+            assert!(!raw.contains("/wallet/seed"), "scroll key leaked into {:?}", file);
+            let raw_json: serde_json::Value = serde_json::from_str(&raw).unwrap();
+            assert!(raw_json.get("ciphertext").is_some());
+        }
+
+        // Transparent decryption
+        let patches = store.history("/wallet/seed").unwrap();
+        assert_eq!(patches[0].key, "/wallet/seed");
+        let anchors = store.anchors("/wallet/seed").unwrap();
+        assert_eq!(anchors[0].scroll.data["phrase"], "abandon abandon ... about");
+    }
+
+    #[test]
+    fn store_history_wrong_key_fails() {
+        let dir = tempdir().unwrap();
+
+        Store::at(dir.path(), &[1u8; 32]).unwrap()
+            .write("/doc", json!({"v": 1})).unwrap();
+
+        let other = Store::at(dir.path(), &[2u8; 32]).unwrap();
+        assert!(other.history("/doc").is_err());
+    }
+
+    #[test]
+    fn store_migrate_history_reseals_plaintext() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+
+        store.write("/ledger", json!({"balance": 100})).unwrap();
+        store.write("/ledger", json!({"balance": 200})).unwrap();
+        store.write("/ledger", json!({"balance": 300})).unwrap();
+        let anchor = store.anchor("/ledger", Some("v3")).unwrap();
+
+        // Simulate a legacy store: rewrite history as plaintext
+        let history_dir = dir.path().join("_history").join("ledger");
+        for patch in store.history("/ledger").unwrap() {
+            let path = history_dir.join("patches").join(format!("{:08}.json", patch.seq));
+            std::fs::write(&path, serde_json::to_vec_pretty(&patch).unwrap()).unwrap();
+        }
+        let anchor_path = history_dir.join("anchors").join(format!("{}.json", anchor.id));
+        std::fs::write(&anchor_path, serde_json::to_vec_pretty(&anchor).unwrap()).unwrap();
+
+        // Legacy plaintext is still readable before migration
+        assert_eq!(store.state_at("/ledger", 2).unwrap().data["balance"], 200);
+
+        assert_eq!(store.migrate_history().unwrap(), 4);
+        assert_eq!(store.migrate_history().unwrap(), 0, "migration is idempotent");
+
+        let raw = std::fs::read_to_string(history_dir.join("patches").join("00000001.json")).unwrap();
+        assert!(!raw.contains("balance"));
+
+        // Everything still works after migration
+        assert_eq!(store.history("/ledger").unwrap().len(), 3);
+        assert_eq!(store.state_at("/ledger", 1).unwrap().data["balance"], 100);
+        store.write("/ledger", json!({"balance": 0})).unwrap();
+        let restored = store.restore("/ledger", &anchor.id).unwrap();
+        assert_eq!(restored.data["balance"], 300);
+        assert_eq!(store.compact("/ledger", Some(3)).unwrap(), 2);
+    }
+
     #[test]
     fn store_always_encrypted() {
         // Sovereignty: encryption is mandatory, not optional
@@ -2174,6 +5203,760 @@ mod tests {
         assert_eq!(scroll.data["data"], "visible");
     }
 
+    #[test]
+    fn store_merge_divergent_state() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+
+        store.write("/doc", json!({"title": "Draft", "n": 1})).unwrap(); // seq 1
+        store.write("/doc", json!({"title": "Ours", "n": 1})).unwrap(); // seq 2
+
+        // Another instance diverged from seq 1
+        let theirs = Scroll::new("/doc", json!({"title": "Theirs", "n": 2}));
+        let result = store.merge("/doc", 1, theirs, &MergePolicy::Ours).unwrap();
+
+        assert_eq!(result.conflicts, vec!["/title".to_string()]);
+        assert_eq!(result.scroll.metadata.version, 3);
+
+        let current = store.read("/doc").unwrap().unwrap();
+        assert_eq!(current.data, json!({"title": "Ours", "n": 2}));
+        assert_eq!(store.history("/doc").unwrap().len(), 3);
+    }
+
+    // ========================================================================
+    // Index Tests - Secondary indexes maintained on write
+    // ========================================================================
+
+    #[test]
+    fn index_backfills_and_tracks_writes() {
+        use crate::nine_s::index::{IndexField, IndexSpec, Query};
+
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/wallet/tx/a", json!({"amount": 300})).unwrap();
+        store.write("/wallet/tx/b", json!({"amount": 100})).unwrap();
+        store.write("/wallet/balance", json!({"amount": 999})).unwrap();
+
+        let spec = IndexSpec::new("tx_amount", "/wallet/tx", IndexField::pointer("/amount"));
+        assert_eq!(store.create_index(spec.clone()).unwrap(), 2);
+        assert_eq!(store.indexes().unwrap(), vec![spec]);
+
+        // Maintained on plain writes and transactions
+        store.write("/wallet/tx/c", json!({"amount": 200})).unwrap();
+        store.write("/wallet/tx/a", json!({"amount": 50})).unwrap();
+        let mut tx = store.begin();
+        tx.write("/wallet/tx/d", json!({"amount": 400})).unwrap();
+        tx.commit().unwrap();
+
+        let page = store.query(&Query::on("tx_amount")).unwrap();
+        let amounts: Vec<_> = page.scrolls.iter().map(|s| s.data["amount"].clone()).collect();
+        assert_eq!(amounts, vec![json!(50), json!(100), json!(200), json!(400)]);
+
+        let page = store
+            .query(&Query::on("tx_amount").gte(json!(100)).descending().limit(2))
+            .unwrap();
+        assert_eq!(page.scrolls[0].key, "/wallet/tx/d");
+        assert_eq!(page.scrolls[1].key, "/wallet/tx/c");
+
+        let next = store
+            .query(&Query::on("tx_amount").gte(json!(100)).descending().limit(2).after(page.next_cursor.unwrap()))
+            .unwrap();
+        assert_eq!(next.scrolls.len(), 1);
+        assert_eq!(next.scrolls[0].key, "/wallet/tx/b");
+        assert!(next.next_cursor.is_none());
+
+        assert!(matches!(store.query(&Query::on("missing")), Err(Error::NotFound(_))));
+        assert!(store.drop_index("tx_amount").unwrap());
+        assert!(store.indexes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn index_write_reseals_one_shard() {
+        use crate::nine_s::index::{IndexField, IndexSpec, Query};
+
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        for i in 0..20 {
+            store.write(&format!("/wallet/tx/{}", i), json!({"amount": i})).unwrap();
+        }
+        store
+            .create_index(IndexSpec::new("tx_amount", "/wallet/tx", IndexField::pointer("/amount")))
+            .unwrap();
+
+        let shards = || {
+            let mut files = Vec::new();
+            collect_json_files(&dir.path().join("_index/tx_amount"), &mut files).unwrap();
+            files
+                .into_iter()
+                .map(|f| (f.clone(), fs::read(f).unwrap()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let before = shards();
+        assert!(before.len() > 2);
+
+        store.write("/wallet/tx/7", json!({"amount": 700})).unwrap();
+        let after = shards();
+        let changed: Vec<_> = before.keys().filter(|f| before[*f] != after[*f]).collect();
+        assert_eq!(changed.len(), 1);
+
+        let page = store.query(&Query::on("tx_amount").descending().limit(1)).unwrap();
+        assert_eq!(page.scrolls[0].key, "/wallet/tx/7");
+        assert_eq!(store.query(&Query::on("tx_amount")).unwrap().scrolls.len(), 20);
+    }
+
+    #[test]
+    fn index_files_are_sealed_and_rotate() {
+        use crate::nine_s::index::{IndexField, IndexSpec, Query};
+
+        let dir = tempdir().unwrap();
+        let (old_key, new_key) = (Store::test_key(), Store::test_key());
+        let mut store = Store::at(dir.path(), &old_key).unwrap();
+        store.write("/contacts/alice", json!({"name": "alice-secret"})).unwrap();
+        store
+            .create_index(IndexSpec::new("by_name", "/contacts", IndexField::pointer("/name")))
+            .unwrap();
+
+        let shard = format!("{:02}.json", crate::nine_s::index::shard_of("/contacts/alice"));
+        let raw = fs::read_to_string(dir.path().join("_index/by_name").join(shard)).unwrap();
+        assert!(!raw.contains("alice-secret"));
+        assert!(raw.contains("ciphertext"));
+
+        store.rotate_key(&old_key, &new_key).unwrap();
+        let reopened = Store::at(dir.path(), &new_key).unwrap();
+        let page = reopened.query(&Query::on("by_name").eq(json!("alice-secret"))).unwrap();
+        assert_eq!(page.scrolls.len(), 1);
+    }
+
+    // ========================================================================
+    // Backup Tests - Export, validate, restore
+    // ========================================================================
+
+    #[test]
+    fn backup_full_history_roundtrip() {
+        let key = Store::test_key();
+        let src_dir = tempdir().unwrap();
+        let src = Store::at(src_dir.path(), &key).unwrap();
+
+        src.write("/wallet/balance", json!({"sats": 100})).unwrap();
+        src.write("/wallet/balance", json!({"sats": 90})).unwrap();
+        src.write("/notes/a", json!({"text": "hi"})).unwrap();
+        let anchor = src.anchor("/wallet/balance", Some("checkpoint")).unwrap();
+
+        let archive = src.export_backup(BackupScope::FullHistory).unwrap();
+        assert!(!String::from_utf8_lossy(&archive).contains("sats"));
+
+        let dst_dir = tempdir().unwrap();
+        let dst = Store::at(dst_dir.path(), &key).unwrap();
+        let report = dst.import_backup(&archive).unwrap();
+        assert_eq!(report.scope, BackupScope::FullHistory);
+        assert_eq!(report.scrolls, 2);
+        assert_eq!(report.patches, 3);
+        assert_eq!(report.anchors, 1);
+
+        let balance = dst.read("/wallet/balance").unwrap().unwrap();
+        assert_eq!(balance.data["sats"], 90);
+        assert_eq!(balance.metadata.version, 2);
+        assert_eq!(dst.history("/wallet/balance").unwrap().len(), 2);
+        assert_eq!(dst.anchors("/wallet/balance").unwrap()[0].id, anchor.id);
+        assert_eq!(dst.state_at("/wallet/balance", 1).unwrap().data["sats"], 100);
+
+        // Writes continue the restored timeline
+        let next = dst.write("/wallet/balance", json!({"sats": 80})).unwrap();
+        assert_eq!(next.metadata.version, 3);
+    }
+
+    #[test]
+    fn backup_scrolls_only_restarts_history() {
+        let key = Store::test_key();
+        let src_dir = tempdir().unwrap();
+        let src = Store::at(src_dir.path(), &key).unwrap();
+        for i in 0..3 {
+            src.write("/counter", json!({"n": i})).unwrap();
+        }
+
+        let archive = src.export_backup(BackupScope::ScrollsOnly).unwrap();
+
+        let dst_dir = tempdir().unwrap();
+        let dst = Store::at(dst_dir.path(), &key).unwrap();
+        let report = dst.import_backup(&archive).unwrap();
+        assert_eq!(report.scope, BackupScope::ScrollsOnly);
+        assert_eq!(report.anchors, 0);
+
+        let history = dst.history("/counter").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].seq, 3);
+
+        let next = dst.write("/counter", json!({"n": 3})).unwrap();
+        assert_eq!(next.metadata.version, 4);
+
+        // A full export of the restored store validates cleanly
+        let again = dst.export_backup(BackupScope::FullHistory).unwrap();
+        let third_dir = tempdir().unwrap();
+        Store::at(third_dir.path(), &key).unwrap().import_backup(&again).unwrap();
+    }
+
+    #[test]
+    fn backup_rejects_wrong_key_and_tampering() {
+        let src_dir = tempdir().unwrap();
+        let src = Store::at(src_dir.path(), &Store::test_key()).unwrap();
+        src.write("/doc", json!({"v": 1})).unwrap();
+        let archive = src.export_backup(BackupScope::FullHistory).unwrap();
+
+        // Different store key
+        let other_dir = tempdir().unwrap();
+        let other = Store::at(other_dir.path(), &Store::test_key()).unwrap();
+        assert!(matches!(other.import_backup(&archive), Err(Error::Permission(_))));
+
+        // Cleartext header edited
+        let mut edited: Value = serde_json::from_slice(&archive).unwrap();
+        edited["scope"] = json!("scrolls-only");
+        let edited = serde_json::to_vec(&edited).unwrap();
+        let dst_dir = tempdir().unwrap();
+        let dst = Store::at(dst_dir.path(), &src.encryption_key.unwrap()).unwrap();
+        assert!(matches!(dst.import_backup(&edited), Err(Error::InvalidData(_))));
+
+        // Not an archive at all
+        assert!(matches!(dst.import_backup(b"{}"), Err(Error::InvalidData(_))));
+        assert!(dst.list("/").unwrap().is_empty());
+    }
+
+    #[test]
+    fn backup_import_rolls_back_on_failure() {
+        let key = Store::test_key();
+        let src_dir = tempdir().unwrap();
+        let src = Store::at(src_dir.path(), &key).unwrap();
+        src.write("/a", json!({"v": 1})).unwrap();
+        src.write("/b", json!({"v": 1})).unwrap();
+        src.write("/b", json!({"v": 2})).unwrap();
+        let archive = src.export_backup(BackupScope::FullHistory).unwrap();
+
+        // A directory where /b's second patch goes makes the import fail
+        // after /a and both scrolls have landed
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("_history/b/patches/00000002.json")).unwrap();
+        let store = Store::at(dir.path(), &key).unwrap();
+        assert!(store.import_backup(&archive).is_err());
+
+        assert!(store.list("/").unwrap().is_empty());
+        assert!(store.history("/a").unwrap().is_empty());
+        assert!(store.verify_integrity().unwrap().is_intact());
+        assert!(!dir.path().join("_import.json").exists());
+
+        // Nothing is left in the way of a clean retry
+        assert_eq!(store.import_backup(&archive).unwrap().patches, 3);
+        assert_eq!(store.read("/b").unwrap().unwrap().data["v"], 2);
+        assert!(store.verify_integrity().unwrap().is_intact());
+
+        // An import cut short by a crash is undone on the next open
+        let crashed = tempdir().unwrap();
+        let store = Store::at(crashed.path(), &key).unwrap();
+        store.import_backup(&archive).unwrap();
+        write_json_atomic(&crashed.path().join("_import.json"), &ImportMarker { audit_seq: 0 }).unwrap();
+        let reopened = Store::at(crashed.path(), &key).unwrap();
+        assert!(reopened.list("/").unwrap().is_empty());
+        assert!(reopened.verify_integrity().unwrap().is_intact());
+    }
+
+    #[test]
+    fn backup_import_requires_empty_store() {
+        let key = Store::test_key();
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &key).unwrap();
+        store.write("/doc", json!({"v": 1})).unwrap();
+
+        let archive = store.export_backup(BackupScope::FullHistory).unwrap();
+        assert!(matches!(store.import_backup(&archive), Err(Error::Conflict(_))));
+    }
+
+    #[test]
+    fn backup_validation_catches_broken_chain_and_anchor() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/doc", json!({"v": 1})).unwrap();
+        store.write("/doc", json!({"v": 2})).unwrap();
+        let anchor = store.anchor("/doc", None).unwrap();
+
+        let payload = || BackupPayload {
+            header: BackupHeader {
+                format: BACKUP_FORMAT.to_string(),
+                version: BACKUP_VERSION,
+                scope: BackupScope::FullHistory,
+                created_at: 0,
+            },
+            scrolls: vec![store.read("/doc").unwrap().unwrap()],
+            history: vec![BackupHistory {
+                path: "/doc".to_string(),
+                patches: store.history("/doc").unwrap(),
+                anchors: vec![anchor.clone()],
+            }],
+        };
+        assert!(validate_backup(&payload()).is_ok());
+
+        let mut broken = payload();
+        broken.history[0].patches[1].parent = Some("0".repeat(64));
+        assert!(validate_backup(&broken).is_err());
+
+        let mut diverged = payload();
+        diverged.scrolls[0].data = json!({"v": 99});
+        diverged.scrolls[0].metadata.hash = Some(diverged.scrolls[0].compute_hash());
+        assert!(validate_backup(&diverged).is_err());
+
+        let mut forged = payload();
+        forged.history[0].anchors[0].scroll.data = json!({"v": 42});
+        assert!(validate_backup(&forged).is_err());
+    }
+
+    // ========================================================================
+    // Key Rotation Tests - Re-seal under a new master key
+    // ========================================================================
+
+    fn rotation_fixture(dir: &Path, key: &[u8; 32]) {
+        let store = Store::at(dir, key).unwrap();
+        store.write("/wallet/balance", json!({"sats": 100})).unwrap();
+        store.write("/wallet/balance", json!({"sats": 90})).unwrap();
+        store.write("/notes/a", json!({"text": "secret"})).unwrap();
+        store.anchor("/wallet/balance", Some("before-rotation")).unwrap();
+    }
+
+    #[test]
+    fn rotate_key_reseals_scrolls_and_history() {
+        let dir = tempdir().unwrap();
+        let (old_key, new_key) = (Store::test_key(), Store::test_key());
+        rotation_fixture(dir.path(), &old_key);
+
+        let mut store = Store::at(dir.path(), &old_key).unwrap();
+        let report = store.rotate_key(&old_key, &new_key).unwrap();
+        assert_eq!(report.scrolls, 2);
+        assert_eq!(report.records, 8); // 3 patches + 1 anchor + 3 audit entries + head
+        assert_eq!(report.resumed, 0);
+
+        // Same instance keeps working under the new key
+        assert_eq!(store.read("/wallet/balance").unwrap().unwrap().data["sats"], 90);
+        store.write("/notes/a", json!({"text": "rotated"})).unwrap();
+
+        // Fresh instance with the new key sees everything
+        let reopened = Store::at(dir.path(), &new_key).unwrap();
+        assert_eq!(reopened.read("/notes/a").unwrap().unwrap().data["text"], "rotated");
+        assert_eq!(reopened.history("/wallet/balance").unwrap().len(), 2);
+        assert_eq!(reopened.anchors("/wallet/balance").unwrap().len(), 1);
+
+        // Old key no longer decrypts anything
+        let stale = Store::at(dir.path(), &old_key).unwrap();
+        assert!(stale.read("/notes/a").is_err());
+        assert!(stale.history("/wallet/balance").is_err());
+
+        // No staging leftovers
+        assert!(!dir.path().join("_rekey").exists());
+        assert!(!dir.path().join("_rotation.json").exists());
+    }
+
+    #[test]
+    fn rotate_key_rejects_wrong_old_key() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+        rotation_fixture(dir.path(), &key);
+
+        let mut store = Store::at(dir.path(), &key).unwrap();
+        let result = store.rotate_key(&Store::test_key(), &Store::test_key());
+        assert!(matches!(result, Err(Error::Permission(_))));
+        assert_eq!(store.read("/notes/a").unwrap().unwrap().data["text"], "secret");
+    }
+
+    #[test]
+    fn rotate_key_resumes_after_crash_while_staging() {
+        let dir = tempdir().unwrap();
+        let (old_key, new_key) = (Store::test_key(), Store::test_key());
+        rotation_fixture(dir.path(), &old_key);
+
+        // Crash after staging, before the commit point
+        {
+            let store = Store::at(dir.path(), &old_key).unwrap();
+            store.stage_rotation(&old_key, &new_key).unwrap();
+        }
+
+        // New key refuses a half-finished rotation
+        assert!(matches!(Store::at(dir.path(), &new_key), Err(Error::Permission(_))));
+
+        // Old key still works, and writes made meanwhile are not lost
+        let mut store = Store::at(dir.path(), &old_key).unwrap();
+        store.write("/notes/a", json!({"text": "edited"})).unwrap();
+
+        let report = store.rotate_key(&old_key, &new_key).unwrap();
+        assert!(report.resumed > 0);
+
+        let reopened = Store::at(dir.path(), &new_key).unwrap();
+        assert_eq!(reopened.read("/notes/a").unwrap().unwrap().data["text"], "edited");
+        assert_eq!(reopened.history("/notes/a").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rotate_key_finishes_swap_on_open() {
+        let dir = tempdir().unwrap();
+        let (old_key, new_key) = (Store::test_key(), Store::test_key());
+        rotation_fixture(dir.path(), &old_key);
+
+        // Crash right after the commit point, before any file was swapped
+        {
+            let store = Store::at(dir.path(), &old_key).unwrap();
+            store.stage_rotation(&old_key, &new_key).unwrap();
+            store
+                .write_rotation_marker(&RotationMarker {
+                    phase: RotationPhase::Swapping,
+                    old_key_id: key_id(&old_key),
+                    new_key_id: key_id(&new_key),
+                })
+                .unwrap();
+        }
+
+        assert!(matches!(Store::at(dir.path(), &old_key), Err(Error::Permission(_))));
+
+        let store = Store::at(dir.path(), &new_key).unwrap();
+        assert_eq!(store.read("/wallet/balance").unwrap().unwrap().data["sats"], 90);
+        assert_eq!(store.state_at("/wallet/balance", 1).unwrap().data["sats"], 100);
+        assert!(!dir.path().join("_rotation.json").exists());
+    }
+
+    // ========================================================================
+    // Path Blinding Tests
+    // ========================================================================
+
+    /// Every file and directory name under the store
+    fn disk_names(dir: &Path) -> Vec<String> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(dir).unwrap().flatten() {
+            names.push(entry.file_name().to_string_lossy().into_owned());
+            if entry.path().is_dir() {
+                names.extend(disk_names(&entry.path()));
+            }
+        }
+        names
+    }
+
+    #[test]
+    fn path_blinding_hides_names_and_keeps_working() {
+        let dir = tempdir().unwrap();
+        let (key, new_key) = (Store::test_key(), Store::test_key());
+        rotation_fixture(dir.path(), &key);
+
+        let mut store = Store::at(dir.path(), &key).unwrap();
+        assert_eq!(store.enable_path_blinding().unwrap(), 2);
+        assert!(store.is_path_blinded());
+        assert_eq!(store.enable_path_blinding().unwrap(), 0);
+
+        store.write("/contacts/alice", json!({"npub": "npub1"})).unwrap();
+        let names = disk_names(dir.path());
+        for leak in ["wallet", "balance", "notes", "contacts", "alice"] {
+            assert!(!names.iter().any(|n| n.contains(leak)), "{} leaked", leak);
+        }
+
+        // list, history and snapshots resolve through the manifest
+        let mut paths = store.list("/").unwrap();
+        paths.sort();
+        assert_eq!(paths, vec!["/contacts/alice", "/notes/a", "/wallet/balance"]);
+        assert_eq!(store.list("/wallet").unwrap(), vec!["/wallet/balance".to_string()]);
+        assert_eq!(store.history("/wallet/balance").unwrap().len(), 2);
+        assert_eq!(store.anchors("/wallet/balance").unwrap().len(), 1);
+        assert_eq!(store.state_at("/wallet/balance", 1).unwrap().data["sats"], 100);
+        assert_eq!(store.snapshot_at("/wallet", current_time_millis()).unwrap().len(), 1);
+
+        // Survives reopening and key rotation
+        store.rotate_key(&key, &new_key).unwrap();
+        let reopened = Store::at(dir.path(), &new_key).unwrap();
+        assert!(reopened.is_path_blinded());
+        assert_eq!(reopened.read("/notes/a").unwrap().unwrap().data["text"], "secret");
+        assert_eq!(reopened.list("/contacts").unwrap(), vec!["/contacts/alice".to_string()]);
+    }
+
+    #[test]
+    fn path_blinding_watch_delete_and_purge() {
+        let dir = tempdir().unwrap();
+        let mut store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.enable_path_blinding().unwrap();
+        let mut rx = store.watch_decrypted("/contacts/*").unwrap();
+
+        store.write("/notes/x", json!(1)).unwrap();
+        store.write("/contacts/alice", json!({"npub": "npub1"})).unwrap();
+        store.write("/contacts/bob", json!({"npub": "npub2"})).unwrap();
+
+        let event = rx.recv().unwrap();
+        assert_eq!(event.key, "/contacts/alice");
+
+        store.delete("/contacts/alice").unwrap();
+        assert_eq!(store.list("/contacts").unwrap(), vec!["/contacts/bob".to_string()]);
+        assert_eq!(store.history("/contacts/alice").unwrap().len(), 2);
+
+        assert!(store.purge("/contacts/bob").unwrap());
+        assert!(store.list("/contacts").unwrap().is_empty());
+        assert!(store.load_manifest().unwrap().unwrap().paths.iter().all(|p| p != "/contacts/bob"));
+
+        assert_eq!(rx.recv().unwrap().key, "/contacts/bob");
+        let deleted = rx.recv().unwrap();
+        assert_eq!(deleted.key, "/contacts/alice");
+        assert!(deleted.is_deleted());
+        let purged = rx.recv().unwrap();
+        assert_eq!(purged.key, "/contacts/bob");
+        assert!(purged.data.is_null());
+    }
+
+    #[test]
+    fn path_blinding_finishes_interrupted_migration_on_open() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+        rotation_fixture(dir.path(), &key);
+
+        // Crash right after the commit point: manifest written, nothing moved
+        let store = Store::at(dir.path(), &key).unwrap();
+        let manifest = Manifest {
+            name_key: hex::encode([7u8; 32]),
+            migrated: false,
+            paths: store.inner.list("/").unwrap().into_iter().collect(),
+        };
+        store.save_manifest(&manifest).unwrap();
+        drop(store);
+
+        let reopened = Store::at(dir.path(), &key).unwrap();
+        assert!(reopened.is_path_blinded());
+        assert!(reopened.load_manifest().unwrap().unwrap().migrated);
+        assert_eq!(reopened.read("/wallet/balance").unwrap().unwrap().data["sats"], 90);
+        assert_eq!(reopened.history("/wallet/balance").unwrap().len(), 2);
+        assert!(!dir.path().join("_scrolls/wallet").exists());
+        assert!(!dir.path().join("_history/wallet").exists());
+    }
+
+    // ========================================================================
+    // Migration Tests - Upgrading old type versions
+    // ========================================================================
+
+    /// `test/payment@v1` stored `amount`; v2 calls it `amount_sat`
+    fn payment_v2() -> Arc<Migrations> {
+        let mut migrations = Migrations::new();
+        migrations
+            .register("test/payment@v1", |mut data| {
+                let object = data.as_object_mut().ok_or("not an object")?;
+                let amount = object.remove("amount").ok_or("missing amount")?;
+                object.insert("amount_sat".into(), amount);
+                Ok(data)
+            })
+            .unwrap();
+        Arc::new(migrations)
+    }
+
+    fn v1_payment(path: &str, amount: u64) -> Scroll {
+        Scroll::typed(path, json!({"amount": amount}), "test/payment@v1")
+    }
+
+    #[test]
+    fn read_upgrades_lazily_and_records_a_migration_patch() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+        Store::at(dir.path(), &key)
+            .unwrap()
+            .write_scroll(v1_payment("/wallet/tx/a", 500))
+            .unwrap();
+
+        let store = Store::at(dir.path(), &key).unwrap().with_migrations(payment_v2());
+        let scroll = store.read("/wallet/tx/a").unwrap().unwrap();
+        assert_eq!(scroll.type_, "test/payment@v2");
+        assert_eq!(scroll.data, json!({"amount_sat": 500}));
+        assert_eq!(scroll.metadata.version, 2);
+
+        let history = store.history("/wallet/tx/a").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].migration, None);
+        assert_eq!(
+            history[1].migration,
+            Some(patch::TypeChange {
+                from: "test/payment@v1".into(),
+                to: "test/payment@v2".into(),
+            })
+        );
+
+        // Replaying history picks up the type change
+        let replayed = store.state_at("/wallet/tx/a", 2).unwrap();
+        assert_eq!(replayed.type_, "test/payment@v2");
+        assert_eq!(replayed.data, scroll.data);
+
+        // Upgraded once; later reads are plain reads
+        store.read("/wallet/tx/a").unwrap();
+        assert_eq!(store.history("/wallet/tx/a").unwrap().len(), 2);
+        assert!(store.verify_integrity().unwrap().is_intact());
+    }
+
+    #[test]
+    fn transactions_wait_for_the_lock_lazy_upgrades_take() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap().with_migrations(payment_v2());
+        store.write_scroll(v1_payment("/wallet/tx/a", 1)).unwrap();
+
+        // Held the way migrate_path holds it while upgrading
+        let lock = store.inner.lock_path(&store.disk_path("/wallet/tx/a")).unwrap();
+        std::thread::scope(|scope| {
+            let commit = scope.spawn(|| {
+                let mut tx = store.begin();
+                tx.write("/wallet/tx/a", json!({"amount_sat": 2})).unwrap();
+                tx.commit().unwrap();
+            });
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            assert_eq!(store.history("/wallet/tx/a").unwrap().len(), 1);
+            drop(lock);
+            commit.join().unwrap();
+        });
+
+        let seqs: Vec<u64> = store.history("/wallet/tx/a").unwrap().iter().map(|p| p.seq).collect();
+        assert_eq!(seqs, vec![1, 2]);
+    }
+
+    #[test]
+    fn migrate_upgrades_a_prefix_eagerly() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap().with_migrations(payment_v2());
+        store.write_scroll(v1_payment("/wallet/tx/a", 1)).unwrap();
+        store.write_scroll(v1_payment("/wallet/tx/b", 2)).unwrap();
+        store.write_scroll(v1_payment("/other/c", 3)).unwrap();
+        store.write("/wallet/balance", json!({"confirmed": 3})).unwrap();
+
+        assert_eq!(store.migrate("/wallet").unwrap(), 2);
+        assert_eq!(store.migrate("/wallet").unwrap(), 0);
+        assert_eq!(store.history("/wallet/tx/b").unwrap().len(), 2);
+        assert_eq!(store.history("/other/c").unwrap().len(), 1);
+
+        // A step that fails leaves the scroll as it was, and readable
+        store
+            .write_scroll(Scroll::typed("/wallet/tx/bad", json!({"amt": 1}), "test/payment@v1"))
+            .unwrap();
+        assert!(matches!(store.migrate("/wallet"), Err(Error::InvalidData(_))));
+        let stored = store.read("/wallet/tx/bad").unwrap().unwrap();
+        assert_eq!(stored.type_, "test/payment@v1");
+        assert_eq!(stored.data, json!({"amt": 1}));
+        assert_eq!(store.history("/wallet/tx/bad").unwrap().len(), 1);
+    }
+
+    // ========================================================================
+    // Transaction Tests - All-or-nothing multi-scroll writes
+    // ========================================================================
+
+    #[test]
+    fn transaction_commit_writes_all() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/wallet/balance", json!({"sats": 100})).unwrap();
+
+        let mut tx = store.begin();
+        tx.write("/wallet/balance", json!({"sats": 90})).unwrap();
+        tx.write("/wallet/tx/abc", json!({"amount": 10})).unwrap();
+        assert_eq!(tx.len(), 2);
+
+        // Nothing lands before commit
+        assert_eq!(store.read("/wallet/balance").unwrap().unwrap().data["sats"], 100);
+        assert!(store.read("/wallet/tx/abc").unwrap().is_none());
+
+        let written = tx.commit().unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!(written[0].metadata.version, 2);
+
+        assert_eq!(store.read("/wallet/balance").unwrap().unwrap().data["sats"], 90);
+        assert_eq!(store.read("/wallet/tx/abc").unwrap().unwrap().data["amount"], 10);
+        assert_eq!(store.history("/wallet/balance").unwrap().len(), 2);
+        assert_eq!(store.history("/wallet/tx/abc").unwrap().len(), 1);
+
+        // Journal is cleaned up
+        let journal_dir = dir.path().join("_journal");
+        assert_eq!(std::fs::read_dir(journal_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn transaction_abort_writes_nothing() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+
+        let mut tx = store.begin();
+        tx.write("/a", json!(1)).unwrap();
+        tx.abort();
+
+        {
+            let mut tx = store.begin();
+            tx.write("/b", json!(2)).unwrap();
+            // dropped without commit
+        }
+
+        assert!(store.read("/a").unwrap().is_none());
+        assert!(store.read("/b").unwrap().is_none());
+        assert!(store.history("/a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn transaction_same_path_twice_chains_history() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+        store.write("/counter", json!({"v": 0})).unwrap();
+
+        let mut tx = store.begin();
+        tx.write("/counter", json!({"v": 1})).unwrap();
+        tx.write("/counter", json!({"v": 2})).unwrap();
+        tx.commit().unwrap();
+
+        let patches = store.history("/counter").unwrap();
+        assert_eq!(patches.len(), 3);
+        assert_eq!(patches[2].parent.as_ref(), Some(&patches[1].hash));
+        assert_eq!(store.state_at("/counter", 2).unwrap().data["v"], 1);
+        assert_eq!(store.read("/counter").unwrap().unwrap().data["v"], 2);
+    }
+
+    #[test]
+    fn transaction_invalid_path_rejected_at_stage() {
+        let dir = tempdir().unwrap();
+        let store = Store::at(dir.path(), &Store::test_key()).unwrap();
+
+        let mut tx = store.begin();
+        assert!(tx.write("../escape", json!(1)).is_err());
+        assert!(tx.is_empty());
+    }
+
+    #[test]
+    fn transaction_recovered_after_crash() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+
+        // Simulate a crash right after the commit point: journal written,
+        // nothing applied yet
+        {
+            let store = Store::at(dir.path(), &key).unwrap();
+            let writes = vec![
+                store.prepare_write(Scroll::new("/wallet/balance", json!({"sats": 5})), None, 1).unwrap(),
+                store.prepare_write(Scroll::new("/wallet/tx/1", json!({"amount": 5})), None, 1).unwrap(),
+            ];
+            store.write_journal(&writes).unwrap();
+            assert!(store.read("/wallet/balance").unwrap().is_none());
+        }
+
+        // Journal is sealed like everything else
+        let journal = std::fs::read_dir(dir.path().join("_journal")).unwrap()
+            .next().unwrap().unwrap().path();
+        assert!(!std::fs::read_to_string(&journal).unwrap().contains("/wallet/balance"));
+
+        // Reopen replays the journal
+        let store = Store::at(dir.path(), &key).unwrap();
+        assert_eq!(store.read("/wallet/balance").unwrap().unwrap().data["sats"], 5);
+        assert_eq!(store.read("/wallet/tx/1").unwrap().unwrap().data["amount"], 5);
+        assert_eq!(store.history("/wallet/tx/1").unwrap().len(), 1);
+        assert!(!journal.exists());
+    }
+
+    #[test]
+    fn transaction_uncommitted_journal_discarded() {
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+
+        // Crash before the commit point leaves only a temp file
+        let journal_dir = dir.path().join("_journal");
+        std::fs::create_dir_all(&journal_dir).unwrap();
+        std::fs::write(journal_dir.join("00000000000000000001-00.json.tmp"), b"{\"trunc").unwrap();
+
+        let store = Store::at(dir.path(), &key).unwrap();
+        assert_eq!(store.list("/").unwrap().len(), 0);
+        assert_eq!(std::fs::read_dir(&journal_dir).unwrap().count(), 0);
+    }
+
     // ========================================================================
     // CSP Sequencing Tests - Rapid writes with correct ordering
     // ========================================================================
@@ -0,0 +1,313 @@
+//! Union - Several namespaces stacked at one path
+//!
+//! Plan 9's union directories for 9S: an ordered list of layers behind one
+//! namespace. Reads fall through from the top layer down, writes go to the
+//! one writable layer, and `list` merges every layer.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: One path, one namespace (longest prefix wins)
+//! **Antithesis**: Copy defaults into every store (duplicated, drifts)
+//! **Synthesis**: Stack namespaces; the top layer shadows the ones below
+//!
+//! # Layers
+//!
+//! ```text
+//! read("/theme")
+//!   ├── user Store        ← writable: writes land here
+//!   └── defaults (memory) ← consulted when the Store has nothing
+//! ```
+//!
+//! Deleting a path in the writable layer reveals whatever the layers below
+//! hold, which for a defaults overlay means "reset to default".
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use beewallet_core_spark::nine_s::{Bind, Kernel, MemoryNamespace, Union};
+//! use std::sync::Arc;
+//!
+//! let prefs = Union::new()
+//!     .with_write_layer(Arc::new(store))
+//!     .with_layer(Arc::new(defaults));
+//! kernel.mount("/prefs", Arc::new(prefs));
+//!
+//! // Or bind onto an existing mount, Plan 9 style
+//! kernel.mount("/wallet", Arc::new(wallet));
+//! kernel.bind("/wallet", Arc::new(cache), Bind::Before);
+//! ```
+
+#[cfg(feature = "std-channel")]
+use super::channel::channel;
+use super::namespace::{Error, Namespace, Receiver, Result};
+use super::scroll::Scroll;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+
+/// Where a bound namespace joins a union
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bind {
+    /// Above the existing layers (consulted first)
+    Before,
+    /// Below the existing layers (consulted last)
+    After,
+}
+
+struct Layer {
+    ns: Arc<dyn Namespace>,
+    /// Receives writes; at most one layer has it
+    writable: bool,
+}
+
+/// Union - Ordered layers behind one namespace
+///
+/// Layers are ordered top to bottom. Binding is interior-mutable so a
+/// mounted union can gain layers.
+pub struct Union {
+    layers: RwLock<Vec<Layer>>,
+}
+
+impl Union {
+    /// Create an empty union
+    pub fn new() -> Self {
+        Self {
+            layers: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Add a read-only layer below the existing ones
+    pub fn with_layer(self, ns: Arc<dyn Namespace>) -> Self {
+        self.bind(ns, Bind::After);
+        self
+    }
+
+    /// Add the writable layer below the existing ones
+    pub fn with_write_layer(self, ns: Arc<dyn Namespace>) -> Self {
+        self.bind_writable(ns, Bind::After);
+        self
+    }
+
+    /// Bind a read-only layer
+    pub fn bind(&self, ns: Arc<dyn Namespace>, order: Bind) {
+        self.insert(Layer { ns, writable: false }, order);
+    }
+
+    /// Bind the writable layer
+    ///
+    /// Takes over from any previous writable layer, which stays readable.
+    pub fn bind_writable(&self, ns: Arc<dyn Namespace>, order: Bind) {
+        self.insert(Layer { ns, writable: true }, order);
+    }
+
+    /// Number of layers
+    pub fn len(&self) -> usize {
+        self.layers.read().unwrap().len()
+    }
+
+    /// Whether the union has no layers
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert(&self, layer: Layer, order: Bind) {
+        let mut layers = self.layers.write().unwrap();
+        if layer.writable {
+            for existing in layers.iter_mut() {
+                existing.writable = false;
+            }
+        }
+        match order {
+            Bind::Before => layers.insert(0, layer),
+            Bind::After => layers.push(layer),
+        }
+    }
+
+    /// Layers top to bottom (lock released before calling into them)
+    fn stack(&self) -> Vec<Arc<dyn Namespace>> {
+        self.layers.read().unwrap().iter().map(|l| l.ns.clone()).collect()
+    }
+
+    fn write_layer(&self) -> Result<Arc<dyn Namespace>> {
+        self.layers
+            .read()
+            .unwrap()
+            .iter()
+            .find(|l| l.writable)
+            .map(|l| l.ns.clone())
+            .ok_or_else(|| Error::Permission("union has no writable layer".to_string()))
+    }
+}
+
+impl Default for Union {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read a path from the first layer that has it
+fn read_through(layers: &[Arc<dyn Namespace>], path: &str) -> Result<Option<Scroll>> {
+    for ns in layers {
+        match ns.read(path) {
+            Ok(Some(scroll)) => return Ok(Some(scroll)),
+            Ok(None) | Err(Error::NotFound(_)) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
+impl Namespace for Union {
+    fn read(&self, path: &str) -> Result<Option<Scroll>> {
+        read_through(&self.stack(), path)
+    }
+
+    fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+        self.write_layer()?.write(path, data)
+    }
+
+    fn write_scroll(&self, scroll: Scroll) -> Result<Scroll> {
+        self.write_layer()?.write_scroll(scroll)
+    }
+
+    fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut paths = BTreeSet::new();
+        for ns in self.stack() {
+            match ns.list(prefix) {
+                Ok(listed) => paths.extend(listed),
+                Err(Error::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Watch every layer
+    ///
+    /// A change in a lower layer is dropped while a layer above still holds
+    /// the path, since a read would not see it. Layers bound later are not
+    /// included; `Kernel::bind` remounts the union so kernel watches are.
+    #[cfg(feature = "std-channel")]
+    fn watch(&self, pattern: &str) -> Result<Receiver<Scroll>> {
+        let layers = self.stack();
+        let (tx, output_rx) = channel(16);
+
+        // Fail only if no layer could be watched at all
+        let mut first_error = None;
+        let mut attached = 0;
+        for (depth, ns) in layers.iter().enumerate() {
+            let mut rx = match ns.watch(pattern) {
+                Ok(rx) => rx,
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            attached += 1;
+
+            let above: Vec<_> = layers[..depth].to_vec();
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                while let Some(scroll) = rx.recv() {
+                    if read_through(&above, &scroll.key).is_ok_and(|s| s.is_some()) {
+                        continue; // Shadowed
+                    }
+                    if tx.send(scroll).is_err() {
+                        break; // Receiver dropped
+                    }
+                }
+            });
+        }
+        if let (0, Some(e)) = (attached, first_error) {
+            return Err(e);
+        }
+
+        Ok(output_rx)
+    }
+
+    #[cfg(not(feature = "std-channel"))]
+    fn watch(&self, _pattern: &str) -> Result<Receiver<Scroll>> {
+        Err(Error::Unavailable("watch requires the std-channel feature".to_string()))
+    }
+
+    fn close(&self) -> Result<()> {
+        for ns in self.stack() {
+            let _ = ns.close();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nine_s::MemoryNamespace;
+    use serde_json::json;
+
+    fn defaults() -> Arc<MemoryNamespace> {
+        let ns = MemoryNamespace::new();
+        ns.write("/theme", json!("dark")).unwrap();
+        ns.write("/unit", json!("sat")).unwrap();
+        Arc::new(ns)
+    }
+
+    #[test]
+    fn reads_fall_through_and_writes_go_to_the_write_layer() {
+        let user = Arc::new(MemoryNamespace::new());
+        let defaults = defaults();
+        let union = Union::new()
+            .with_write_layer(user.clone())
+            .with_layer(defaults.clone());
+
+        assert_eq!(union.read("/theme").unwrap().unwrap().data, "dark");
+
+        union.write("/theme", json!("light")).unwrap();
+        assert_eq!(union.read("/theme").unwrap().unwrap().data, "light");
+        assert_eq!(defaults.read("/theme").unwrap().unwrap().data, "dark");
+
+        // Deleting the override reveals the default again
+        user.delete("/theme").unwrap();
+        assert_eq!(union.read("/theme").unwrap().unwrap().data, "dark");
+        assert!(union.read("/missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn union_without_write_layer_is_read_only() {
+        let union = Union::new().with_layer(defaults());
+
+        assert!(matches!(union.write("/theme", json!("light")), Err(Error::Permission(_))));
+        assert_eq!(union.read("/unit").unwrap().unwrap().data, "sat");
+    }
+
+    #[test]
+    fn list_merges_layers() {
+        let user = Arc::new(MemoryNamespace::new());
+        let union = Union::new().with_layer(defaults());
+        union.bind_writable(user, Bind::Before);
+
+        union.write("/theme", json!("light")).unwrap();
+        union.write("/lang", json!("en")).unwrap();
+
+        assert_eq!(union.list("/").unwrap(), vec!["/lang", "/theme", "/unit"]);
+        assert_eq!(union.len(), 2);
+    }
+
+    #[cfg(feature = "std-channel")]
+    #[test]
+    fn watch_drops_changes_shadowed_by_a_higher_layer() {
+        let user = Arc::new(MemoryNamespace::new());
+        let defaults = defaults();
+        let union = Union::new()
+            .with_write_layer(user.clone())
+            .with_layer(defaults.clone());
+        user.write("/theme", json!("light")).unwrap();
+
+        let mut rx = union.watch("/*").unwrap();
+        defaults.write("/theme", json!("solarized")).unwrap(); // Hidden by user
+        defaults.write("/unit", json!("btc")).unwrap();
+
+        let scroll = rx.recv().unwrap();
+        assert_eq!(scroll.key, "/unit");
+        assert_eq!(scroll.data, "btc");
+    }
+}
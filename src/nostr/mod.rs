@@ -188,6 +188,32 @@ impl NostrSigner {
     }
 }
 
+/// Verify a BIP-340 Schnorr signature made by `sign_message_schnorr`
+///
+/// `pubkey` may be hex or npub. The message is hashed with SHA-256 before
+/// verifying, matching the signing side.
+///
+/// # Returns
+/// False if the signature doesn't match; an error if an input is malformed.
+pub fn verify_message_schnorr(pubkey: &str, message: &str, signature: &str) -> Result<bool, NostrError> {
+    use nostr::secp256k1::{schnorr, Message, Secp256k1, XOnlyPublicKey};
+    use sha2::{Digest, Sha256};
+    use std::str::FromStr;
+
+    let pubkey = XOnlyPublicKey::from_str(&parse_public_key(pubkey)?.to_hex())
+        .map_err(|e| NostrError::InvalidPublicKey(e.to_string()))?;
+    let signature = schnorr::Signature::from_str(signature)
+        .map_err(|e| NostrError::SigningError(format!("Invalid signature: {}", e)))?;
+
+    let hash = Sha256::digest(message.as_bytes());
+    let msg = Message::from_digest_slice(&hash)
+        .map_err(|e| NostrError::SigningError(format!("Invalid message hash: {}", e)))?;
+
+    Ok(Secp256k1::verification_only()
+        .verify_schnorr(&signature, &msg, &pubkey)
+        .is_ok())
+}
+
 /// Parse a public key from hex or bech32 (npub) format
 pub fn parse_public_key(input: &str) -> Result<PublicKey, NostrError> {
     // Try npub first
@@ -312,4 +338,20 @@ mod tests {
         let different = signer.sign_message_schnorr("different message").unwrap();
         assert_ne!(signature, different);
     }
+
+    #[test]
+    fn test_verify_message_schnorr() {
+        let master = MasterKey::from_mnemonic(TEST_MNEMONIC).unwrap();
+        let signer = NostrSigner::from_master_key(&master).unwrap();
+        let other = NostrSigner::from_master_key_with_account(&master, 1).unwrap();
+
+        let signature = signer.sign_message_schnorr("checkpoint").unwrap();
+        let hex = signer.public_key_hex();
+
+        assert!(verify_message_schnorr(&hex, "checkpoint", &signature).unwrap());
+        assert!(verify_message_schnorr(&signer.npub().unwrap(), "checkpoint", &signature).unwrap());
+        assert!(!verify_message_schnorr(&hex, "tampered", &signature).unwrap());
+        assert!(!verify_message_schnorr(&other.public_key_hex(), "checkpoint", &signature).unwrap());
+        assert!(verify_message_schnorr(&hex, "checkpoint", "not-hex").is_err());
+    }
 }
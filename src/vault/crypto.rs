@@ -225,7 +225,7 @@ pub fn derive_app_key(master_key: &[u8; 32], app_key: &str) -> [u8; 32] {
 }
 
 /// HMAC-SHA256 implementation
-fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+pub(crate) fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
     use sha2::{Sha256, Digest};
 
     const BLOCK_SIZE: usize = 64;
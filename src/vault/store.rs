@@ -178,6 +178,18 @@ impl VaultStore {
     /// # Security
     /// - Current key is zeroized after use
     /// - Seed is automatically zeroized via SecureSeed when function returns
+    ///
+    /// Returns the new vault key. 9S `Store`s opened with the old key must
+    /// be re-keyed with `Store::rotate_key(&old_key, &new_key)`. The old key
+    /// this method unlocks is zeroized inside it, and the old passphrase
+    /// stops working once it returns, so take the old key first:
+    ///
+    /// ```rust,ignore
+    /// let mut old_key = vault.unlock(old_pass)?;
+    /// let new_key = vault.change_passphrase(old_pass, new_pass)?;
+    /// store.rotate_key(&old_key, &new_key)?;
+    /// old_key.zeroize();
+    /// ```
     pub fn change_passphrase(
         &self,
         current_passphrase: &str,
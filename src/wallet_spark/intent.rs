@@ -0,0 +1,400 @@
+//! Send Intents - At most one payment per user-initiated send
+//!
+//! A UI that retries `/send` after a timeout can't tell "never sent" from
+//! "sent, but the answer was lost". Every send therefore runs under an
+//! intent: a record with its own idempotency key, written to the wallet
+//! Store *before* the SDK is called.
+//!
+//! ```text
+//! /send/intent {to, amount} ──► intent (pending, key K) ──persist──► Store
+//!                                     │
+//! /send {intent_id} ──────────────────┤
+//!                                     ▼ send_payment(idempotency_key = K)
+//!                                resolved → payment_id
+//! ```
+//!
+//! - **Reserve**: `/send/intent` hands out the id before any money moves,
+//!   so even a first attempt that errors can be retried. A `/send` without
+//!   an id makes one up and names it in its error.
+//! - **Retry**: the same `intent_id` (or `quote_id` for `/send/confirm`)
+//!   reuses K, so the SDK pays once however often the call is repeated.
+//!   An LNURL-Pay intent keeps the invoice it fetched and pays that again.
+//! - **Crash**: on reconnect, pending intents are looked up in
+//!   `list_payments` by idempotency key, or by the invoice they pay. Once
+//!   a sync has completed, an intent with no payment never went out and is
+//!   marked failed, so nothing is paid without the user asking again;
+//!   until then it stays pending.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Fire and forget (a retry can pay twice)
+//! **Antithesis**: Never retry (a timeout strands the user)
+//! **Synthesis**: Persist the intent, retry under one key, reconcile on reconnect
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::nine_s::{self, Scroll, ScrollType};
+
+use super::events::PaymentType;
+use super::reactor::{WalletReactor, WALLET_ROOT};
+use super::sdk::PaymentInfo;
+
+#[cfg(feature = "crypto")]
+use super::persistence::WalletPersistence;
+
+/// Where an intent stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntentStatus {
+    /// Persisted; the SDK may or may not have seen it
+    Pending,
+    /// Bound to exactly one payment
+    Resolved,
+    /// Never reached the network; a new send needs a new intent
+    Failed,
+}
+
+/// A user-initiated send and the one payment it became
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ScrollType)]
+#[scroll(type = "wallet/send-intent@v1")]
+pub struct SendIntent {
+    pub intent_id: String,
+    /// Passed to the SDK on every attempt
+    pub idempotency_key: String,
+    pub destination: String,
+    /// `None` when the destination carries the amount (BOLT11)
+    pub amount_sat: Option<u64>,
+    /// Invoice fetched for an LNURL-Pay destination, paid on every attempt
+    #[serde(default)]
+    pub invoice: Option<String>,
+    pub status: IntentStatus,
+    /// Unix seconds the intent was created
+    pub created_at: u64,
+    pub payment_id: Option<String>,
+    pub fee_sat: Option<u64>,
+    pub timestamp: Option<u64>,
+    /// Payment state when resolved (`Pending`, `Complete`, ...)
+    pub payment_state: Option<String>,
+}
+
+impl SendIntent {
+    /// A pending intent with a fresh idempotency key
+    pub fn new(
+        intent_id: impl Into<String>,
+        destination: impl Into<String>,
+        amount_sat: Option<u64>,
+        now: u64,
+    ) -> Self {
+        Self {
+            intent_id: intent_id.into(),
+            idempotency_key: new_idempotency_key(),
+            destination: destination.into(),
+            amount_sat,
+            invoice: None,
+            status: IntentStatus::Pending,
+            created_at: now,
+            payment_id: None,
+            fee_sat: None,
+            timestamp: None,
+            payment_state: None,
+        }
+    }
+
+    /// What the SDK is asked to pay: the fetched invoice, else the destination
+    pub fn pays(&self) -> &str {
+        self.invoice.as_deref().unwrap_or(&self.destination)
+    }
+
+    /// Store and reactor key of this intent
+    pub fn key(&self) -> String {
+        intent_key(&self.intent_id)
+    }
+
+    /// The intent as a `wallet/send-intent@v1` scroll
+    pub fn to_scroll(&self) -> nine_s::Result<Scroll> {
+        Scroll::encode(self.key(), self)
+    }
+
+    /// Check that a retry asks for the same send
+    ///
+    /// # Errors
+    /// `Conflict` if the destination or amount differ from the intent.
+    pub fn check_retry(&self, destination: &str, amount_sat: Option<u64>) -> nine_s::Result<()> {
+        if destination != self.destination {
+            return Err(nine_s::Error::Conflict(format!(
+                "intent {} is for {}, not {}",
+                self.intent_id, self.destination, destination
+            )));
+        }
+        match (amount_sat, self.amount_sat) {
+            (Some(asked), Some(intended)) if asked != intended => Err(nine_s::Error::Conflict(format!(
+                "intent {} is for {} sat, not {}",
+                self.intent_id, intended, asked
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Bind the intent to the payment it produced
+    pub fn resolve(&mut self, payment: &PaymentInfo) {
+        self.status = IntentStatus::Resolved;
+        self.amount_sat = Some(payment.amount_sat);
+        self.payment_id = Some(payment.id.clone());
+        self.fee_sat = payment.fee_sat;
+        self.timestamp = payment.timestamp;
+        self.payment_state = Some(format!("{:?}", payment.status));
+    }
+
+    /// The `wallet/payment@v1` scroll a resolved intent returns
+    ///
+    /// Retries get the same scroll the first attempt did.
+    pub fn payment_scroll(&self) -> Option<Scroll> {
+        let payment_id = self.payment_id.as_ref().filter(|_| self.status == IntentStatus::Resolved)?;
+        Some(Scroll::typed(
+            format!("{}/tx/{}", WALLET_ROOT, payment_id),
+            json!({
+                "id": payment_id,
+                "txid": payment_id,
+                "type": "send",
+                "amount_sat": self.amount_sat,
+                "fee_sat": self.fee_sat,
+                "timestamp": self.timestamp,
+                "state": self.payment_state,
+                "intent_id": self.intent_id,
+                "idempotency_key": self.idempotency_key,
+            }),
+            "wallet/payment@v1",
+        ))
+    }
+}
+
+/// Store and reactor key of intent `intent_id`
+pub fn intent_key(intent_id: &str) -> String {
+    format!("{}/intent/{}", WALLET_ROOT, intent_id)
+}
+
+/// A random intent id, for sends the caller didn't name
+pub fn new_intent_id() -> String {
+    hex::encode(rand::random::<[u8; 16]>())
+}
+
+/// A random UUIDv4, the format Spark transfer ids use
+pub fn new_idempotency_key() -> String {
+    let mut bytes = rand::random::<[u8; 16]>();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// Bind pending intents to the payments that carried them out
+///
+/// A payment whose id is the intent's idempotency key wins; otherwise a
+/// send that settled the invoice the intent pays. Amounts and times are
+/// never enough: two sends of the same amount can't be told apart. No
+/// payment is bound to two intents, nor to one already in `claimed`.
+///
+/// # Returns
+/// Indices into `pending` of intents no payment was found for.
+pub fn reconcile(
+    pending: &mut [SendIntent],
+    payments: &[PaymentInfo],
+    claimed: &HashSet<String>,
+) -> Vec<usize> {
+    let mut claimed = claimed.clone();
+    let mut order: Vec<usize> = (0..pending.len()).collect();
+    order.sort_by_key(|&i| pending[i].created_at);
+
+    let mut unmatched = Vec::new();
+    for i in order {
+        let intent = &mut pending[i];
+        let unclaimed = |p: &&PaymentInfo| !claimed.contains(&p.id);
+
+        let found = payments
+            .iter()
+            .filter(unclaimed)
+            .find(|p| p.id == intent.idempotency_key)
+            .or_else(|| {
+                payments
+                    .iter()
+                    .filter(unclaimed)
+                    .filter(|p| p.payment_type == PaymentType::Send)
+                    .find(|p| {
+                        p.invoice.as_deref()
+                            .is_some_and(|invoice| invoice.eq_ignore_ascii_case(intent.pays()))
+                    })
+            });
+
+        match found {
+            Some(payment) => {
+                claimed.insert(payment.id.clone());
+                intent.resolve(payment);
+            }
+            None => unmatched.push(i),
+        }
+    }
+
+    unmatched.sort_unstable();
+    unmatched
+}
+
+/// Where intents are kept: the Store when there is one, else the reactor
+pub struct IntentLog<'a> {
+    reactor: &'a WalletReactor,
+    #[cfg(feature = "crypto")]
+    persistence: Option<&'a WalletPersistence>,
+}
+
+impl<'a> IntentLog<'a> {
+    /// Intents held only in the reactor cache (lost on restart)
+    pub fn new(reactor: &'a WalletReactor) -> Self {
+        Self {
+            reactor,
+            #[cfg(feature = "crypto")]
+            persistence: None,
+        }
+    }
+
+    /// Keep intents in the wallet Store as well
+    #[cfg(feature = "crypto")]
+    pub fn set_persistence(&mut self, persistence: Option<&'a WalletPersistence>) {
+        self.persistence = persistence;
+    }
+
+    /// Look up an intent by id
+    pub fn get(&self, intent_id: &str) -> nine_s::Result<Option<SendIntent>> {
+        let key = intent_key(intent_id);
+
+        #[cfg(feature = "crypto")]
+        if let Some(persistence) = self.persistence {
+            return persistence.get(&key)?.map(|s| s.decode()).transpose();
+        }
+
+        self.reactor.get_cached(&key).map(|s| s.decode()).transpose()
+    }
+
+    /// Record an intent (write-through to the Store)
+    pub fn save(&self, intent: &SendIntent) -> nine_s::Result<()> {
+        let scroll = intent.to_scroll()?;
+
+        #[cfg(feature = "crypto")]
+        if let Some(persistence) = self.persistence {
+            return persistence.persist(scroll);
+        }
+
+        self.reactor.emit(scroll);
+        Ok(())
+    }
+
+    /// Every intent on record
+    pub fn all(&self) -> nine_s::Result<Vec<SendIntent>> {
+        let prefix = format!("{}/intent", WALLET_ROOT);
+
+        #[cfg(feature = "crypto")]
+        if let Some(persistence) = self.persistence {
+            let mut intents = Vec::new();
+            for path in persistence.list(&prefix)? {
+                if let Some(scroll) = persistence.get(&path)? {
+                    intents.push(scroll.decode()?);
+                }
+            }
+            return Ok(intents);
+        }
+
+        self.reactor
+            .cached_under(&prefix)
+            .iter()
+            .map(Scroll::decode)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet_spark::events::PaymentState;
+
+    fn sent(id: &str, amount_sat: u64, timestamp: u64) -> PaymentInfo {
+        PaymentInfo {
+            id: id.to_string(),
+            payment_type: PaymentType::Send,
+            status: PaymentState::Complete,
+            amount_sat,
+            fee_sat: Some(1),
+            timestamp: Some(timestamp),
+            description: None,
+            invoice: None,
+        }
+    }
+
+    fn paid(id: &str, invoice: &str, amount_sat: u64) -> PaymentInfo {
+        PaymentInfo { invoice: Some(invoice.to_string()), ..sent(id, amount_sat, 101) }
+    }
+
+    #[test]
+    fn idempotency_keys_are_uuid_v4() {
+        let key = new_idempotency_key();
+        assert_eq!(key.len(), 36);
+        assert_eq!(&key[14..15], "4");
+        assert_ne!(key, new_idempotency_key());
+    }
+
+    #[test]
+    fn reconcile_binds_each_payment_to_one_intent() {
+        let mut keyed = SendIntent::new("a", "sp1qa", Some(500), 100);
+        keyed.idempotency_key = "k-a".into();
+        let mut pending = vec![
+            keyed,
+            SendIntent::new("b", "lnbcrt500n1b", None, 100),
+            SendIntent::new("c", "sp1qc", Some(500), 100),
+            SendIntent::new("d", "lnbcrt900n1d", None, 100),
+            SendIntent::new("e", "lnbcrt700n1e", None, 100),
+            SendIntent { invoice: Some("lnbcrt300n1f".into()), ..SendIntent::new("f", "zap@example.com", Some(30), 100) },
+        ];
+        let payments = vec![
+            sent("p1", 500, 102),
+            sent("k-a", 500, 101),
+            paid("p2", "LNBCRT500N1B", 500),
+            paid("claimed", "lnbcrt900n1d", 900),
+            paid("other", "lnbcrt700n1x", 700),
+            paid("p3", "lnbcrt300n1f", 30),
+        ];
+        let claimed = HashSet::from(["claimed".to_string()]);
+
+        let unmatched = reconcile(&mut pending, &payments, &claimed);
+
+        assert_eq!(pending[0].payment_id.as_deref(), Some("k-a"));
+        assert_eq!(pending[1].payment_id.as_deref(), Some("p2"));
+        // An LNURL-Pay intent is found by the invoice it fetched
+        assert_eq!(pending[5].payment_id.as_deref(), Some("p3"));
+        // A send of the same amount isn't proof, "claimed" belongs to
+        // another intent, and an amount-less intent takes only its invoice
+        assert_eq!(unmatched, vec![2, 3, 4]);
+        assert_eq!(pending[2].status, IntentStatus::Pending);
+    }
+
+    #[test]
+    fn log_round_trips_and_retries_must_match() {
+        let reactor = WalletReactor::new();
+        let log = IntentLog::new(&reactor);
+        let mut intent = SendIntent::new("a", "sp1qa", Some(500), 100);
+        log.save(&intent).unwrap();
+        assert!(intent.payment_scroll().is_none());
+
+        intent.resolve(&sent("p1", 500, 101));
+        log.save(&intent).unwrap();
+        assert_eq!(log.get("a").unwrap(), Some(intent.clone()));
+        assert_eq!(log.all().unwrap().len(), 1);
+
+        let scroll = intent.payment_scroll().unwrap();
+        assert_eq!(scroll.key, "/wallet/tx/p1");
+        assert_eq!(scroll.data["idempotency_key"], intent.idempotency_key.as_str());
+
+        intent.check_retry("sp1qa", None).unwrap();
+        assert!(matches!(intent.check_retry("sp1qb", Some(500)), Err(nine_s::Error::Conflict(_))));
+        assert!(matches!(intent.check_retry("sp1qa", Some(501)), Err(nine_s::Error::Conflict(_))));
+    }
+}
@@ -0,0 +1,653 @@
+//! LNURL Flows - Pay a Lightning Address or LNURL, withdraw from one
+//!
+//! `/parse` tells an LNURL apart from an invoice; this module acts on it.
+//! Every step that talks to the service goes through [`LnurlHttp`], so the
+//! flows run against a stub server in tests:
+//!
+//! ```text
+//! LNURL-Pay (LUD-06/16):
+//!   alice@example.com ─GET─► {callback, min/maxSendable, metadata, commentAllowed}
+//!        check amount and comment
+//!   callback?amount=msat&comment=.. ─GET─► {pr}
+//!        check pr: description_hash = sha256(metadata), amount = requested
+//!   pay pr (under a send intent, like /send)
+//!
+//! LNURL-Withdraw (LUD-03):
+//!   lnurl1... ─GET─► {callback, k1, min/maxWithdrawable, defaultDescription}
+//!        check amount, create our own invoice
+//!   callback?k1=..&pr=invoice ─GET─► {status: "OK"}
+//! ```
+//!
+//! Service errors (`{"status": "ERROR", "reason": ..}`) and responses that
+//! fail a check are `InvalidData`; transport failures are `Connection`.
+//! Services and their callbacks must be https, except on `.onion` hosts
+//! (LUD-01), and a response may be at most `MAX_RESPONSE_BYTES`.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Hand the LNURL to the SDK (opaque, untestable offline)
+//! **Antithesis**: Trust whatever invoice the service returns (it could ask for more)
+//! **Synthesis**: Run the protocol here, verify every response, inject the transport
+
+use std::io::Read;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::nine_s::{self, Scroll, ScrollType};
+
+use super::parse::{Bolt11Invoice, LnurlKind, ParsedInput};
+use super::reactor::WALLET_ROOT;
+
+/// How long a request to an LNURL service may take
+pub const HTTP_TIMEOUT_SECS: u64 = 30;
+
+/// Largest response body read from an LNURL service
+pub const MAX_RESPONSE_BYTES: u64 = 128 * 1024;
+
+/// Transport for LNURL requests (every LNURL call is a GET returning JSON)
+pub trait LnurlHttp: Send + Sync {
+    /// GET `url` and parse the body as JSON
+    ///
+    /// # Errors
+    /// `Connection` if the request fails, `InvalidData` if the body isn't JSON.
+    fn get_json(&self, url: &str) -> nine_s::Result<Value>;
+}
+
+/// The default transport: blocking HTTPS via `ureq`
+pub struct UreqHttp {
+    agent: ureq::Agent,
+}
+
+impl UreqHttp {
+    /// Client with a `HTTP_TIMEOUT_SECS` timeout
+    pub fn new() -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+                .build(),
+        }
+    }
+}
+
+impl Default for UreqHttp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LnurlHttp for UreqHttp {
+    fn get_json(&self, url: &str) -> nine_s::Result<Value> {
+        // LNURL services report errors in the body, often with a 4xx status
+        let response = match self.agent.get(url).call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(nine_s::Error::Connection(format!("{}: {}", url, e))),
+        };
+        let mut body = String::new();
+        response
+            .into_reader()
+            .take(MAX_RESPONSE_BYTES + 1)
+            .read_to_string(&mut body)
+            .map_err(|e| nine_s::Error::Connection(format!("{}: {}", url, e)))?;
+        if body.len() as u64 > MAX_RESPONSE_BYTES {
+            return Err(nine_s::Error::InvalidData(format!(
+                "{} returned more than {} bytes",
+                url, MAX_RESPONSE_BYTES
+            )));
+        }
+        serde_json::from_str(&body)
+            .map_err(|e| nine_s::Error::InvalidData(format!("{} did not return JSON: {}", url, e)))
+    }
+}
+
+/// What an LNURL-Pay service accepts (LUD-06)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayParams {
+    pub domain: String,
+    pub callback: String,
+    pub min_sendable_msat: u64,
+    pub max_sendable_msat: u64,
+    /// Raw metadata JSON; the invoice's description hash commits to it
+    pub metadata: String,
+    /// Longest comment accepted (LUD-12), 0 if none
+    pub comment_allowed: u64,
+    /// The Lightning Address this came from, if any
+    pub address: Option<String>,
+}
+
+/// What an LNURL-Withdraw service offers (LUD-03)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawParams {
+    pub domain: String,
+    pub callback: String,
+    pub k1: String,
+    pub min_withdrawable_msat: u64,
+    pub max_withdrawable_msat: u64,
+    pub default_description: String,
+}
+
+/// A resolved LNURL, for the UI to show before asking for an amount
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ScrollType)]
+#[scroll(type = "wallet/lnurl@v1")]
+#[serde(tag = "tag", rename_all = "lowercase")]
+pub enum LnurlParams {
+    Pay(PayParams),
+    Withdraw(WithdrawParams),
+}
+
+impl LnurlParams {
+    /// The params as a `wallet/lnurl@v1` scroll
+    pub fn to_scroll(&self) -> nine_s::Result<Scroll> {
+        Scroll::encode(format!("{}/lnurl", WALLET_ROOT), self)
+    }
+}
+
+/// An invoice an LNURL-Pay service issued, checked against its params
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayInvoice {
+    pub invoice: Bolt11Invoice,
+    /// Shown after paying (LUD-09), passed through unchecked
+    pub success_action: Option<Value>,
+}
+
+/// Fetch the params behind a Lightning Address or LNURL
+///
+/// # Errors
+/// `InvalidData` if `target` is neither, is LNURL-Auth or channel, is not
+/// https, or the service answers with an error, an unknown tag or a plain
+/// http callback.
+pub fn resolve(http: &dyn LnurlHttp, target: &str) -> nine_s::Result<LnurlParams> {
+    let (url, domain, address) = match ParsedInput::parse(target)? {
+        ParsedInput::LightningAddress(ln) => (ln.lnurlp_url, ln.domain, Some(ln.address)),
+        ParsedInput::Lnurl(lnurl) if matches!(lnurl.kind, LnurlKind::Auth | LnurlKind::Channel) => {
+            return Err(invalid(format!("{:?} LNURLs can't be paid or withdrawn from", lnurl.kind)));
+        }
+        ParsedInput::Lnurl(lnurl) => (lnurl.url, lnurl.domain, None),
+        other => return Err(invalid(format!("{} is not an LNURL or Lightning Address", other.kind()))),
+    };
+
+    secure(&url)?;
+    let response = checked(http.get_json(&url)?)?;
+    if let Some(callback) = response["callback"].as_str() {
+        secure(callback)?;
+    }
+    match response["tag"].as_str() {
+        Some("payRequest") => {
+            let min = field_u64(&response, "minSendable")?;
+            let max = field_u64(&response, "maxSendable")?;
+            if min > max {
+                return Err(invalid(format!("{} sends between {} and {} msat", domain, min, max)));
+            }
+            Ok(LnurlParams::Pay(PayParams {
+                domain,
+                callback: field_str(&response, "callback")?,
+                min_sendable_msat: min,
+                max_sendable_msat: max,
+                metadata: field_str(&response, "metadata")?,
+                comment_allowed: response["commentAllowed"].as_u64().unwrap_or(0),
+                address,
+            }))
+        }
+        Some("withdrawRequest") => {
+            let min = response["minWithdrawable"].as_u64().unwrap_or(0);
+            let max = field_u64(&response, "maxWithdrawable")?;
+            if min > max {
+                return Err(invalid(format!("{} withdraws between {} and {} msat", domain, min, max)));
+            }
+            Ok(LnurlParams::Withdraw(WithdrawParams {
+                domain,
+                callback: field_str(&response, "callback")?,
+                k1: field_str(&response, "k1")?,
+                min_withdrawable_msat: min,
+                max_withdrawable_msat: max,
+                default_description: response["defaultDescription"].as_str().unwrap_or_default().to_string(),
+            }))
+        }
+        other => Err(invalid(format!("unsupported LNURL tag {:?}", other))),
+    }
+}
+
+/// Ask an LNURL-Pay service for an invoice and check it
+///
+/// # Errors
+/// `InvalidData` if the amount or comment is out of bounds, or the invoice
+/// doesn't commit to the metadata or is for a different amount.
+pub fn request_invoice(
+    http: &dyn LnurlHttp,
+    params: &PayParams,
+    amount_sat: u64,
+    comment: Option<&str>,
+) -> nine_s::Result<PayInvoice> {
+    let amount_msat = to_msat(amount_sat)?;
+    if amount_msat < params.min_sendable_msat || amount_msat > params.max_sendable_msat {
+        return Err(invalid(format!(
+            "{} accepts {} to {} sat, not {}",
+            params.domain,
+            to_sat_ceil(params.min_sendable_msat),
+            params.max_sendable_msat / 1000,
+            amount_sat
+        )));
+    }
+
+    let mut query = vec![("amount", amount_msat.to_string())];
+    if let Some(comment) = comment.filter(|c| !c.is_empty()) {
+        let length = comment.chars().count() as u64;
+        if length > params.comment_allowed {
+            return Err(invalid(format!(
+                "{} allows comments of up to {} characters, not {}",
+                params.domain, params.comment_allowed, length
+            )));
+        }
+        query.push(("comment", comment.to_string()));
+    }
+
+    let response = checked(http.get_json(&with_query(&params.callback, &query)?)?)?;
+    let pr = field_str(&response, "pr")?;
+    let ParsedInput::Bolt11(invoice) = ParsedInput::parse(&pr)? else {
+        return Err(invalid(format!("{} returned something other than an invoice", params.domain)));
+    };
+
+    let metadata_hash = hex::encode(Sha256::digest(params.metadata.as_bytes()));
+    if invoice.description_hash.as_deref() != Some(metadata_hash.as_str()) {
+        return Err(invalid(format!("invoice from {} doesn't commit to its metadata", params.domain)));
+    }
+    if invoice.amount_msat != Some(amount_msat) {
+        return Err(invalid(format!(
+            "invoice from {} is for {:?} msat, not {}",
+            params.domain, invoice.amount_msat, amount_msat
+        )));
+    }
+
+    Ok(PayInvoice {
+        invoice,
+        success_action: response.get("successAction").filter(|a| !a.is_null()).cloned(),
+    })
+}
+
+/// Check a withdrawal amount against what the service offers
+///
+/// # Errors
+/// `InvalidData` if the amount is out of bounds.
+pub fn check_withdraw(params: &WithdrawParams, amount_sat: u64) -> nine_s::Result<()> {
+    let amount_msat = to_msat(amount_sat)?;
+    if amount_msat < params.min_withdrawable_msat || amount_msat > params.max_withdrawable_msat {
+        return Err(invalid(format!(
+            "{} offers {} to {} sat, not {}",
+            params.domain,
+            to_sat_ceil(params.min_withdrawable_msat),
+            params.max_withdrawable_msat / 1000,
+            amount_sat
+        )));
+    }
+    Ok(())
+}
+
+/// Hand our invoice to an LNURL-Withdraw service
+///
+/// # Errors
+/// `InvalidData` if the service refuses it.
+pub fn submit_withdraw(http: &dyn LnurlHttp, params: &WithdrawParams, invoice: &str) -> nine_s::Result<()> {
+    let url = with_query(&params.callback, &[("k1", params.k1.clone()), ("pr", invoice.to_string())])?;
+    checked(http.get_json(&url)?).map(|_| ())
+}
+
+/// Which LNURL flow a `/lnurl/...` write wants, read from its data
+pub fn target(data: &Value) -> nine_s::Result<&str> {
+    data["to"].as_str()
+        .or_else(|| data["lnurl"].as_str())
+        .or_else(|| data["destination"].as_str())
+        .ok_or_else(|| invalid("Missing 'to' field"))
+}
+
+/// The amount a `/lnurl/...` write asks for (always needed: LNURL has no "any")
+pub fn amount_sat(data: &Value) -> nine_s::Result<u64> {
+    data["amount"].as_u64()
+        .or_else(|| data["amount_sat"].as_u64())
+        .ok_or_else(|| invalid("Missing 'amount' field"))
+}
+
+/// A `wallet/payment@v1` scroll for an LNURL-Pay, with what the UI shows after
+pub fn paid_scroll(mut payment: Scroll, params: &PayParams, paid: &PayInvoice) -> Scroll {
+    payment.data["lnurl_domain"] = Value::from(params.domain.as_str());
+    if let Some(address) = &params.address {
+        payment.data["lightning_address"] = Value::from(address.as_str());
+    }
+    if let Some(action) = &paid.success_action {
+        payment.data["success_action"] = action.clone();
+    }
+    payment
+}
+
+/// A `wallet/lnurl-withdraw@v1` scroll: the invoice the service agreed to pay
+///
+/// The service pays asynchronously; the payment itself arrives on `/tx/**`.
+pub fn withdraw_scroll(params: &WithdrawParams, invoice: &str, amount_sat: u64) -> Scroll {
+    Scroll::typed(
+        format!("{}/lnurl/withdraw", WALLET_ROOT),
+        serde_json::json!({
+            "domain": params.domain,
+            "invoice": invoice,
+            "amount_sat": amount_sat,
+            "status": "submitted",
+        }),
+        "wallet/lnurl-withdraw@v1",
+    )
+}
+
+fn invalid(reason: impl Into<String>) -> nine_s::Error {
+    nine_s::Error::InvalidData(reason.into())
+}
+
+/// Turn `{"status": "ERROR", "reason": ..}` into an error
+fn checked(response: Value) -> nine_s::Result<Value> {
+    if response["status"].as_str().is_some_and(|s| s.eq_ignore_ascii_case("ERROR")) {
+        let reason = response["reason"].as_str().unwrap_or("no reason given");
+        return Err(invalid(format!("LNURL service error: {}", reason)));
+    }
+    Ok(response)
+}
+
+fn field_str(response: &Value, name: &str) -> nine_s::Result<String> {
+    response[name]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| invalid(format!("LNURL response has no '{}'", name)))
+}
+
+fn field_u64(response: &Value, name: &str) -> nine_s::Result<u64> {
+    response[name]
+        .as_u64()
+        .ok_or_else(|| invalid(format!("LNURL response has no '{}'", name)))
+}
+
+fn to_msat(amount_sat: u64) -> nine_s::Result<u64> {
+    amount_sat
+        .checked_mul(1000)
+        .ok_or_else(|| invalid(format!("{} sat is too large to send", amount_sat)))
+}
+
+/// Whole sats covering `msat`, rounded up
+fn to_sat_ceil(msat: u64) -> u64 {
+    msat / 1000 + u64::from(msat % 1000 != 0)
+}
+
+/// Refuse a service URL that isn't https, unless it is an onion service
+fn secure(url: &str) -> nine_s::Result<url::Url> {
+    let parsed = url::Url::parse(url).map_err(|e| invalid(format!("bad LNURL URL: {}", e)))?;
+    let onion = parsed.host_str().is_some_and(|host| host.ends_with(".onion"));
+    match parsed.scheme() {
+        "https" => Ok(parsed),
+        "http" if onion => Ok(parsed),
+        scheme => Err(invalid(format!("LNURL service {} uses {}, not https", url, scheme))),
+    }
+}
+
+/// `base` with `pairs` appended to its query (it may already have one)
+fn with_query(base: &str, pairs: &[(&str, String)]) -> nine_s::Result<String> {
+    let mut url = secure(base)?;
+    {
+        let mut query = url.query_pairs_mut();
+        for (key, value) in pairs {
+            query.append_pair(key, value);
+        }
+    }
+    Ok(url.into())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::wallet_spark::parse::encode_lnurl;
+    use crate::wallet_spark::parse::tests::{groups, sign_invoice};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    pub(crate) const METADATA: &str = r#"[["text/plain","zap"],["text/identifier","alice@127.0.0.1"]]"#;
+
+    /// A signed invoice an LNURL-Pay service would return
+    pub(crate) fn lnurl_invoice(amount_msat: u64, metadata: &str) -> String {
+        let hrp = format!("lnbcrt{}p", amount_msat * 10);
+        let hash = Sha256::digest(metadata.as_bytes());
+        sign_invoice(&hrp, 1_700_000_000, &[(1, groups(&[5; 32])), (23, groups(&hash))], [1; 32])
+    }
+
+    /// A one-thread HTTP server answering GETs from a path → JSON table
+    ///
+    /// Queries are recorded and stripped before lookup, so tests can check
+    /// what was asked and answer by path alone. `base` is an https URL, as
+    /// services must be; reach it through `http()`, which speaks plain http.
+    pub(crate) struct StubServer {
+        pub base: String,
+        routes: Arc<Mutex<HashMap<String, Value>>>,
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StubServer {
+        pub(crate) fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let base = format!("https://{}", listener.local_addr().unwrap());
+            let routes: Arc<Mutex<HashMap<String, Value>>> = Arc::default();
+            let requests: Arc<Mutex<Vec<String>>> = Arc::default();
+            let (table, seen) = (routes.clone(), requests.clone());
+
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(mut stream) = stream else { continue };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).is_err() {
+                        continue;
+                    }
+                    // Skip the headers
+                    let mut header = String::new();
+                    while reader.read_line(&mut header).is_ok_and(|n| n > 0) && header != "\r\n" {
+                        header.clear();
+                    }
+
+                    let target = line.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    seen.lock().unwrap().push(target.clone());
+                    let path = target.split('?').next().unwrap_or("/");
+                    let (status, body) = match table.lock().unwrap().get(path) {
+                        Some(body) => ("200 OK", body.to_string()),
+                        None => ("404 Not Found", json!({"status": "ERROR", "reason": "not found"}).to_string()),
+                    };
+                    let _ = write!(
+                        stream,
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                }
+            });
+
+            Self { base, routes, requests }
+        }
+
+        /// Answer GETs of `path` with `body`
+        pub(crate) fn route(&self, path: &str, body: Value) {
+            self.routes.lock().unwrap().insert(path.to_string(), body);
+        }
+
+        /// Every request target (path and query) received so far
+        pub(crate) fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        /// A transport that reaches this server without TLS
+        pub(crate) fn http(&self) -> Arc<dyn LnurlHttp> {
+            Arc::new(Plaintext(UreqHttp::new()))
+        }
+    }
+
+    struct Plaintext(UreqHttp);
+
+    impl LnurlHttp for Plaintext {
+        fn get_json(&self, url: &str) -> nine_s::Result<Value> {
+            self.0.get_json(&url.replacen("https://", "http://", 1))
+        }
+    }
+
+    fn pay_params(base: &str) -> PayParams {
+        PayParams {
+            domain: "127.0.0.1".into(),
+            callback: format!("{}/callback", base),
+            min_sendable_msat: 1_000,
+            max_sendable_msat: 1_000_000,
+            metadata: METADATA.into(),
+            comment_allowed: 10,
+            address: None,
+        }
+    }
+
+    #[test]
+    fn request_invoice_checks_amount_comment_and_invoice() {
+        let server = StubServer::start();
+        server.route("/callback", json!({"pr": lnurl_invoice(500_000, METADATA)}));
+        let http = server.http();
+        let http = http.as_ref();
+        let params = pay_params(&server.base);
+
+        let paid = request_invoice(http, &params, 500, Some("thanks")).unwrap();
+        assert_eq!(paid.invoice.amount_sat, Some(500));
+        assert_eq!(server.requests(), vec!["/callback?amount=500000&comment=thanks"]);
+
+        // Checked before asking the service
+        assert!(request_invoice(http, &params, 2_000, None).is_err());
+        assert!(request_invoice(http, &params, 500, Some("far too long a comment")).is_err());
+        let overflow = request_invoice(http, &params, u64::MAX, None);
+        assert!(matches!(overflow, Err(nine_s::Error::InvalidData(_))));
+        let plain = PayParams { callback: params.callback.replace("https", "http"), ..params.clone() };
+        assert!(matches!(request_invoice(http, &plain, 500, None), Err(nine_s::Error::InvalidData(_))));
+        assert_eq!(server.requests().len(), 1);
+
+        // The invoice is for 500 sat, so asking for 600 must fail
+        let wrong_amount = request_invoice(http, &params, 600, None);
+        assert!(matches!(wrong_amount, Err(nine_s::Error::InvalidData(ref m)) if m.contains("msat")));
+
+        // Metadata the invoice doesn't commit to
+        let other = PayParams { metadata: "[]".into(), ..params };
+        let unhashed = request_invoice(http, &other, 500, None);
+        assert!(matches!(unhashed, Err(nine_s::Error::InvalidData(ref m)) if m.contains("metadata")));
+    }
+
+    #[test]
+    fn service_errors_and_unreachable_hosts() {
+        let server = StubServer::start();
+        server.route("/big", json!({"pad": "x".repeat(MAX_RESPONSE_BYTES as usize)}));
+        let http = UreqHttp::new();
+
+        let missing = server.http().get_json(&format!("{}/nowhere", server.base)).unwrap();
+        assert!(matches!(checked(missing), Err(nine_s::Error::InvalidData(ref m)) if m.contains("not found")));
+        let big = server.http().get_json(&format!("{}/big", server.base));
+        assert!(matches!(big, Err(nine_s::Error::InvalidData(ref m)) if m.contains("bytes")));
+
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let refused = http.get_json(&format!("http://{}/", closed));
+        assert!(matches!(refused, Err(nine_s::Error::Connection(_))));
+    }
+
+    #[test]
+    fn with_query_keeps_existing_params() {
+        let url = with_query("https://s.com/cb?id=7", &[("amount", "1000".into()), ("comment", "a b".into())]).unwrap();
+        assert_eq!(url, "https://s.com/cb?id=7&amount=1000&comment=a+b");
+    }
+
+    /// A transport answering from a table, for tests that don't need sockets
+    pub(crate) struct TableHttp(pub HashMap<String, Value>);
+
+    impl LnurlHttp for TableHttp {
+        fn get_json(&self, url: &str) -> nine_s::Result<Value> {
+            let path = url.split('?').next().unwrap_or(url);
+            self.0
+                .get(path)
+                .cloned()
+                .ok_or_else(|| nine_s::Error::Connection(format!("no route to {}", url)))
+        }
+    }
+
+    #[test]
+    fn resolve_by_tag() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "https://example.com/.well-known/lnurlp/alice".to_string(),
+            json!({
+                "tag": "payRequest",
+                "callback": "https://example.com/cb",
+                "minSendable": 1000,
+                "maxSendable": 5000,
+                "metadata": METADATA,
+            }),
+        );
+        routes.insert(
+            "https://atm.example.com/w".to_string(),
+            json!({
+                "tag": "withdrawRequest",
+                "callback": "https://atm.example.com/cb",
+                "k1": "abc",
+                "maxWithdrawable": 20_000,
+                "defaultDescription": "ATM",
+            }),
+        );
+        let http = TableHttp(routes);
+
+        let LnurlParams::Pay(pay) = resolve(&http, "alice@example.com").unwrap() else { panic!("expected pay") };
+        assert_eq!(pay.address.as_deref(), Some("alice@example.com"));
+        assert_eq!(pay.comment_allowed, 0);
+
+        let withdraw = resolve(&http, "lnurlw://atm.example.com/w").unwrap();
+        let LnurlParams::Withdraw(params) = &withdraw else { panic!("expected withdraw") };
+        check_withdraw(params, 20).unwrap();
+        assert!(check_withdraw(params, 21).is_err());
+        assert_eq!(withdraw.to_scroll().unwrap().data["tag"], "withdraw");
+
+        assert!(resolve(&http, "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").is_err());
+        assert!(matches!(resolve(&http, "bob@example.com"), Err(nine_s::Error::Connection(_))));
+    }
+
+    #[test]
+    fn inverted_bounds_are_refused() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "https://atm.example.com/w".to_string(),
+            json!({
+                "tag": "withdrawRequest",
+                "callback": "https://atm.example.com/cb",
+                "k1": "abc",
+                "minWithdrawable": 30_000,
+                "maxWithdrawable": 20_000,
+            }),
+        );
+        let http = TableHttp(routes);
+
+        let inverted = resolve(&http, "lnurlw://atm.example.com/w");
+        assert!(matches!(inverted, Err(nine_s::Error::InvalidData(ref m)) if m.contains("withdraws")));
+    }
+
+    #[test]
+    fn plain_http_only_for_onion_services() {
+        let pay = |callback: &str| json!({
+            "tag": "payRequest",
+            "callback": callback,
+            "minSendable": 1000,
+            "maxSendable": 5000,
+            "metadata": METADATA,
+        });
+        let mut routes = HashMap::new();
+        routes.insert("https://example.com/.well-known/lnurlp/alice".to_string(), pay("http://example.com/cb"));
+        routes.insert("http://example.com/p".to_string(), pay("https://example.com/cb"));
+        routes.insert("http://abc.onion/.well-known/lnurlp/bob".to_string(), pay("http://abc.onion/cb"));
+        let http = TableHttp(routes);
+
+        let plain_callback = resolve(&http, "alice@example.com");
+        assert!(matches!(plain_callback, Err(nine_s::Error::InvalidData(ref m)) if m.contains("https")));
+        let plain_service = encode_lnurl("http://example.com/p").unwrap();
+        assert!(matches!(resolve(&http, &plain_service), Err(nine_s::Error::InvalidData(_))));
+        assert!(resolve(&http, "bob@abc.onion").is_ok());
+    }
+}
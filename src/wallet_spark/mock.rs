@@ -0,0 +1,1663 @@
+//! Mock Wallet - An offline `WalletBackend` driven by a scripted ledger
+//!
+//! Everything else in `wallet_spark` needs a live Breez Spark connection.
+//! `MockWalletBackend` answers the same Namespace paths as `WalletManager`
+//! from an in-memory [`MockLedger`], and pushes every ledger change through
+//! a real [`WalletReactor`] as `SdkEvent`s, so watchers, persistence and UI
+//! code run exactly as they would against the SDK.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Test against regtest (real, but slow and flaky in CI)
+//! **Antithesis**: Stub each call site (fast, but skips the reactor)
+//! **Synthesis**: Fake the network, keep the reactor (deterministic, end to end)
+//!
+//! # Architecture
+//!
+//! ```text
+//!  test script ──► MockLedger ──SdkEvent──► WalletReactor ──► watch()
+//!  (receive,        (balance,                   │
+//!   settle, fail)    payments)                  └──► WalletPersistence
+//! ```
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! let wallet = MockWalletBackend::new(
+//!     SparkNetwork::Regtest,
+//!     MockLedger::new().with_balance(50_000).with_fee(10).settle_manually(),
+//! );
+//! wallet.connect(MNEMONIC, None)?;
+//!
+//! let mut rx = wallet.watch("/wallet/tx/**")?;
+//! let sent = wallet.write("/send", json!({"to": "sp1qfriend", "amount": 1_000}))?;
+//! wallet.settle(sent.data["id"].as_str().unwrap())?;   // PaymentSucceeded
+//!
+//! let invoice = wallet.create_invoice(2_100, Some("coffee"))?;
+//! wallet.pay_invoice(&invoice)?;                       // someone pays us
+//! ```
+//!
+//! Ids (`mock-0001`, ...), invoices and timestamps come from counters, so
+//! the same script always produces the same scrolls.
+//!
+//! Sends honour idempotency keys like the SDK does, and `time_out_next_send`
+//! pays but loses the answer, so retries and reconnects can be tested.
+//!
+//! The `/lnurl/...` paths talk to real LNURL services through the same
+//! [`LnurlHttp`] as `WalletManager`; point `with_http` at a stub server and
+//! the whole flow runs offline.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::nine_s::{self, Namespace, Scroll};
+use crate::wallet_trait::{
+    SignedMessage, TransactionDetails, WalletBackend, WalletBalance, WalletError,
+};
+
+use super::events::{Payment, PaymentDetails, PaymentState, PaymentType, SdkEvent};
+use super::intent::{IntentLog, IntentStatus, SendIntent};
+use super::lnurl::{self, LnurlHttp, LnurlParams};
+use super::parse::ParsedInput;
+use super::parse_helpers::{detect_input_type, InputTypeHint};
+use super::quote::{self, SendQuote};
+use super::reactor::WalletReactor;
+use super::sdk::PaymentInfo;
+use super::SparkNetwork;
+
+#[cfg(feature = "crypto")]
+use super::persistence::WalletPersistence;
+
+/// When outgoing payments complete
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Settlement {
+    /// Sends complete as soon as they are made
+    #[default]
+    Instant,
+    /// Sends stay pending until `settle` or `fail`
+    Manual,
+}
+
+/// An invoice we issued and nobody has paid yet
+#[derive(Debug, Clone)]
+struct OpenInvoice {
+    amount_sat: u64,
+    description: Option<String>,
+}
+
+/// Scripted wallet state behind a [`MockWalletBackend`]
+///
+/// Built up front with the `with_*` methods; changed at runtime through the
+/// backend so every change is also emitted as an event.
+#[derive(Debug, Clone)]
+pub struct MockLedger {
+    /// Spendable balance (sends are debited when made)
+    confirmed_sat: u64,
+    /// Every payment, oldest first
+    payments: Vec<Payment>,
+    /// Fee charged on every send
+    fee_sat: u64,
+    settlement: Settlement,
+    /// Reasons the next sends fail with, in order
+    failures: VecDeque<String>,
+    /// Sends that go out but report a timeout
+    timeouts: usize,
+    /// Idempotency key → the payment made under it
+    idempotency: BTreeMap<String, String>,
+    invoices: BTreeMap<String, OpenInvoice>,
+    /// Prepared sends held until paid, as the SDK holds prepare responses
+    quotes: BTreeMap<String, SendQuote>,
+    address: String,
+    bitcoin_address: String,
+    pubkey: String,
+    /// Unix seconds stamped on the next payment
+    clock: u64,
+    next_id: u64,
+    next_invoice: u64,
+    next_quote: u64,
+    next_intent: u64,
+}
+
+impl MockLedger {
+    /// An empty ledger with zero fees and instant settlement
+    pub fn new() -> Self {
+        Self {
+            confirmed_sat: 0,
+            payments: Vec::new(),
+            fee_sat: 0,
+            settlement: Settlement::Instant,
+            failures: VecDeque::new(),
+            timeouts: 0,
+            idempotency: BTreeMap::new(),
+            invoices: BTreeMap::new(),
+            quotes: BTreeMap::new(),
+            address: "sp1qmockwallet".to_string(),
+            bitcoin_address: "bcrt1qmockwallet".to_string(),
+            pubkey: format!("02{}", "ab".repeat(32)),
+            clock: 1_700_000_000,
+            next_id: 1,
+            next_invoice: 1,
+            next_quote: 1,
+            next_intent: 1,
+        }
+    }
+
+    /// Start with a confirmed balance
+    pub fn with_balance(mut self, confirmed_sat: u64) -> Self {
+        self.confirmed_sat = confirmed_sat;
+        self
+    }
+
+    /// Charge `fee_sat` on every send
+    pub fn with_fee(mut self, fee_sat: u64) -> Self {
+        self.fee_sat = fee_sat;
+        self
+    }
+
+    /// Seed history with a payment (no event is emitted, balance unchanged)
+    pub fn with_payment(mut self, payment: Payment) -> Self {
+        self.payments.push(payment);
+        self
+    }
+
+    /// Set the Spark address returned by `/address`
+    pub fn with_address(mut self, address: impl Into<String>) -> Self {
+        self.address = address.into();
+        self
+    }
+
+    /// Stamp payments from `unix_secs` onward (one second per payment)
+    pub fn with_clock(mut self, unix_secs: u64) -> Self {
+        self.clock = unix_secs;
+        self
+    }
+
+    /// Keep sends pending until `settle` or `fail` is called
+    pub fn settle_manually(mut self) -> Self {
+        self.settlement = Settlement::Manual;
+        self
+    }
+
+    /// Make the next send fail with `reason`
+    pub fn fail_next_send(mut self, reason: impl Into<String>) -> Self {
+        self.failures.push_back(reason.into());
+        self
+    }
+
+    /// Make the next send go out but report a timeout
+    pub fn time_out_next_send(mut self) -> Self {
+        self.timeouts += 1;
+        self
+    }
+
+    /// Balance as the SDK would report it
+    ///
+    /// Incoming payments that haven't settled count as trusted pending.
+    pub fn balance(&self) -> WalletBalance {
+        let trusted_pending = self
+            .payments
+            .iter()
+            .filter(|p| p.payment_type == PaymentType::Receive && p.state == PaymentState::Pending)
+            .map(|p| p.amount_sat)
+            .sum();
+        WalletBalance {
+            confirmed: self.confirmed_sat,
+            immature: 0,
+            trusted_pending,
+            untrusted_pending: 0,
+        }
+    }
+
+    /// Every payment, oldest first
+    pub fn payments(&self) -> &[Payment] {
+        &self.payments
+    }
+
+    /// Look up a payment by id
+    pub fn payment(&self, id: &str) -> Option<&Payment> {
+        self.payments.iter().find(|p| p.id == id)
+    }
+
+    fn payment_mut(&mut self, id: &str) -> Option<&mut Payment> {
+        self.payments.iter_mut().find(|p| p.id == id)
+    }
+
+    /// Record a new payment with the next id and timestamp
+    fn record(
+        &mut self,
+        payment_type: PaymentType,
+        state: PaymentState,
+        amount_sat: u64,
+        fee_sat: Option<u64>,
+        description: Option<String>,
+        details: PaymentDetails,
+    ) -> Payment {
+        let payment = Payment {
+            id: format!("mock-{:04}", self.next_id),
+            payment_type,
+            state,
+            amount_sat,
+            fee_sat,
+            timestamp: Some(self.clock),
+            description,
+            details,
+        };
+        self.next_id += 1;
+        self.clock += 1;
+        self.payments.push(payment.clone());
+        payment
+    }
+}
+
+impl Default for MockLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Offline wallet answering the `WalletManager` paths from a [`MockLedger`]
+///
+/// Implements both `Namespace` and `WalletBackend`, so it can be mounted in
+/// a Kernel at `/wallet` or handed to code written against the trait.
+pub struct MockWalletBackend {
+    network: SparkNetwork,
+    ledger: Mutex<MockLedger>,
+    connected: AtomicBool,
+    /// The same reactor `WalletManager` uses; all events flow through it
+    reactor: Arc<WalletReactor>,
+    /// Transport for LNURL services
+    http: Arc<dyn LnurlHttp>,
+    #[cfg(feature = "crypto")]
+    persistence: Option<Arc<WalletPersistence>>,
+}
+
+impl MockWalletBackend {
+    /// Create a disconnected mock wallet over `ledger`
+    pub fn new(network: SparkNetwork, ledger: MockLedger) -> Self {
+        Self {
+            network,
+            ledger: Mutex::new(ledger),
+            connected: AtomicBool::new(false),
+            reactor: Arc::new(WalletReactor::new()),
+            http: Arc::new(lnurl::UreqHttp::new()),
+            #[cfg(feature = "crypto")]
+            persistence: None,
+        }
+    }
+
+    /// Reach LNURL services through `http` instead of `ureq`
+    pub fn with_http(mut self, http: Arc<dyn LnurlHttp>) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Write payment and balance scrolls through to an encrypted Store
+    ///
+    /// Persisted state is loaded back into the reactor cache on `connect`.
+    #[cfg(feature = "crypto")]
+    pub fn with_store(mut self, store: crate::nine_s::Store) -> Self {
+        let persistence = WalletPersistence::new(store, self.reactor.clone());
+        self.persistence = Some(Arc::new(persistence));
+        self
+    }
+
+    /// Get reactor for event handling
+    pub fn reactor(&self) -> Arc<WalletReactor> {
+        self.reactor.clone()
+    }
+
+    /// Get persistence layer (if initialized)
+    #[cfg(feature = "crypto")]
+    pub fn persistence(&self) -> Option<Arc<WalletPersistence>> {
+        self.persistence.clone()
+    }
+
+    /// Get current network
+    pub fn network(&self) -> SparkNetwork {
+        self.network
+    }
+
+    /// A copy of the ledger as it stands
+    pub fn ledger(&self) -> MockLedger {
+        self.lock().clone()
+    }
+
+    // =========================================================================
+    // Scripting (what the network would do)
+    // =========================================================================
+
+    /// Someone pays us `amount_sat`; settles immediately
+    pub fn receive(&self, amount_sat: u64, description: Option<&str>) -> Result<Payment, WalletError> {
+        let (payment, balance) = {
+            let mut ledger = self.lock();
+            ledger.confirmed_sat += amount_sat;
+            let details = PaymentDetails::Spark {
+                transfer_id: None,
+                spark_address: Some(ledger.address.clone()),
+            };
+            let payment = ledger.record(
+                PaymentType::Receive,
+                PaymentState::Complete,
+                amount_sat,
+                None,
+                description.map(str::to_string),
+                details,
+            );
+            (payment, ledger.balance())
+        };
+        self.publish(SdkEvent::PaymentSucceeded { payment: payment.clone() }, &balance)?;
+        Ok(payment)
+    }
+
+    /// Someone pays us `amount_sat`; stays pending until `settle`
+    pub fn receive_pending(&self, amount_sat: u64) -> Result<Payment, WalletError> {
+        let (payment, balance) = {
+            let mut ledger = self.lock();
+            let details = PaymentDetails::Spark {
+                transfer_id: None,
+                spark_address: Some(ledger.address.clone()),
+            };
+            let payment = ledger.record(
+                PaymentType::Receive,
+                PaymentState::Pending,
+                amount_sat,
+                None,
+                None,
+                details,
+            );
+            (payment, ledger.balance())
+        };
+        self.publish(SdkEvent::PaymentPending { payment: payment.clone() }, &balance)?;
+        Ok(payment)
+    }
+
+    /// Someone pays an invoice from `/invoice`
+    ///
+    /// # Errors
+    /// `InvalidAddress` if we didn't issue the invoice or it's already paid.
+    pub fn pay_invoice(&self, invoice: &str) -> Result<Payment, WalletError> {
+        let (payment, balance) = {
+            let mut ledger = self.lock();
+            let open = ledger
+                .invoices
+                .remove(invoice)
+                .ok_or_else(|| WalletError::InvalidAddress(format!("unknown invoice: {}", invoice)))?;
+            ledger.confirmed_sat += open.amount_sat;
+            let payment = ledger.record(
+                PaymentType::Receive,
+                PaymentState::Complete,
+                open.amount_sat,
+                None,
+                open.description,
+                PaymentDetails::Lightning {
+                    swap_id: None,
+                    bolt11: Some(invoice.to_string()),
+                    preimage: Some(hex::encode(Sha256::digest(invoice.as_bytes()))),
+                },
+            );
+            (payment, ledger.balance())
+        };
+        self.publish(SdkEvent::PaymentSucceeded { payment: payment.clone() }, &balance)?;
+        Ok(payment)
+    }
+
+    /// Complete a pending payment
+    ///
+    /// # Errors
+    /// `Transaction` if there is no pending payment with that id.
+    pub fn settle(&self, id: &str) -> Result<Payment, WalletError> {
+        let (payment, balance) = {
+            let mut ledger = self.lock();
+            let payment = pending(&mut ledger, id)?;
+            payment.state = PaymentState::Complete;
+            let payment = payment.clone();
+            if payment.payment_type == PaymentType::Receive {
+                ledger.confirmed_sat += payment.amount_sat;
+            }
+            (payment, ledger.balance())
+        };
+        self.publish(SdkEvent::PaymentSucceeded { payment: payment.clone() }, &balance)?;
+        Ok(payment)
+    }
+
+    /// Fail a pending payment, refunding a send
+    ///
+    /// # Errors
+    /// `Transaction` if there is no pending payment with that id.
+    pub fn fail(&self, id: &str) -> Result<Payment, WalletError> {
+        let (payment, balance) = {
+            let mut ledger = self.lock();
+            let payment = pending(&mut ledger, id)?;
+            payment.state = PaymentState::Failed;
+            let payment = payment.clone();
+            if payment.payment_type == PaymentType::Send {
+                ledger.confirmed_sat += payment.amount_sat + payment.fee_sat.unwrap_or(0);
+            }
+            (payment, ledger.balance())
+        };
+        self.publish(SdkEvent::PaymentFailed { payment: payment.clone() }, &balance)?;
+        Ok(payment)
+    }
+
+    /// Move the ledger clock forward (expires quotes, stamps payments)
+    pub fn advance_clock(&self, secs: u64) {
+        self.lock().clock += secs;
+    }
+
+    /// Make the next send fail with `reason`
+    pub fn fail_next_send(&self, reason: impl Into<String>) {
+        self.lock().failures.push_back(reason.into());
+    }
+
+    /// Make the next send go out but report a timeout
+    pub fn time_out_next_send(&self) {
+        self.lock().timeouts += 1;
+    }
+
+    /// Push an arbitrary event through the reactor (deposits, etc.)
+    pub fn emit(&self, event: SdkEvent) {
+        self.reactor.ingest(event);
+    }
+
+    // =========================================================================
+    // Ledger Operations (shared by Namespace and WalletBackend)
+    // =========================================================================
+
+    fn lock(&self) -> MutexGuard<'_, MockLedger> {
+        // A test that panicked mid-script shouldn't wedge the rest
+        self.ledger.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn require_connected(&self) -> Result<(), WalletError> {
+        if self.connected.load(Ordering::SeqCst) {
+            Ok(())
+        } else {
+            Err(WalletError::NotConnected)
+        }
+    }
+
+    /// Debit the ledger and record an outgoing payment
+    ///
+    /// `fee_sat` is the ledger's current fee unless paying a quote. A send
+    /// under an `idempotency_key` already used returns that payment.
+    fn pay(
+        &self,
+        destination: &str,
+        amount_sat: u64,
+        fee_sat: Option<u64>,
+        idempotency_key: Option<&str>,
+    ) -> Result<Payment, WalletError> {
+        self.require_connected()?;
+
+        let (payment, balance, timed_out) = {
+            let mut ledger = self.lock();
+            let earlier = idempotency_key
+                .and_then(|key| ledger.idempotency.get(key))
+                .and_then(|id| ledger.payment(id));
+            if let Some(payment) = earlier {
+                return Ok(payment.clone());
+            }
+            if let Some(reason) = ledger.failures.pop_front() {
+                return Err(WalletError::Transaction(reason));
+            }
+            let fee_sat = fee_sat.unwrap_or(ledger.fee_sat);
+            let total = amount_sat.checked_add(fee_sat).ok_or_else(|| {
+                WalletError::Parse(format!("amount {} plus fee {} overflows", amount_sat, fee_sat))
+            })?;
+            if total > ledger.confirmed_sat {
+                return Err(WalletError::InsufficientFunds);
+            }
+
+            ledger.confirmed_sat -= total;
+            let state = match ledger.settlement {
+                Settlement::Instant => PaymentState::Complete,
+                Settlement::Manual => PaymentState::Pending,
+            };
+            let payment = ledger.record(
+                PaymentType::Send,
+                state,
+                amount_sat,
+                Some(fee_sat),
+                None,
+                destination_details(destination),
+            );
+            if let Some(key) = idempotency_key {
+                ledger.idempotency.insert(key.to_string(), payment.id.clone());
+            }
+            let timed_out = ledger.timeouts > 0;
+            ledger.timeouts = ledger.timeouts.saturating_sub(1);
+            (payment, ledger.balance(), timed_out)
+        };
+
+        let event = match payment.state {
+            PaymentState::Complete => SdkEvent::PaymentSucceeded { payment: payment.clone() },
+            _ => SdkEvent::PaymentPending { payment: payment.clone() },
+        };
+        self.publish(event, &balance)?;
+        if timed_out {
+            return Err(WalletError::Transaction("send timed out".into()));
+        }
+        Ok(payment)
+    }
+
+    /// Where send intents are kept, as in `WalletManager`
+    fn intents(&self) -> IntentLog<'_> {
+        let mut log = IntentLog::new(&self.reactor);
+        #[cfg(feature = "crypto")]
+        log.set_persistence(self.persistence.as_deref());
+        log
+    }
+
+    /// Pay under an intent, persisting it first
+    fn pay_intent(&self, mut send: SendIntent, fee_sat: Option<u64>) -> Result<SendIntent, WalletError> {
+        let intents = self.intents();
+        intents.save(&send).map_err(|e| WalletError::Internal(e.to_string()))?;
+
+        // A BOLT11 destination may carry the amount instead of the intent
+        let amount = send.amount_sat.unwrap_or_else(|| match ParsedInput::parse(send.pays()) {
+            Ok(ParsedInput::Bolt11(invoice)) => invoice.amount_sat.unwrap_or(0),
+            _ => 0,
+        });
+        let payment = self.pay(send.pays(), amount, fee_sat, Some(&send.idempotency_key))?;
+
+        send.resolve(&payment_info(&payment));
+        intents.save(&send).map_err(|e| WalletError::Internal(e.to_string()))?;
+        Ok(send)
+    }
+
+    /// A fresh intent id and the ledger time
+    fn next_intent(&self) -> (String, u64) {
+        let mut ledger = self.lock();
+        let id = format!("intent-{:04}", ledger.next_intent);
+        ledger.next_intent += 1;
+        (id, ledger.clock)
+    }
+
+    /// Answer a repeated send from its intent, paying only if still pending
+    ///
+    /// A pending confirm pays the fee of the quote it was prepared under,
+    /// and is refused once that quote is gone.
+    fn retry_intent(
+        &self,
+        send: SendIntent,
+        destination: &str,
+        amount_sat: Option<u64>,
+        quote_id: Option<&str>,
+    ) -> nine_s::Result<Scroll> {
+        send.check_retry(destination, amount_sat)?;
+
+        match send.status {
+            IntentStatus::Resolved => resolved_scroll(&send),
+            IntentStatus::Failed => Err(nine_s::Error::Conflict(format!(
+                "send {} failed; start a new one",
+                send.intent_id
+            ))),
+            IntentStatus::Pending => match quote_id {
+                Some(quote_id) => {
+                    let fee_sat = self.held_quote(quote_id)?.fee_sat;
+                    let paid = self.pay_intent(send, Some(fee_sat)).map_err(to_nine_s)?;
+                    self.lock().quotes.remove(quote_id);
+                    resolved_scroll(&paid)
+                }
+                None => resolved_scroll(&self.pay_intent(send, None).map_err(to_nine_s)?),
+            },
+        }
+    }
+
+    /// The prepared send behind `quote_id`, if it can still be paid
+    fn held_quote(&self, quote_id: &str) -> nine_s::Result<SendQuote> {
+        let mut ledger = self.lock();
+        let now = ledger.clock;
+        match ledger.quotes.get(quote_id) {
+            Some(quote) if !quote.is_expired(now) => Ok(quote.clone()),
+            _ => {
+                ledger.quotes.remove(quote_id);
+                Err(nine_s::Error::Conflict(format!(
+                    "quote {} consumed, prepare again",
+                    quote_id
+                )))
+            }
+        }
+    }
+
+    /// Settle pending intents against the ledger, as `connect` does
+    ///
+    /// The ledger is the network, so its idempotency keys are a definite
+    /// lookup: an intent whose key it never saw was never sent.
+    fn reconcile_intents(&self) -> nine_s::Result<usize> {
+        let intents = self.intents();
+        let mut pending: Vec<SendIntent> = intents
+            .all()?
+            .into_iter()
+            .filter(|i| i.status == IntentStatus::Pending)
+            .collect();
+
+        {
+            let ledger = self.lock();
+            for send in &mut pending {
+                let payment = ledger.idempotency
+                    .get(&send.idempotency_key)
+                    .and_then(|id| ledger.payment(id));
+                match payment {
+                    Some(payment) => send.resolve(&payment_info(payment)),
+                    None => send.status = IntentStatus::Failed,
+                }
+            }
+        }
+        for settled in &pending {
+            intents.save(settled)?;
+        }
+
+        Ok(pending.len())
+    }
+
+    /// Issue a deterministic invoice that `pay_invoice` can settle
+    fn issue_invoice(&self, amount_sat: u64, description: Option<String>) -> Result<String, WalletError> {
+        self.require_connected()?;
+
+        let mut ledger = self.lock();
+        let invoice = format!("lnbcrt{}n1mock{:04}", amount_sat, ledger.next_invoice);
+        ledger.next_invoice += 1;
+        ledger.invoices.insert(invoice.clone(), OpenInvoice { amount_sat, description });
+        Ok(invoice)
+    }
+
+    /// Deterministic stand-in for a signature over `message`
+    fn signature(pubkey: &str, message: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(pubkey.as_bytes());
+        hasher.update(b":");
+        hasher.update(message.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Ingest an event, then write the payment and new balance through
+    fn publish(&self, event: SdkEvent, balance: &WalletBalance) -> Result<(), WalletError> {
+        let tx_key = match &event {
+            SdkEvent::PaymentSucceeded { payment }
+            | SdkEvent::PaymentPending { payment }
+            | SdkEvent::PaymentFailed { payment } => Some(format!("/wallet/tx/{}", payment.id)),
+            _ => None,
+        };
+        self.reactor.ingest(event);
+
+        #[cfg(feature = "crypto")]
+        if let Some(persistence) = &self.persistence {
+            if let Some(scroll) = tx_key.and_then(|key| self.reactor.get_cached(&key)) {
+                persistence
+                    .store()
+                    .write_scroll(scroll)
+                    .map_err(|e| WalletError::Internal(e.to_string()))?;
+            }
+            return persistence
+                .persist_balance(balance.confirmed, balance.trusted_pending)
+                .map_err(|e| WalletError::Internal(e.to_string()));
+        }
+
+        #[cfg(not(feature = "crypto"))]
+        let _ = tx_key;
+        self.reactor.emit_balance(balance.confirmed, balance.trusted_pending);
+        Ok(())
+    }
+
+    /// Convert network to string for scroll responses
+    fn network_str(&self) -> &'static str {
+        match self.network {
+            SparkNetwork::Mainnet => "bitcoin",
+            SparkNetwork::Testnet => "testnet",
+            SparkNetwork::Regtest => "regtest",
+        }
+    }
+}
+
+/// The pending payment `id`, or a `Transaction` error
+fn pending<'a>(ledger: &'a mut MockLedger, id: &str) -> Result<&'a mut Payment, WalletError> {
+    ledger
+        .payment_mut(id)
+        .filter(|p| p.state == PaymentState::Pending)
+        .ok_or_else(|| WalletError::Transaction(format!("no pending payment {}", id)))
+}
+
+/// Payment details for a send, by the shape of the destination
+fn destination_details(destination: &str) -> PaymentDetails {
+    match detect_input_type(destination) {
+        InputTypeHint::Bolt11 => PaymentDetails::Lightning {
+            swap_id: None,
+            bolt11: Some(destination.to_string()),
+            preimage: Some(hex::encode(Sha256::digest(destination.as_bytes()))),
+        },
+        InputTypeHint::BitcoinAddress => PaymentDetails::Bitcoin {
+            txid: None,
+            confirmations: None,
+        },
+        _ => PaymentDetails::Spark {
+            transfer_id: None,
+            spark_address: Some(destination.to_string()),
+        },
+    }
+}
+
+/// Payment as the `/tx/{id}` scroll
+fn payment_scroll(payment: &Payment) -> Scroll {
+    Scroll::typed(
+        format!("/wallet/tx/{}", payment.id),
+        json!({
+            "id": payment.id,
+            "txid": payment.id,
+            "type": if matches!(payment.payment_type, PaymentType::Receive) { "receive" } else { "send" },
+            "amount_sat": payment.amount_sat,
+            "fee_sat": payment.fee_sat,
+            "timestamp": payment.timestamp,
+            "state": format!("{:?}", payment.state),
+        }),
+        "wallet/payment@v1",
+    )
+}
+
+/// The payment scroll of an intent `pay_intent` resolved
+fn resolved_scroll(send: &SendIntent) -> nine_s::Result<Scroll> {
+    send.payment_scroll()
+        .ok_or_else(|| nine_s::Error::Internal("intent not resolved".into()))
+}
+
+/// Payment as `SparkSdkWrapper::list_payments` would report it
+fn payment_info(payment: &Payment) -> PaymentInfo {
+    PaymentInfo {
+        id: payment.id.clone(),
+        payment_type: payment.payment_type,
+        status: payment.state,
+        amount_sat: payment.amount_sat,
+        fee_sat: payment.fee_sat,
+        timestamp: payment.timestamp,
+        description: payment.description.clone(),
+        invoice: match &payment.details {
+            PaymentDetails::Lightning { bolt11, .. } => bolt11.clone(),
+            _ => None,
+        },
+    }
+}
+
+fn payment_to_details(payment: &Payment) -> TransactionDetails {
+    let receive = matches!(payment.payment_type, PaymentType::Receive);
+    let confirmed = payment.state == PaymentState::Complete;
+    TransactionDetails {
+        txid: payment.id.clone(),
+        received: if receive { payment.amount_sat } else { 0 },
+        sent: if receive { 0 } else { payment.amount_sat },
+        fee: payment.fee_sat,
+        confirmation_time: payment.timestamp.filter(|_| confirmed),
+        is_confirmed: confirmed,
+        vsize: None,
+        timestamp: payment.timestamp,
+    }
+}
+
+/// Map a wallet error onto the 9S error the SDK path would produce
+fn to_nine_s(e: WalletError) -> nine_s::Error {
+    match e {
+        WalletError::NotConnected => nine_s::Error::Unavailable("Wallet not connected".into()),
+        WalletError::InvalidAddress(d) | WalletError::Parse(d) => nine_s::Error::InvalidData(d),
+        _ => nine_s::Error::Internal(e.to_string()),
+    }
+}
+
+/// A send error naming the intent to retry under, as `WalletManager` reports it
+fn send_failed(intent_id: &str, e: WalletError) -> nine_s::Error {
+    match to_nine_s(e) {
+        nine_s::Error::Internal(m) => nine_s::Error::Internal(format!("send {} failed: {}", intent_id, m)),
+        other => other,
+    }
+}
+
+// =============================================================================
+// Namespace Implementation: same paths as WalletManager
+// =============================================================================
+
+impl Namespace for MockWalletBackend {
+    fn read(&self, path: &str) -> nine_s::Result<Option<Scroll>> {
+        if path == "/status" || path.is_empty() || path == "/" {
+            return Ok(Some(Scroll::typed(
+                "/wallet/status",
+                json!({
+                    "connected": self.is_connected(),
+                    "network": self.network_str(),
+                    "backend": self.backend_name(),
+                    "version": self.backend_version(),
+                }),
+                "wallet/status@v1",
+            )));
+        }
+
+        self.require_connected().map_err(to_nine_s)?;
+        let ledger = self.lock();
+
+        match path {
+            "/balance" => {
+                let balance = ledger.balance();
+                Ok(Some(Scroll::typed(
+                    "/wallet/balance",
+                    json!({
+                        "confirmed": balance.confirmed,
+                        "trusted_pending": balance.trusted_pending,
+                        "untrusted_pending": 0,
+                        "immature": 0,
+                    }),
+                    "wallet/balance@v1",
+                )))
+            }
+            "/address" => Ok(Some(Scroll::typed(
+                "/wallet/address",
+                json!({"address": ledger.address}),
+                "wallet/address@v1",
+            ))),
+            "/bitcoin-address" | "/deposit-address" => Ok(Some(Scroll::typed(
+                "/wallet/bitcoin-address",
+                json!({"address": ledger.bitcoin_address, "fee_sat": 0}),
+                "wallet/bitcoin-address@v1",
+            ))),
+            "/pubkey" => Ok(Some(Scroll::typed(
+                "/wallet/pubkey",
+                json!({"pubkey": ledger.pubkey}),
+                "wallet/pubkey@v1",
+            ))),
+            "/network" => Ok(Some(Scroll::typed(
+                "/wallet/network",
+                json!({"network": self.network_str()}),
+                "wallet/network@v1",
+            ))),
+            p if p == "/transactions" || p.starts_with("/transactions?") => {
+                let limit = p.split("limit=")
+                    .nth(1)
+                    .and_then(|s| s.split('&').next())
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .unwrap_or(usize::MAX);
+
+                let txs: Vec<Value> = ledger.payments.iter().rev().take(limit).map(|p| json!({
+                    "txid": p.id,
+                    "received": if matches!(p.payment_type, PaymentType::Receive) { p.amount_sat } else { 0 },
+                    "sent": if matches!(p.payment_type, PaymentType::Send) { p.amount_sat } else { 0 },
+                    "fee": p.fee_sat,
+                    "timestamp": p.timestamp,
+                    "is_confirmed": matches!(p.state, PaymentState::Complete),
+                })).collect();
+
+                Ok(Some(Scroll::typed(
+                    "/wallet/transactions",
+                    json!({"transactions": txs}),
+                    "wallet/transactions@v1",
+                )))
+            }
+            p if p.starts_with("/tx/") => {
+                let txid = &p[4..];
+                ledger
+                    .payment(txid)
+                    .map(|payment| Some(payment_scroll(payment)))
+                    .ok_or_else(|| nine_s::Error::NotFound(format!("Transaction {} not found", txid)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn write(&self, path: &str, data: Value) -> nine_s::Result<Scroll> {
+        if path == "/parse" {
+            return super::parse::parse_request(&data);
+        }
+
+        self.require_connected().map_err(to_nine_s)?;
+
+        match path {
+            "/send" => {
+                let destination = data["to"].as_str()
+                    .or_else(|| data["destination"].as_str())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                let amount = data["amount"].as_u64()
+                    .or_else(|| data["amount_sat"].as_u64())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'amount' field".into()))?;
+
+                let intent_id = data["intent_id"].as_str();
+                if let Some(existing) = intent_id.map(|id| self.intents().get(id)).transpose()?.flatten() {
+                    return self.retry_intent(existing, destination, Some(amount), None);
+                }
+
+                let (generated, now) = self.next_intent();
+                let intent_id = intent_id.map(String::from).unwrap_or(generated);
+                let send = SendIntent::new(intent_id.clone(), destination, Some(amount), now);
+                resolved_scroll(&self.pay_intent(send, None).map_err(|e| send_failed(&intent_id, e))?)
+            }
+            "/send/intent" => {
+                let destination = data["to"].as_str()
+                    .or_else(|| data["destination"].as_str())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                let amount = data["amount"].as_u64()
+                    .or_else(|| data["amount_sat"].as_u64());
+
+                let (intent_id, now) = self.next_intent();
+                let send = SendIntent::new(intent_id, destination, amount, now);
+                self.intents().save(&send)?;
+                send.to_scroll()
+            }
+            "/send/prepare" => {
+                let destination = data["to"].as_str()
+                    .or_else(|| data["destination"].as_str())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                let amount = data["amount"].as_u64()
+                    .or_else(|| data["amount_sat"].as_u64())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'amount' field".into()))?;
+
+                let quote = {
+                    let mut ledger = self.lock();
+                    let quote = SendQuote::new(
+                        format!("quote-{:04}", ledger.next_quote),
+                        destination,
+                        quote::destination_type(destination),
+                        amount,
+                        ledger.fee_sat,
+                        ledger.clock,
+                    )?;
+                    ledger.next_quote += 1;
+                    ledger.quotes.insert(quote.quote_id.clone(), quote.clone());
+                    quote
+                };
+
+                let scroll = quote.to_scroll()?;
+                self.reactor.emit(scroll.clone());
+                Ok(scroll)
+            }
+            "/send/confirm" => {
+                if let Some(quote_id) = data["quote_id"].as_str() {
+                    if let Some(existing) = self.intents().get(quote_id)? {
+                        let destination = data["to"].as_str()
+                            .or_else(|| data["destination"].as_str())
+                            .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                        let amount = data["amount"].as_u64()
+                            .or_else(|| data["amount_sat"].as_u64());
+                        let mut scroll = self.retry_intent(existing, destination, amount, Some(quote_id))?;
+                        scroll.data["quote_id"] = json!(quote_id);
+                        return Ok(scroll);
+                    }
+                }
+
+                let quote = quote::take(&self.reactor, &data)?;
+                let now = self.lock().clock;
+                if let Err(e) = quote.check(&data, now) {
+                    self.lock().quotes.remove(&quote.quote_id);
+                    return Err(e);
+                }
+
+                let send = SendIntent::new(
+                    quote.quote_id.clone(),
+                    quote.destination.clone(),
+                    Some(quote.amount_sat),
+                    now,
+                );
+                let paid = self.pay_intent(send, Some(quote.fee_sat)).map_err(to_nine_s)?;
+                self.lock().quotes.remove(&quote.quote_id);
+                let mut scroll = resolved_scroll(&paid)?;
+                scroll.data["quote_id"] = json!(quote.quote_id);
+                Ok(scroll)
+            }
+            "/invoice" | "/receive" => {
+                let amount = data["amount"].as_u64()
+                    .or_else(|| data["amountSat"].as_u64())
+                    .or_else(|| data["amount_sat"].as_u64())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'amount' field".into()))?;
+                let description = data["description"].as_str().map(|s| s.to_string());
+
+                let invoice = self.issue_invoice(amount, description).map_err(to_nine_s)?;
+                Ok(Scroll::typed(
+                    "/wallet/invoice",
+                    json!({
+                        "invoice": invoice,
+                        "destination": invoice,
+                        "fee_sat": 0,
+                    }),
+                    "wallet/invoice@v1",
+                ))
+            }
+            "/lnurl/resolve" => lnurl::resolve(self.http.as_ref(), lnurl::target(&data)?)?.to_scroll(),
+            "/lnurl/pay" => {
+                let target = lnurl::target(&data)?;
+                let amount = lnurl::amount_sat(&data)?;
+
+                let intent_id = data["intent_id"].as_str();
+                let reserved = match intent_id.map(|id| self.intents().get(id)).transpose()?.flatten() {
+                    Some(existing) if existing.invoice.is_none() && existing.status == IntentStatus::Pending => {
+                        existing.check_retry(target, Some(amount))?;
+                        Some(existing)
+                    }
+                    Some(existing) => return self.retry_intent(existing, target, Some(amount), None),
+                    None => None,
+                };
+
+                let LnurlParams::Pay(params) = lnurl::resolve(self.http.as_ref(), target)? else {
+                    return Err(nine_s::Error::InvalidData("not an LNURL-Pay".into()));
+                };
+                let paid = lnurl::request_invoice(self.http.as_ref(), &params, amount, data["comment"].as_str())?;
+
+                let mut send = reserved.unwrap_or_else(|| {
+                    let (generated, now) = self.next_intent();
+                    let intent_id = intent_id.map(String::from).unwrap_or(generated);
+                    SendIntent::new(intent_id, target, Some(amount), now)
+                });
+                send.invoice = Some(paid.invoice.invoice.clone());
+                let intent_id = send.intent_id.clone();
+                let payment = resolved_scroll(&self.pay_intent(send, None).map_err(|e| send_failed(&intent_id, e))?)?;
+                Ok(lnurl::paid_scroll(payment, &params, &paid))
+            }
+            "/lnurl/withdraw" => {
+                let LnurlParams::Withdraw(params) = lnurl::resolve(self.http.as_ref(), lnurl::target(&data)?)? else {
+                    return Err(nine_s::Error::InvalidData("not an LNURL-Withdraw".into()));
+                };
+                let amount = lnurl::amount_sat(&data)?;
+                lnurl::check_withdraw(&params, amount)?;
+
+                let description = data["description"].as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| params.default_description.clone());
+                let invoice = self.issue_invoice(amount, Some(description)).map_err(to_nine_s)?;
+                lnurl::submit_withdraw(self.http.as_ref(), &params, &invoice)?;
+                Ok(lnurl::withdraw_scroll(&params, &invoice, amount))
+            }
+            "/sync" => {
+                self.reactor.ingest(SdkEvent::Synced);
+                Ok(Scroll::typed(
+                    "/wallet/sync",
+                    json!({"synced": true}),
+                    "wallet/sync@v1",
+                ))
+            }
+            "/sign" => {
+                let message = data["message"].as_str()
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'message' field".into()))?;
+                let pubkey = self.lock().pubkey.clone();
+
+                Ok(Scroll::typed(
+                    "/wallet/sign",
+                    json!({
+                        "address": pubkey,
+                        "message": message,
+                        "signature": Self::signature(&pubkey, message),
+                    }),
+                    "wallet/signed-message@v1",
+                ))
+            }
+            "/verify" => {
+                let message = data["message"].as_str()
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'message' field".into()))?;
+                let signature = data["signature"].as_str()
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'signature' field".into()))?;
+                let pubkey = data["pubkey"].as_str()
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'pubkey' field".into()))?;
+
+                Ok(Scroll::typed(
+                    "/wallet/verify",
+                    json!({"valid": Self::signature(pubkey, message) == signature}),
+                    "wallet/verification@v1",
+                ))
+            }
+            "/fee-estimate" => Ok(Scroll::typed(
+                "/wallet/fee-estimate",
+                json!({"fee": self.lock().fee_sat}),
+                "wallet/fee-estimate@v1",
+            )),
+            _ => Err(nine_s::Error::NotFound(path.into())),
+        }
+    }
+
+    fn list(&self, prefix: &str) -> nine_s::Result<Vec<String>> {
+        match prefix {
+            "/" | "" => Ok(vec![
+                "/status".to_string(),
+                "/balance".to_string(),
+                "/address".to_string(),
+                "/bitcoin-address".to_string(),
+                "/pubkey".to_string(),
+                "/network".to_string(),
+                "/transactions".to_string(),
+            ]),
+            "/tx" => Ok(self
+                .lock()
+                .payments
+                .iter()
+                .map(|p| format!("/tx/{}", p.id))
+                .collect()),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Watch reactor events, `/wallet/...` or mount-relative like `WalletManager`
+    fn watch(&self, pattern: &str) -> nine_s::Result<nine_s::Receiver<Scroll>> {
+        if super::is_wallet_root_pattern(pattern) {
+            Ok(self.reactor.watch(pattern))
+        } else {
+            Ok(self.reactor.watch_relative(pattern))
+        }
+    }
+
+    fn close(&self) -> nine_s::Result<()> {
+        self.connected.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+// =============================================================================
+// WalletBackend Implementation
+// =============================================================================
+
+impl WalletBackend for MockWalletBackend {
+    /// Accepts any valid BIP39 mnemonic; the ledger is the same either way
+    fn connect(&self, mnemonic: &str, _passphrase: Option<&str>) -> Result<(), WalletError> {
+        bip39::Mnemonic::parse(mnemonic).map_err(|e| WalletError::InvalidMnemonic(e.to_string()))?;
+
+        #[cfg(feature = "crypto")]
+        if let Some(persistence) = &self.persistence {
+            persistence.load_into_cache().map_err(|e| WalletError::Internal(e.to_string()))?;
+        }
+
+        self.connected.store(true, Ordering::SeqCst);
+        self.reconcile_intents().map_err(|e| WalletError::Internal(e.to_string()))?;
+        self.reactor.ingest(SdkEvent::Synced);
+        Ok(())
+    }
+
+    fn disconnect(&self) -> Result<(), WalletError> {
+        self.connected.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    fn balance(&self) -> Result<WalletBalance, WalletError> {
+        self.require_connected()?;
+        Ok(self.lock().balance())
+    }
+
+    fn sync(&self) -> Result<(), WalletError> {
+        self.require_connected()?;
+        self.reactor.ingest(SdkEvent::Synced);
+        Ok(())
+    }
+
+    fn new_address(&self) -> Result<String, WalletError> {
+        self.require_connected()?;
+        Ok(self.lock().address.clone())
+    }
+
+    fn send(
+        &self,
+        destination: &str,
+        amount_sats: u64,
+        _fee_rate: Option<f64>,
+    ) -> Result<String, WalletError> {
+        self.require_connected()?;
+        let (intent_id, now) = self.next_intent();
+        let send = SendIntent::new(intent_id, destination, Some(amount_sats), now);
+        self.pay_intent(send, None)?
+            .payment_id
+            .ok_or_else(|| WalletError::Internal("intent not resolved".into()))
+    }
+
+    fn estimate_fee(&self, _destination: &str, _amount_sats: u64) -> Result<u64, WalletError> {
+        self.require_connected()?;
+        Ok(self.lock().fee_sat)
+    }
+
+    fn transactions(&self, limit: usize) -> Result<Vec<TransactionDetails>, WalletError> {
+        self.require_connected()?;
+        Ok(self.lock().payments.iter().rev().take(limit).map(payment_to_details).collect())
+    }
+
+    fn create_invoice(
+        &self,
+        amount_sats: u64,
+        description: Option<&str>,
+    ) -> Result<String, WalletError> {
+        self.issue_invoice(amount_sats, description.map(str::to_string))
+    }
+
+    fn pubkey(&self) -> Result<String, WalletError> {
+        self.require_connected()?;
+        Ok(self.lock().pubkey.clone())
+    }
+
+    fn sign_message(&self, message: &str) -> Result<SignedMessage, WalletError> {
+        self.require_connected()?;
+        let pubkey = self.lock().pubkey.clone();
+        Ok(SignedMessage {
+            signature: Self::signature(&pubkey, message),
+            address: pubkey,
+            message: message.to_string(),
+        })
+    }
+
+    fn verify_message(
+        &self,
+        message: &str,
+        signature: &str,
+        pubkey: &str,
+    ) -> Result<bool, WalletError> {
+        Ok(Self::signature(pubkey, message) == signature)
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn backend_version(&self) -> &'static str {
+        "0.1.0-mock"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn connected(ledger: MockLedger) -> MockWalletBackend {
+        let wallet = MockWalletBackend::new(SparkNetwork::Regtest, ledger);
+        wallet.connect(MNEMONIC, None).unwrap();
+        wallet
+    }
+
+    #[test]
+    fn send_debits_ledger_and_emits_through_reactor() {
+        let wallet = connected(MockLedger::new().with_balance(10_000).with_fee(10));
+        let mut tx_rx = wallet.watch("/wallet/tx/**").unwrap();
+        let mut balance_rx = wallet.watch("/balance").unwrap();
+
+        let sent = wallet.write("/send", json!({"to": "sp1qfriend", "amount": 1_000})).unwrap();
+        assert_eq!(sent.key, "/wallet/tx/mock-0001");
+        assert_eq!(sent.data["fee_sat"], 10);
+
+        let event = tx_rx.try_recv().expect("Should receive payment scroll");
+        assert_eq!(event.data["status"], "succeeded");
+        assert_eq!(event.data["type"], "send");
+
+        // Hint from the reactor, then the fresh balance
+        assert_eq!(balance_rx.try_recv().unwrap().type_, "wallet/balance-hint@v1");
+        let balance = balance_rx.try_recv().unwrap();
+        assert_eq!(balance.key, "/balance");
+        assert_eq!(balance.data["confirmed"], 8_990);
+
+        let txs = wallet.read("/transactions").unwrap().unwrap();
+        assert_eq!(txs.data["transactions"][0]["sent"], 1_000);
+        assert_eq!(wallet.list("/tx").unwrap(), vec!["/tx/mock-0001"]);
+        assert_eq!(wallet.read("/tx/mock-0001").unwrap().unwrap().data["state"], "Complete");
+    }
+
+    #[test]
+    fn manual_settlement_and_failure_refund() {
+        let wallet = connected(MockLedger::new().with_balance(5_000).with_fee(5).settle_manually());
+        let mut rx = wallet.watch("/wallet/tx/**").unwrap();
+
+        let kept = wallet.send("sp1qa", 1_000, None).unwrap();
+        let dropped = wallet.send("sp1qb", 2_000, None).unwrap();
+        assert_eq!(rx.try_recv().unwrap().data["status"], "pending");
+        assert_eq!(rx.try_recv().unwrap().data["status"], "pending");
+        assert_eq!(wallet.balance().unwrap().confirmed, 1_990);
+
+        wallet.settle(&kept).unwrap();
+        wallet.fail(&dropped).unwrap();
+        assert_eq!(rx.try_recv().unwrap().data["status"], "succeeded");
+        assert_eq!(rx.try_recv().unwrap().data["status"], "failed");
+        assert_eq!(wallet.balance().unwrap().confirmed, 3_995);
+
+        // Only pending payments can be settled
+        assert!(matches!(wallet.settle(&kept), Err(WalletError::Transaction(_))));
+    }
+
+    #[test]
+    fn scripted_failures_and_insufficient_funds() {
+        let wallet = connected(MockLedger::new().with_balance(1_000).fail_next_send("no route"));
+
+        let err = wallet.write("/send", json!({"to": "lnbcrt1x", "amount": 10})).unwrap_err();
+        assert!(matches!(err, nine_s::Error::Internal(ref m) if m.contains("no route")));
+        assert!(matches!(wallet.send("sp1qa", 5_000, None), Err(WalletError::InsufficientFunds)));
+        assert_eq!(wallet.balance().unwrap().confirmed, 1_000);
+        assert!(wallet.ledger().payments().is_empty());
+
+        // An amount the fee would overflow is refused, not wrapped
+        let fee_paying = connected(MockLedger::new().with_fee(1));
+        let huge = fee_paying.write("/send", json!({"to": "sp1qa", "amount": u64::MAX}));
+        assert!(matches!(huge, Err(nine_s::Error::InvalidData(_))));
+
+        wallet.disconnect().unwrap();
+        assert!(matches!(wallet.read("/balance"), Err(nine_s::Error::Unavailable(_))));
+        assert!(matches!(wallet.connect("not a mnemonic", None), Err(WalletError::InvalidMnemonic(_))));
+    }
+
+    #[test]
+    fn invoices_and_pending_receives_through_a_kernel_mount() {
+        use crate::nine_s::Kernel;
+
+        let wallet = Arc::new(connected(MockLedger::new()));
+        let kernel = Kernel::new();
+        kernel.mount("/wallet", wallet.clone());
+        let mut rx = kernel.watch("/wallet/tx/**").unwrap();
+
+        let invoice = kernel.write("/wallet/invoice", json!({"amount": 2_100})).unwrap();
+        let invoice = invoice.data["invoice"].as_str().unwrap().to_string();
+        let paid = wallet.pay_invoice(&invoice).unwrap();
+        assert!(wallet.pay_invoice(&invoice).is_err());
+
+        let scroll = rx.recv().expect("Should receive payment scroll");
+        assert_eq!(scroll.key, format!("/wallet/tx/{}", paid.id));
+        assert_eq!(scroll.data["type"], "receive");
+
+        let incoming = wallet.receive_pending(500).unwrap();
+        let balance = kernel.read("/wallet/balance").unwrap().unwrap();
+        assert_eq!(balance.data["confirmed"], 2_100);
+        assert_eq!(balance.data["trusted_pending"], 500);
+
+        wallet.settle(&incoming.id).unwrap();
+        assert_eq!(wallet.balance().unwrap().total(), 2_600);
+    }
+
+    #[test]
+    fn two_phase_send_pays_the_quote_once() {
+        let wallet = connected(MockLedger::new().with_balance(10_000).with_fee(7));
+
+        let quote = wallet.write("/send/prepare", json!({"to": "lnbcrt1x", "amount": 1_000})).unwrap();
+        assert_eq!(quote.type_, "wallet/send-quote@v1");
+        assert_eq!(quote.data["destination_type"], "bolt11");
+        assert_eq!(quote.data["total_sat"], 1_007);
+        assert_eq!(wallet.balance().unwrap().confirmed, 10_000);
+
+        // The quoted fee holds even if the network's fee moves
+        let confirm = json!({"quote_id": "quote-0001", "to": "lnbcrt1x", "amount": 1_000});
+        let paid = wallet.write("/send/confirm", confirm.clone()).unwrap();
+        assert_eq!(paid.data["quote_id"], "quote-0001");
+        assert_eq!(paid.data["fee_sat"], 7);
+        assert_eq!(wallet.balance().unwrap().confirmed, 8_993);
+
+        // Confirming again answers from the intent instead of paying twice
+        let again = wallet.write("/send/confirm", confirm).unwrap();
+        assert_eq!(again.data["id"], paid.data["id"]);
+        assert_eq!(again.data["idempotency_key"], paid.data["idempotency_key"]);
+        assert_eq!(wallet.balance().unwrap().confirmed, 8_993);
+        let unknown = json!({"quote_id": "quote-0009", "to": "lnbcrt1x"});
+        assert!(matches!(wallet.write("/send/confirm", unknown), Err(nine_s::Error::NotFound(_))));
+
+        // Changed amount, then an expired quote: both refused, nothing paid
+        wallet.write("/send/prepare", json!({"to": "sp1qa", "amount": 500})).unwrap();
+        let changed = wallet.write("/send/confirm", json!({"quote_id": "quote-0002", "to": "sp1qa", "amount": 900}));
+        assert!(matches!(changed, Err(nine_s::Error::Conflict(_))));
+
+        wallet.write("/send/prepare", json!({"to": "sp1qa", "amount": 500})).unwrap();
+        wallet.advance_clock(quote::QUOTE_TTL_SECS);
+        let expired = wallet.write("/send/confirm", json!({"quote_id": "quote-0003", "to": "sp1qa"}));
+        assert!(matches!(expired, Err(nine_s::Error::Conflict(_))));
+        assert_eq!(wallet.ledger().payments().len(), 1);
+    }
+
+    #[test]
+    fn scoped_spending_limit_covers_confirmed_sends() {
+        use crate::nine_s::capability::{Ops, Policy};
+        use crate::nine_s::Kernel;
+        use crate::wallet_spark::SPEND_PATHS;
+
+        let wallet = Arc::new(connected(MockLedger::new().with_balance(10_000).with_fee(7)));
+        let kernel = Arc::new(Kernel::new());
+        kernel.mount("/wallet", wallet.clone());
+        let scope = kernel.scoped(
+            Policy::new()
+                .allow("/wallet/send/**", Ops::WRITE)
+                .limit_daily_across(&SPEND_PATHS, &["amount", "amount_sat"], 2_500),
+        );
+
+        scope.write("/wallet/send", json!({"to": "sp1qa", "amount": 1_000})).unwrap();
+        scope.write("/wallet/send/prepare", json!({"to": "sp1qb", "amount": 1_000})).unwrap();
+        let confirm = json!({"quote_id": "quote-0001", "to": "sp1qb", "amount": 1_000});
+        scope.write("/wallet/send/confirm", confirm).unwrap();
+
+        // The second quote would take the day's total past the cap
+        scope.write("/wallet/send/prepare", json!({"to": "sp1qc", "amount": 1_000})).unwrap();
+        let confirm = json!({"quote_id": "quote-0002", "to": "sp1qc", "amount": 1_000});
+        assert!(matches!(scope.write("/wallet/send/confirm", confirm), Err(nine_s::Error::Permission(_))));
+        // Without an amount the limit can't price the confirm
+        let unpriced = json!({"quote_id": "quote-0002", "to": "sp1qc"});
+        assert!(matches!(scope.write("/wallet/send/confirm", unpriced), Err(nine_s::Error::Permission(_))));
+        assert_eq!(wallet.balance().unwrap().confirmed, 7_986);
+    }
+
+    #[test]
+    fn failed_confirm_retries_the_held_quote_until_it_expires() {
+        let wallet = connected(MockLedger::new().with_balance(10_000).with_fee(7));
+        wallet.write("/send/prepare", json!({"to": "sp1qa", "amount": 1_000})).unwrap();
+        wallet.write("/send/prepare", json!({"to": "sp1qb", "amount": 1_000})).unwrap();
+
+        // The first confirm fails in flight; the retry pays the quoted fee
+        wallet.fail_next_send("route lost");
+        let confirm = json!({"quote_id": "quote-0001", "to": "sp1qa"});
+        assert!(wallet.write("/send/confirm", confirm.clone()).is_err());
+        let paid = wallet.write("/send/confirm", confirm).unwrap();
+        assert_eq!(paid.data["fee_sat"], 7);
+        assert_eq!(wallet.balance().unwrap().confirmed, 8_993);
+
+        // Past its expiry the quote is gone and the retry is refused
+        wallet.fail_next_send("route lost");
+        let confirm = json!({"quote_id": "quote-0002", "to": "sp1qb"});
+        assert!(wallet.write("/send/confirm", confirm.clone()).is_err());
+        wallet.advance_clock(quote::QUOTE_TTL_SECS);
+        assert!(matches!(wallet.write("/send/confirm", confirm), Err(nine_s::Error::Conflict(_))));
+        assert_eq!(wallet.balance().unwrap().confirmed, 8_993);
+    }
+
+    #[test]
+    fn retry_after_a_timeout_pays_once() {
+        let wallet = connected(MockLedger::new().with_balance(10_000).with_fee(10).time_out_next_send());
+        let send = json!({"to": "sp1qfriend", "amount": 1_000, "intent_id": "buy-1"});
+
+        // The payment went out but the caller only saw an error
+        assert!(wallet.write("/send", send.clone()).is_err());
+        let pending = IntentLog::new(&wallet.reactor()).get("buy-1").unwrap().unwrap();
+        assert_eq!(pending.status, IntentStatus::Pending);
+
+        let sent = wallet.write("/send", send.clone()).unwrap();
+        assert_eq!(sent.data["id"], "mock-0001");
+        assert_eq!(sent.data["intent_id"], "buy-1");
+        assert_eq!(sent.data["idempotency_key"], pending.idempotency_key.as_str());
+        assert_eq!(wallet.write("/send", send).unwrap().data["id"], "mock-0001");
+        assert_eq!(wallet.ledger().payments().len(), 1);
+        assert_eq!(wallet.balance().unwrap().confirmed, 8_990);
+
+        // Same intent id, different send
+        let changed = wallet.write("/send", json!({"to": "sp1qfriend", "amount": 2_000, "intent_id": "buy-1"}));
+        assert!(matches!(changed, Err(nine_s::Error::Conflict(_))));
+    }
+
+    #[test]
+    fn reserved_intent_makes_a_failed_first_send_retryable() {
+        let wallet = connected(MockLedger::new().with_balance(10_000).with_fee(10));
+
+        // The id is in hand before any money moves
+        let reserved = wallet.write("/send/intent", json!({"to": "sp1qfriend", "amount": 1_000})).unwrap();
+        assert_eq!(reserved.type_, "wallet/send-intent@v1");
+        assert_eq!(reserved.data["status"], "pending");
+        let intent_id = reserved.data["intent_id"].as_str().unwrap().to_string();
+        assert!(wallet.ledger().payments().is_empty());
+
+        // The first attempt pays but errors; the retry answers, once
+        wallet.time_out_next_send();
+        let send = json!({"to": "sp1qfriend", "amount": 1_000, "intent_id": intent_id});
+        assert!(wallet.write("/send", send.clone()).is_err());
+        let sent = wallet.write("/send", send).unwrap();
+        assert_eq!(sent.data["intent_id"], intent_id.as_str());
+        assert_eq!(wallet.ledger().payments().len(), 1);
+        assert_eq!(wallet.balance().unwrap().confirmed, 8_990);
+
+        // Unreserved, the error still names the intent to retry under
+        wallet.fail_next_send("route lost");
+        let err = wallet.write("/send", json!({"to": "sp1qb", "amount": 500})).unwrap_err();
+        assert!(matches!(err, nine_s::Error::Internal(ref m) if m.contains("send intent-0002 failed")));
+        let retried = wallet.write("/send", json!({"to": "sp1qb", "amount": 500, "intent_id": "intent-0002"}));
+        assert_eq!(retried.unwrap().data["intent_id"], "intent-0002");
+        assert_eq!(wallet.ledger().payments().len(), 2);
+    }
+
+    #[test]
+    fn reconnect_settles_pending_intents() {
+        let wallet = connected(MockLedger::new().with_balance(10_000));
+
+        wallet.time_out_next_send();
+        assert!(wallet.write("/send", json!({"to": "sp1qa", "amount": 700, "intent_id": "a"})).is_err());
+        let never_sent = SendIntent::new("b", "sp1qb", Some(300), 0);
+        IntentLog::new(&wallet.reactor()).save(&never_sent).unwrap();
+
+        wallet.disconnect().unwrap();
+        wallet.connect(MNEMONIC, None).unwrap();
+
+        let reactor = wallet.reactor();
+        let log = IntentLog::new(&reactor);
+        let a = log.get("a").unwrap().unwrap();
+        assert_eq!(a.status, IntentStatus::Resolved);
+        assert_eq!(a.payment_id.as_deref(), Some("mock-0001"));
+        assert_eq!(log.get("b").unwrap().unwrap().status, IntentStatus::Failed);
+        assert_eq!(wallet.ledger().payments().len(), 1);
+
+        let retried = wallet.write("/send", json!({"to": "sp1qb", "amount": 300, "intent_id": "b"}));
+        assert!(matches!(retried, Err(nine_s::Error::Conflict(_))));
+    }
+
+    #[test]
+    fn lnurl_pay_against_a_stub_service() {
+        use crate::wallet_spark::lnurl::tests::{lnurl_invoice, StubServer, METADATA};
+        use crate::wallet_spark::parse::encode_lnurl;
+
+        let server = StubServer::start();
+        server.route("/lnurlp", json!({
+            "tag": "payRequest",
+            "callback": format!("{}/callback", server.base),
+            "minSendable": 1_000,
+            "maxSendable": 5_000_000,
+            "metadata": METADATA,
+            "commentAllowed": 20,
+        }));
+        server.route("/callback", json!({
+            "pr": lnurl_invoice(2_000_000, METADATA),
+            "successAction": {"tag": "message", "message": "thanks!"},
+        }));
+        let target = encode_lnurl(&format!("{}/lnurlp", server.base)).unwrap();
+        let wallet = connected(MockLedger::new().with_balance(10_000).with_fee(3)).with_http(server.http());
+
+        let resolved = wallet.write("/lnurl/resolve", json!({"to": target})).unwrap();
+        assert_eq!(resolved.type_, "wallet/lnurl@v1");
+        assert_eq!(resolved.data["tag"], "pay");
+
+        let pay = json!({"to": target, "amount": 2_000, "comment": "gm", "intent_id": "zap-1"});
+        let paid = wallet.write("/lnurl/pay", pay.clone()).unwrap();
+        assert_eq!(paid.data["amount_sat"], 2_000);
+        assert_eq!(paid.data["lnurl_domain"], "127.0.0.1");
+        assert_eq!(paid.data["success_action"]["message"], "thanks!");
+        assert_eq!(wallet.balance().unwrap().confirmed, 7_997);
+
+        // A retry pays the invoice on record without asking the service again
+        let asked = server.requests().len();
+        assert_eq!(wallet.write("/lnurl/pay", pay).unwrap().data["id"], paid.data["id"]);
+        assert_eq!(server.requests().len(), asked);
+        assert_eq!(wallet.ledger().payments().len(), 1);
+
+        // Out of bounds, or an invoice for less than asked: nothing paid
+        let too_much = wallet.write("/lnurl/pay", json!({"to": target, "amount": 6_000}));
+        assert!(matches!(too_much, Err(nine_s::Error::InvalidData(_))));
+        let short = wallet.write("/lnurl/pay", json!({"to": target, "amount": 3_000}));
+        assert!(matches!(short, Err(nine_s::Error::InvalidData(_))));
+        assert_eq!(wallet.ledger().payments().len(), 1);
+
+        // A reserved zap whose first attempt errors: the retry pays the
+        // invoice already fetched, and only once
+        let reserved = wallet.write("/send/intent", json!({"to": target, "amount": 2_000})).unwrap();
+        let zap = json!({"to": target, "amount": 2_000, "intent_id": reserved.data["intent_id"]});
+        wallet.time_out_next_send();
+        assert!(wallet.write("/lnurl/pay", zap.clone()).is_err());
+        let asked = server.requests().len();
+        let retried = wallet.write("/lnurl/pay", zap).unwrap();
+        assert_eq!(retried.data["intent_id"], reserved.data["intent_id"]);
+        assert_eq!(server.requests().len(), asked);
+        assert_eq!(wallet.ledger().payments().len(), 2);
+        assert_eq!(wallet.balance().unwrap().confirmed, 5_994);
+    }
+
+    #[test]
+    fn lnurl_withdraw_submits_our_invoice() {
+        use crate::wallet_spark::lnurl::tests::StubServer;
+        use crate::wallet_spark::parse::encode_lnurl;
+
+        let server = StubServer::start();
+        server.route("/withdraw", json!({
+            "tag": "withdrawRequest",
+            "callback": format!("{}/withdraw/cb", server.base),
+            "k1": "k1x",
+            "minWithdrawable": 1_000,
+            "maxWithdrawable": 10_000_000,
+            "defaultDescription": "faucet",
+        }));
+        server.route("/withdraw/cb", json!({"status": "OK"}));
+        let target = encode_lnurl(&format!("{}/withdraw", server.base)).unwrap();
+        let wallet = connected(MockLedger::new()).with_http(server.http());
+
+        let too_much = wallet.write("/lnurl/withdraw", json!({"to": target, "amount": 20_000}));
+        assert!(matches!(too_much, Err(nine_s::Error::InvalidData(_))));
+        let paying = wallet.write("/lnurl/pay", json!({"to": target, "amount": 5_000}));
+        assert!(matches!(paying, Err(nine_s::Error::InvalidData(_))));
+
+        let withdrawn = wallet.write("/lnurl/withdraw", json!({"to": target, "amount": 5_000})).unwrap();
+        assert_eq!(withdrawn.type_, "wallet/lnurl-withdraw@v1");
+        assert_eq!(withdrawn.data["status"], "submitted");
+        let invoice = withdrawn.data["invoice"].as_str().unwrap();
+        assert!(server.requests().iter().any(|r| r.starts_with("/withdraw/cb?k1=k1x&pr=lnbcrt5000n")));
+
+        // The service pays it later, like any payer would
+        wallet.pay_invoice(invoice).unwrap();
+        assert_eq!(wallet.balance().unwrap().confirmed, 5_000);
+    }
+
+    #[test]
+    fn backend_trait_signs_and_verifies() {
+        let wallet = connected(MockLedger::new());
+        let backend: &dyn WalletBackend = &wallet;
+
+        let signed = backend.sign_message("hello").unwrap();
+        assert!(backend.verify_message("hello", &signed.signature, &signed.address).unwrap());
+        assert!(!backend.verify_message("bye", &signed.signature, &signed.address).unwrap());
+        assert_eq!(backend.backend_name(), "mock");
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn payments_persist_and_reload_on_connect() {
+        use crate::nine_s::Store;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let key = Store::test_key();
+
+        {
+            let wallet = MockWalletBackend::new(SparkNetwork::Regtest, MockLedger::new())
+                .with_store(Store::at(dir.path(), &key).unwrap());
+            wallet.connect(MNEMONIC, None).unwrap();
+            wallet.receive(21_000, Some("zap")).unwrap();
+            wallet.write("/send", json!({"to": "sp1qa", "amount": 1_000, "intent_id": "x"})).unwrap();
+        }
+
+        let wallet = MockWalletBackend::new(SparkNetwork::Regtest, MockLedger::new())
+            .with_store(Store::at(dir.path(), &key).unwrap());
+        wallet.connect(MNEMONIC, None).unwrap();
+
+        let reactor = wallet.reactor();
+        assert_eq!(reactor.get_cached("/wallet/balance").unwrap().data["confirmed"], 20_000);
+        assert_eq!(reactor.get_cached("/wallet/tx/mock-0001").unwrap().data["amount_sat"], 21_000);
+
+        // The intent outlives the process, so a retry still finds it
+        let persistence = wallet.persistence().unwrap();
+        let intent: SendIntent = persistence.store().read("/wallet/intent/x").unwrap().unwrap().decode().unwrap();
+        assert_eq!(intent.payment_id.as_deref(), Some("mock-0002"));
+    }
+}
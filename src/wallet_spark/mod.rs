@@ -22,11 +22,27 @@
 //! | Get Bitcoin address | `read("/bitcoin-address")` | - (for faucet/exchanges) |
 //! | List transactions | `read("/transactions")` | - |
 //! | Get single tx | `read("/tx/{txid}")` | - |
-//! | Send payment | `write("/send", ...)` | `{to, amount, feeRate?}` |
+//! | Send payment | `write("/send", ...)` | `{to, amount, feeRate?, intent_id?}` |
+//! | Quote a send | `write("/send/prepare", ...)` | `{to, amount?}` |
+//! | Pay a quote | `write("/send/confirm", ...)` | `{quote_id, to, amount?}` |
 //! | Create invoice | `write("/invoice", ...)` | `{amount, description?}` |
+//! | Decode an input | `write("/parse", ...)` | `{input}` (works offline) |
+//! | Look up an LNURL | `write("/lnurl/resolve", ...)` | `{to}` |
+//! | Pay an LNURL / Lightning Address | `write("/lnurl/pay", ...)` | `{to, amount, comment?, intent_id?}` |
+//! | Withdraw from an LNURL | `write("/lnurl/withdraw", ...)` | `{to, amount, description?}` |
 //! | Watch payments | `watch("/tx/**")` | - |
 //! | Get pubkey | `read("/pubkey")` | - |
 //!
+//! Every send runs under a send intent persisted before the SDK is called
+//! (see [`intent`]): retrying with the same `intent_id` (or `quote_id`)
+//! pays once, and `connect()` settles intents a crash left pending.
+//!
+//! LNURL services are reached through an injectable [`LnurlHttp`] (see
+//! [`lnurl`]), so the flows can run against a local stub server.
+//!
+//! With the `mock` feature, `MockWalletBackend` serves the same paths from
+//! a scripted ledger, for tests that can't reach the Spark network.
+//!
 //! ## Status: SCAFFOLD
 //!
 //! This is a placeholder implementation. Methods return NotImplemented.
@@ -39,19 +55,31 @@ pub mod namespace;
 pub mod reactor;
 pub mod sdk;
 pub mod json_helpers;
+pub mod intent;
+pub mod lnurl;
+pub mod parse;
 pub mod parse_helpers;
+pub mod quote;
+pub mod schemas;
 
 // Persistence requires encrypted Store (crypto feature)
 #[cfg(feature = "crypto")]
 pub mod persistence;
 
+// Offline backend for tests and CI (mock feature)
+#[cfg(feature = "mock")]
+pub mod mock;
+
 use std::path::PathBuf;
 use std::sync::Arc;
 use thiserror::Error;
 use serde_json::{json, Value};
 use tokio::runtime::Runtime;
 
+use async_trait::async_trait;
+
 use crate::nine_s::{self, Namespace, Scroll};
+use crate::nine_s::async_namespace::{self, receiver_stream, ScrollStream};
 
 // Re-export submodule types
 pub use config::WalletConfig;
@@ -59,16 +87,29 @@ pub use events::{
     EventListener, SdkEvent, Payment, PaymentType, PaymentState, PaymentDetails,
     ClaimedDeposit, UnclaimedDeposit,
 };
+pub use intent::SendIntent;
+pub use lnurl::{LnurlHttp, LnurlParams, UreqHttp};
 pub use namespace::WalletNamespace;
+pub use parse::ParsedInput;
+pub use quote::SendQuote;
 pub use reactor::{WalletReactor, ReactorEventAdapter};
 pub use sdk::{SparkSdkWrapper, PaymentInfo, ReceiveInfo};
 
 #[cfg(feature = "crypto")]
 pub use persistence::WalletPersistence;
 
+#[cfg(feature = "mock")]
+pub use mock::{MockLedger, MockWalletBackend};
+
 // Re-export common types
 pub use crate::wallet_trait::{SignedMessage, TransactionDetails, WalletBalance};
 
+/// Kernel paths whose writes pay out, for a wallet mounted at `/wallet`
+///
+/// A scoped spending cap should cover all of them at once, e.g. with
+/// `Policy::limit_daily_across(&SPEND_PATHS, &["amount", "amount_sat"], max)`.
+pub const SPEND_PATHS: [&str; 3] = ["/wallet/send", "/wallet/send/confirm", "/wallet/lnurl/pay"];
+
 #[derive(Error, Debug)]
 pub enum WalletError {
     #[error("Not connected")]
@@ -162,6 +203,8 @@ pub struct WalletManager {
     working_dir: Option<PathBuf>,
     /// Reactive event core - handles SDK events → Scroll streams
     reactor: Arc<reactor::WalletReactor>,
+    /// Transport for LNURL services
+    http: Arc<dyn lnurl::LnurlHttp>,
     /// Persistence layer - bridges reactor with encrypted store
     #[cfg(feature = "crypto")]
     persistence: Option<Arc<persistence::WalletPersistence>>,
@@ -186,6 +229,7 @@ impl WalletManager {
             api_key,
             working_dir: None,
             reactor,
+            http: Arc::new(lnurl::UreqHttp::new()),
             #[cfg(feature = "crypto")]
             persistence: None,
         }
@@ -215,6 +259,12 @@ impl WalletManager {
         self
     }
 
+    /// Reach LNURL services through `http` instead of `ureq`
+    pub fn with_http(mut self, http: Arc<dyn lnurl::LnurlHttp>) -> Self {
+        self.http = http;
+        self
+    }
+
     /// Initialize persistence with an encrypted Store
     ///
     /// This bridges the reactive event core (Reactor) with encrypted
@@ -268,10 +318,62 @@ impl WalletManager {
                 Ok(Ok(())) => Ok(()),
                 Ok(Err(e)) => Err(WalletError::Sdk(e.to_string())),
                 Err(_) => Err(WalletError::Sdk("Connection timed out after 30 seconds".to_string())),
-            }
+            }?;
+
+            // Best effort: whatever is left pending is retried next connect
+            let _ = self.reconcile_intents().await;
+            Ok(())
         })
     }
 
+    /// Settle send intents left pending by a crash or a lost response
+    ///
+    /// Syncs first, then binds each pending intent to the payment
+    /// `list_payments` shows for it. Called by `connect()`.
+    ///
+    /// # Returns
+    /// How many intents were settled.
+    pub async fn reconcile_intents(&self) -> nine_s::Result<usize> {
+        let synced = self.sdk.sync().await.is_ok();
+        self.settle_intents(synced).await
+    }
+
+    /// Bind pending intents to their payments
+    ///
+    /// Only a completed sync makes a missing payment conclusive: then the
+    /// intent never reached the network and is marked failed rather than
+    /// paid behind the user's back. Otherwise it stays pending for the
+    /// next `/sync`.
+    async fn settle_intents(&self, synced: bool) -> nine_s::Result<usize> {
+        let intents = self.intents();
+        let all = intents.all()?;
+        let claimed = all.iter().filter_map(|i| i.payment_id.clone()).collect();
+        let mut pending: Vec<SendIntent> = all
+            .into_iter()
+            .filter(|i| i.status == intent::IntentStatus::Pending)
+            .collect();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let payments = self.sdk.list_payments(None).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+        let unmatched = intent::reconcile(&mut pending, &payments, &claimed);
+        if synced {
+            for &i in &unmatched {
+                pending[i].status = intent::IntentStatus::Failed;
+            }
+        }
+        let settled: Vec<&SendIntent> = pending
+            .iter()
+            .filter(|i| i.status != intent::IntentStatus::Pending)
+            .collect();
+        for send in &settled {
+            intents.save(send)?;
+        }
+
+        Ok(settled.len())
+    }
+
     /// Initialize from mnemonic (same as connect for Spark)
     pub fn init_from_mnemonic(
         &self,
@@ -336,12 +438,31 @@ impl WalletManager {
             .ok_or(WalletError::InvalidData("no address in response".into()))
     }
 
-    /// Send to destination (delegates to write("/send", {to, amount, feeRate}))
+    /// Reserve a send (delegates to write("/send/intent", {to, amount}))
+    ///
+    /// Nothing is paid; pass the returned id to `send` so that retrying a
+    /// send that failed or timed out pays at most once.
+    pub fn reserve_send(&self, destination: &str, amount_sats: u64) -> Result<String, WalletError> {
+        let scroll = self.write("/send/intent", json!({
+            "to": destination,
+            "amount": amount_sats,
+        })).map_err(WalletError::from)?;
+
+        scroll.data["intent_id"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or(WalletError::InvalidData("no intent_id in response".into()))
+    }
+
+    /// Send to destination (delegates to write("/send", {to, amount, feeRate, intent_id}))
+    ///
+    /// Retrying with the same `intent_id` (from `reserve_send`) never pays twice.
     pub fn send(
         &self,
         destination: &str,
         amount_sats: u64,
         fee_rate: Option<f64>,
+        intent_id: Option<&str>,
     ) -> Result<String, WalletError> {
         let mut data = json!({
             "to": destination,
@@ -350,6 +471,9 @@ impl WalletManager {
         if let Some(rate) = fee_rate {
             data["feeRate"] = json!(rate);
         }
+        if let Some(intent_id) = intent_id {
+            data["intent_id"] = json!(intent_id);
+        }
 
         let scroll = self.write("/send", data).map_err(WalletError::from)?;
 
@@ -359,6 +483,37 @@ impl WalletManager {
             .ok_or(WalletError::InvalidData("no txid in response".into()))
     }
 
+    /// Quote a send for review (delegates to write("/send/prepare", {to, amount}))
+    ///
+    /// Nothing is paid until `confirm_send` is called with the quote.
+    pub fn prepare_send(
+        &self,
+        destination: &str,
+        amount_sats: Option<u64>,
+    ) -> Result<SendQuote, WalletError> {
+        let mut data = json!({"to": destination});
+        if let Some(amount) = amount_sats {
+            data["amount"] = json!(amount);
+        }
+
+        let scroll = self.write("/send/prepare", data).map_err(WalletError::from)?;
+        scroll.decode().map_err(WalletError::from)
+    }
+
+    /// Pay a reviewed quote (delegates to write("/send/confirm", {quote_id, to, amount}))
+    pub fn confirm_send(&self, quote: &SendQuote) -> Result<String, WalletError> {
+        let scroll = self.write("/send/confirm", json!({
+            "quote_id": quote.quote_id,
+            "to": quote.destination,
+            "amount": quote.amount_sat,
+        })).map_err(WalletError::from)?;
+
+        scroll.data["txid"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or(WalletError::InvalidData("no txid in response".into()))
+    }
+
     /// Estimate fee (delegates to write("/fee-estimate", {to, amount}))
     pub fn estimate_fee(&self, destination: &str, amount_sats: u64) -> Result<u64, WalletError> {
         let scroll = self.write("/fee-estimate", json!({
@@ -468,6 +623,88 @@ impl WalletManager {
         "0.1.0-experimental"
     }
 
+    /// Where send intents are kept (the Store, once `with_store` is called)
+    fn intents(&self) -> intent::IntentLog<'_> {
+        let mut log = intent::IntentLog::new(&self.reactor);
+        #[cfg(feature = "crypto")]
+        log.set_persistence(self.persistence.as_deref());
+        log
+    }
+
+    /// Pay under an intent, persisting it before the SDK sees it
+    ///
+    /// If the SDK call fails the intent stays pending: the payment may
+    /// still have gone out, so only a retry or reconnect can tell. The
+    /// error names the intent to retry under.
+    async fn pay_intent(&self, mut send: SendIntent, quote_id: Option<&str>) -> nine_s::Result<Scroll> {
+        let intents = self.intents();
+        intents.save(&send)?;
+
+        // A fetched invoice names its own amount
+        let amount = send.amount_sat.filter(|_| send.invoice.is_none());
+        let key = Some(send.idempotency_key.clone());
+        let payment = match quote_id {
+            Some(quote_id) => self.sdk.send_quote(quote_id, key).await,
+            None => self.sdk.send(send.pays(), amount, key).await,
+        }.map_err(|e| nine_s::Error::Internal(format!("send {} failed: {}", send.intent_id, e)))?;
+
+        send.resolve(&payment);
+        intents.save(&send)?;
+        send.payment_scroll()
+            .ok_or_else(|| nine_s::Error::Internal("intent not resolved".into()))
+    }
+
+    /// Answer a repeated send from its intent, paying only if still pending
+    ///
+    /// A pending confirm pays the quote it was prepared under; once that
+    /// quote is gone it is refused rather than re-prepared at a new fee.
+    async fn retry_intent(
+        &self,
+        send: SendIntent,
+        destination: &str,
+        amount_sat: Option<u64>,
+        quote_id: Option<&str>,
+    ) -> nine_s::Result<Scroll> {
+        send.check_retry(destination, amount_sat)?;
+
+        match send.status {
+            intent::IntentStatus::Resolved => send.payment_scroll()
+                .ok_or_else(|| nine_s::Error::Internal("resolved intent without payment".into())),
+            intent::IntentStatus::Failed => Err(nine_s::Error::Conflict(format!(
+                "send {} failed; start a new one",
+                send.intent_id
+            ))),
+            intent::IntentStatus::Pending => match quote_id {
+                Some(quote_id) if !self.sdk.holds_quote(quote_id, reactor::now_unix()) => {
+                    Err(nine_s::Error::Conflict(format!(
+                        "quote {} consumed, prepare again",
+                        quote_id
+                    )))
+                }
+                // Same key as the first attempt, so the SDK pays at most once
+                _ => self.pay_intent(send, quote_id).await,
+            },
+        }
+    }
+
+    /// Run a blocking LNURL request off the async runtime's workers
+    async fn lnurl<T, F>(&self, request: F) -> nine_s::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn lnurl::LnurlHttp) -> nine_s::Result<T> + Send + 'static,
+    {
+        let http = self.http.clone();
+        tokio::task::spawn_blocking(move || request(http.as_ref()))
+            .await
+            .map_err(|e| nine_s::Error::Internal(e.to_string()))?
+    }
+
+    /// Fetch the params behind the target of a `/lnurl/...` write
+    async fn resolve_lnurl(&self, data: &Value) -> nine_s::Result<lnurl::LnurlParams> {
+        let target = lnurl::target(data)?.to_string();
+        self.lnurl(move |http| lnurl::resolve(http, &target)).await
+    }
+
     /// Convert network to string for scroll responses
     fn network_str(&self) -> &'static str {
         match self.network {
@@ -478,11 +715,24 @@ impl WalletManager {
     }
 }
 
+/// Whether a watch pattern uses the reactor's own `/wallet/...` keys
+fn is_wallet_root_pattern(pattern: &str) -> bool {
+    pattern == reactor::WALLET_ROOT
+        || pattern
+            .strip_prefix(reactor::WALLET_ROOT)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
 // =============================================================================
 // Namespace Implementation: The 9S Way
 // =============================================================================
+//
+// The async impl is the real one: SDK calls are awaited, so Tauri commands
+// and other tokio tasks can use the wallet directly. The sync impl blocks on
+// it from the wallet's own runtime for plain threads and FFI.
 
-impl Namespace for WalletManager {
+#[async_trait]
+impl async_namespace::AsyncNamespace for WalletManager {
     /// Read wallet data by path
     ///
     /// Paths:
@@ -493,10 +743,10 @@ impl Namespace for WalletManager {
     /// - `/network` - Current network
     /// - `/transactions` - Recent transactions
     /// - `/tx/{txid}` - Single transaction
-    fn read(&self, path: &str) -> nine_s::Result<Option<Scroll>> {
+    async fn read(&self, path: &str) -> nine_s::Result<Option<Scroll>> {
         // Status check doesn't require connection
         if path == "/status" || path.is_empty() || path == "/" {
-            let connected = self.is_connected();
+            let connected = self.sdk.is_connected().await;
             return Ok(Some(Scroll::typed(
                 "/wallet/status",
                 json!({
@@ -510,7 +760,7 @@ impl Namespace for WalletManager {
         }
 
         // Everything else requires connection
-        if !self.is_connected() {
+        if !self.sdk.is_connected().await {
             return Err(nine_s::Error::Unavailable("Wallet not connected".into()));
         }
 
@@ -521,9 +771,7 @@ impl Namespace for WalletManager {
                     return Ok(Some(cached));
                 }
 
-                let balance = self.runtime.block_on(async {
-                    self.sdk.get_balance().await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let balance = self.sdk.get_balance().await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 // Cache in reactor
                 self.reactor.emit_balance(balance, 0);
@@ -545,9 +793,7 @@ impl Namespace for WalletManager {
                     return Ok(Some(cached));
                 }
 
-                let address = self.runtime.block_on(async {
-                    self.sdk.get_spark_address().await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let address = self.sdk.get_spark_address().await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 let scroll = Scroll::typed(
                     "/wallet/address",
@@ -566,9 +812,7 @@ impl Namespace for WalletManager {
                     return Ok(Some(cached));
                 }
 
-                let receive_info = self.runtime.block_on(async {
-                    self.sdk.get_bitcoin_address().await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let receive_info = self.sdk.get_bitcoin_address().await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 let scroll = Scroll::typed(
                     "/wallet/bitcoin-address",
@@ -590,9 +834,7 @@ impl Namespace for WalletManager {
                 }
 
                 // Spark address includes the identity pubkey
-                let address = self.runtime.block_on(async {
-                    self.sdk.get_spark_address().await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let address = self.sdk.get_spark_address().await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 // Extract pubkey from spark address (it's part of the address format)
                 let scroll = Scroll::typed(
@@ -620,9 +862,7 @@ impl Namespace for WalletManager {
                     .and_then(|s| s.split('&').next())
                     .and_then(|s| s.parse::<u32>().ok());
 
-                let payments = self.runtime.block_on(async {
-                    self.sdk.list_payments(limit).await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let payments = self.sdk.list_payments(limit).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 let txs: Vec<Value> = payments.iter().map(|p| json!({
                     "txid": p.id,
@@ -642,14 +882,13 @@ impl Namespace for WalletManager {
             p if p.starts_with("/tx/") => {
                 let txid = &p[4..];
                 // Try to find in cached payments
-                let payments = self.runtime.block_on(async {
-                    self.sdk.list_payments(Some(100)).await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let payments = self.sdk.list_payments(Some(100)).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 if let Some(payment) = payments.iter().find(|p| p.id == txid) {
                     Ok(Some(Scroll::typed(
                         &format!("/wallet/tx/{}", txid),
                         json!({
+                            "id": payment.id,
                             "txid": payment.id,
                             "type": if matches!(payment.payment_type, PaymentType::Receive) { "receive" } else { "send" },
                             "amount_sat": payment.amount_sat,
@@ -670,14 +909,26 @@ impl Namespace for WalletManager {
     /// Write wallet operations
     ///
     /// Paths:
-    /// - `/send` - Send payment (requires: to, amount; optional: feeRate)
+    /// - `/send/intent` - Reserve an intent id for `/send` or `/lnurl/pay` (requires: to; optional: amount)
+    /// - `/send` - Send payment (requires: to, amount; optional: feeRate, intent_id)
+    /// - `/send/prepare` - Quote a send for review (requires: to; optional: amount)
+    /// - `/send/confirm` - Pay a quote (requires: quote_id, to; optional: amount)
     /// - `/invoice` - Create invoice (requires: amount; optional: description)
     /// - `/sync` - Trigger wallet sync
     /// - `/sign` - Sign message (requires: message)
     /// - `/verify` - Verify signature (requires: message, signature, pubkey)
     /// - `/fee-estimate` - Estimate fee (requires: to, amount)
-    fn write(&self, path: &str, data: Value) -> nine_s::Result<Scroll> {
-        if !self.is_connected() {
+    /// - `/parse` - Decode a payment input (requires: input; no connection needed)
+    /// - `/lnurl/resolve` - Fetch an LNURL's params (requires: to)
+    /// - `/lnurl/pay` - Pay an LNURL-Pay or Lightning Address (requires: to, amount; optional: comment, intent_id)
+    /// - `/lnurl/withdraw` - Withdraw from an LNURL-Withdraw (requires: to, amount)
+    async fn write(&self, path: &str, data: Value) -> nine_s::Result<Scroll> {
+        // Parsing is offline
+        if path == "/parse" {
+            return parse::parse_request(&data);
+        }
+
+        if !self.sdk.is_connected().await {
             return Err(nine_s::Error::Unavailable("Wallet not connected".into()));
         }
 
@@ -689,22 +940,77 @@ impl Namespace for WalletManager {
                 let amount = data["amount"].as_u64()
                     .or_else(|| data["amount_sat"].as_u64());
 
-                let payment = self.runtime.block_on(async {
-                    self.sdk.send(destination, amount).await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                // A caller-chosen intent_id is what makes a retry recognisable
+                let intent_id = data["intent_id"].as_str();
+                if let Some(existing) = intent_id.map(|id| self.intents().get(id)).transpose()?.flatten() {
+                    return self.retry_intent(existing, destination, amount, None).await;
+                }
 
-                Ok(Scroll::typed(
-                    &format!("/wallet/tx/{}", payment.id),
-                    json!({
-                        "txid": payment.id,
-                        "type": "send",
-                        "amount_sat": payment.amount_sat,
-                        "fee_sat": payment.fee_sat,
-                        "timestamp": payment.timestamp,
-                        "state": format!("{:?}", payment.status),
-                    }),
-                    "wallet/payment@v1",
-                ))
+                let intent_id = intent_id.map(String::from).unwrap_or_else(intent::new_intent_id);
+                let send = SendIntent::new(intent_id, destination, amount, reactor::now_unix());
+                self.pay_intent(send, None).await
+            }
+            "/send/intent" => {
+                let destination = data["to"].as_str()
+                    .or_else(|| data["destination"].as_str())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                let amount = data["amount"].as_u64()
+                    .or_else(|| data["amount_sat"].as_u64());
+
+                let send = SendIntent::new(intent::new_intent_id(), destination, amount, reactor::now_unix());
+                self.intents().save(&send)?;
+                send.to_scroll()
+            }
+            "/send/prepare" => {
+                let destination = data["to"].as_str()
+                    .or_else(|| data["destination"].as_str())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                let amount = data["amount"].as_u64()
+                    .or_else(|| data["amount_sat"].as_u64());
+
+                // Quotes nobody confirmed in time leave the cache here
+                for quote_id in self.sdk.prune_quotes(reactor::now_unix()) {
+                    self.reactor.take_cached(&quote::quote_key(&quote_id));
+                }
+
+                let quote = self.sdk.prepare_send(destination, amount).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+
+                let scroll = quote.to_scroll()?;
+                self.reactor.emit(scroll.clone());
+
+                Ok(scroll)
+            }
+            "/send/confirm" => {
+                // The quote id doubles as the intent id: a retried confirm
+                // finds the quote spent but its intent on record
+                if let Some(quote_id) = data["quote_id"].as_str() {
+                    if let Some(existing) = self.intents().get(quote_id)? {
+                        let destination = data["to"].as_str()
+                            .or_else(|| data["destination"].as_str())
+                            .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                        let amount = data["amount"].as_u64()
+                            .or_else(|| data["amount_sat"].as_u64());
+                        let mut scroll = self.retry_intent(existing, destination, amount, Some(quote_id)).await?;
+                        scroll.data["quote_id"] = json!(quote_id);
+                        return Ok(scroll);
+                    }
+                }
+
+                let quote = quote::take(&self.reactor, &data)?;
+                if let Err(e) = quote.check(&data, reactor::now_unix()) {
+                    self.sdk.discard_quote(&quote.quote_id);
+                    return Err(e);
+                }
+
+                let send = SendIntent::new(
+                    quote.quote_id.clone(),
+                    quote.destination.clone(),
+                    Some(quote.amount_sat),
+                    reactor::now_unix(),
+                );
+                let mut scroll = self.pay_intent(send, Some(&quote.quote_id)).await?;
+                scroll.data["quote_id"] = json!(quote.quote_id);
+                Ok(scroll)
             }
             "/invoice" | "/receive" => {
                 let amount = data["amount"].as_u64()
@@ -713,9 +1019,7 @@ impl Namespace for WalletManager {
                     .ok_or_else(|| nine_s::Error::InvalidData("Missing 'amount' field".into()))?;
                 let description = data["description"].as_str().map(|s| s.to_string());
 
-                let receive_info = self.runtime.block_on(async {
-                    self.sdk.create_invoice(amount, description).await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let receive_info = self.sdk.create_invoice(amount, description).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 Ok(Scroll::typed(
                     "/wallet/invoice",
@@ -727,10 +1031,63 @@ impl Namespace for WalletManager {
                     "wallet/invoice@v1",
                 ))
             }
+            "/lnurl/resolve" => self.resolve_lnurl(&data).await?.to_scroll(),
+            "/lnurl/pay" => {
+                let target = lnurl::target(&data)?.to_string();
+                let amount = lnurl::amount_sat(&data)?;
+
+                // Once the intent holds an invoice, a retry pays that, not a
+                // new one; a reserved intent goes on to fetch its invoice
+                let intent_id = data["intent_id"].as_str();
+                let reserved = match intent_id.map(|id| self.intents().get(id)).transpose()?.flatten() {
+                    Some(existing) if existing.invoice.is_none() && existing.status == intent::IntentStatus::Pending => {
+                        existing.check_retry(&target, Some(amount))?;
+                        Some(existing)
+                    }
+                    Some(existing) => return self.retry_intent(existing, &target, Some(amount), None).await,
+                    None => None,
+                };
+
+                let lnurl::LnurlParams::Pay(params) = self.resolve_lnurl(&data).await? else {
+                    return Err(nine_s::Error::InvalidData("not an LNURL-Pay".into()));
+                };
+                let comment = data["comment"].as_str().map(String::from);
+                let request = params.clone();
+                let paid = self
+                    .lnurl(move |http| lnurl::request_invoice(http, &request, amount, comment.as_deref()))
+                    .await?;
+
+                // The invoice is on record before the SDK sees it
+                let mut send = reserved.unwrap_or_else(|| {
+                    let intent_id = intent_id.map(String::from).unwrap_or_else(intent::new_intent_id);
+                    SendIntent::new(intent_id, target, Some(amount), reactor::now_unix())
+                });
+                send.invoice = Some(paid.invoice.invoice.clone());
+                let payment = self.pay_intent(send, None).await?;
+                Ok(lnurl::paid_scroll(payment, &params, &paid))
+            }
+            "/lnurl/withdraw" => {
+                let lnurl::LnurlParams::Withdraw(params) = self.resolve_lnurl(&data).await? else {
+                    return Err(nine_s::Error::InvalidData("not an LNURL-Withdraw".into()));
+                };
+                let amount = lnurl::amount_sat(&data)?;
+                lnurl::check_withdraw(&params, amount)?;
+
+                let description = data["description"].as_str()
+                    .map(String::from)
+                    .unwrap_or_else(|| params.default_description.clone());
+                let invoice = self.sdk.create_lightning_invoice(amount, description).await
+                    .map_err(|e| nine_s::Error::Internal(e.to_string()))?
+                    .destination;
+
+                let (request, submitted) = (params.clone(), invoice.clone());
+                self.lnurl(move |http| lnurl::submit_withdraw(http, &request, &submitted)).await?;
+                Ok(lnurl::withdraw_scroll(&params, &invoice, amount))
+            }
             "/sync" => {
-                self.runtime.block_on(async {
-                    self.sdk.sync().await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                self.sdk.sync().await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                // Intents a crash left pending can now be settled for good
+                self.settle_intents(true).await?;
 
                 Ok(Scroll::typed(
                     "/wallet/sync",
@@ -743,9 +1100,7 @@ impl Namespace for WalletManager {
                     .ok_or_else(|| nine_s::Error::InvalidData("Missing 'message' field".into()))?;
 
                 // Use SDK to sign
-                let signed = self.runtime.block_on(async {
-                    self.sdk.sign_message(message).await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let signed = self.sdk.sign_message(message).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 Ok(Scroll::typed(
                     "/wallet/sign",
@@ -766,9 +1121,7 @@ impl Namespace for WalletManager {
                     .ok_or_else(|| nine_s::Error::InvalidData("Missing 'pubkey' field".into()))?;
 
                 // Use SDK to verify
-                let valid = self.runtime.block_on(async {
-                    self.sdk.verify_message(message, signature, pubkey).await
-                }).map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                let valid = self.sdk.verify_message(message, signature, pubkey).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
 
                 Ok(Scroll::typed(
                     "/wallet/verify",
@@ -777,11 +1130,19 @@ impl Namespace for WalletManager {
                 ))
             }
             "/fee-estimate" => {
-                // Spark has no fees for Spark-to-Spark transfers
-                // Fee estimation would be needed for Lightning/on-chain
+                // A quote nobody will confirm
+                let destination = data["to"].as_str()
+                    .or_else(|| data["destination"].as_str())
+                    .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+                let amount = data["amount"].as_u64()
+                    .or_else(|| data["amount_sat"].as_u64());
+
+                let quote = self.sdk.prepare_send(destination, amount).await.map_err(|e| nine_s::Error::Internal(e.to_string()))?;
+                self.sdk.discard_quote(&quote.quote_id);
+
                 Ok(Scroll::typed(
                     "/wallet/fee-estimate",
-                    json!({"fee": 0}),
+                    json!({"fee": quote.fee_sat}),
                     "wallet/fee-estimate@v1",
                 ))
             }
@@ -789,6 +1150,37 @@ impl Namespace for WalletManager {
         }
     }
 
+    async fn list(&self, prefix: &str) -> nine_s::Result<Vec<String>> {
+        Namespace::list(self, prefix)
+    }
+
+    /// Watch for changes (payment events), as a stream
+    async fn watch(&self, pattern: &str) -> nine_s::Result<ScrollStream> {
+        Ok(receiver_stream(Namespace::watch(self, pattern)?))
+    }
+
+    /// Close wallet connection
+    async fn close(&self) -> nine_s::Result<()> {
+        let _ = self.sdk.disconnect().await;
+        Ok(())
+    }
+}
+
+impl Namespace for WalletManager {
+    /// Read wallet data by path (blocks on the async impl)
+    ///
+    /// Must not be called from inside a tokio task; use the async impl there.
+    fn read(&self, path: &str) -> nine_s::Result<Option<Scroll>> {
+        self.runtime.block_on(async_namespace::AsyncNamespace::read(self, path))
+    }
+
+    /// Write wallet operations (blocks on the async impl)
+    ///
+    /// Must not be called from inside a tokio task; use the async impl there.
+    fn write(&self, path: &str, data: Value) -> nine_s::Result<Scroll> {
+        self.runtime.block_on(async_namespace::AsyncNamespace::write(self, path, data))
+    }
+
     /// List available paths
     fn list(&self, prefix: &str) -> nine_s::Result<Vec<String>> {
         match prefix {
@@ -821,6 +1213,10 @@ impl Namespace for WalletManager {
     /// - `/wallet/tx/**` - All transaction events
     /// - `/wallet/**` - All wallet events
     ///
+    /// Patterns outside `/wallet` are mount-relative (`/balance`, `/tx/**`),
+    /// the form a Kernel passes when the wallet is mounted at `/wallet`.
+    /// Their scrolls carry mount-relative keys, like `read` and `list`.
+    ///
     /// ## Example
     ///
     /// ```rust,ignore
@@ -838,7 +1234,11 @@ impl Namespace for WalletManager {
     fn watch(&self, pattern: &str) -> nine_s::Result<nine_s::Receiver<Scroll>> {
         // Watch works even when disconnected (will receive events when connected)
         // This allows UI to set up watchers before connection is established
-        Ok(self.reactor.watch(pattern))
+        if is_wallet_root_pattern(pattern) {
+            Ok(self.reactor.watch(pattern))
+        } else {
+            Ok(self.reactor.watch_relative(pattern))
+        }
     }
 
     /// Close wallet connection
@@ -878,6 +1278,23 @@ mod tests {
         assert_eq!(scroll.data["backend"], "spark");
     }
 
+    #[test]
+    fn test_async_namespace_inside_tokio_task() {
+        use crate::nine_s::AsyncNamespace;
+
+        let manager = WalletManager::new(SparkNetwork::Regtest, None);
+        let rt = Runtime::new().unwrap();
+
+        // The sync impl would panic here (block_on inside a runtime)
+        rt.block_on(async {
+            let scroll = AsyncNamespace::read(&manager, "/status").await.unwrap().unwrap();
+            assert_eq!(scroll.data["connected"], false);
+
+            let result = AsyncNamespace::read(&manager, "/balance").await;
+            assert!(matches!(result, Err(nine_s::Error::Unavailable(_))));
+        });
+    }
+
     #[test]
     fn test_namespace_list() {
         let manager = WalletManager::new(SparkNetwork::Testnet, None);
@@ -925,6 +1342,23 @@ mod tests {
         assert_eq!(scroll.data["trusted_pending"], 1000);
     }
 
+    #[test]
+    fn test_kernel_watch_through_wallet_mount() {
+        use crate::nine_s::Kernel;
+
+        let manager = Arc::new(WalletManager::new(SparkNetwork::Regtest, None));
+        let kernel = Kernel::new();
+        kernel.mount("/wallet", manager.clone());
+
+        // Kernel hands the wallet a mount-relative pattern and restores the prefix
+        let mut rx = kernel.watch("/wallet/balance").unwrap();
+        manager.reactor().emit_balance(42000, 0);
+
+        let scroll = rx.recv().expect("Should receive balance scroll");
+        assert_eq!(scroll.key, "/wallet/balance");
+        assert_eq!(scroll.data["confirmed"], 42000);
+    }
+
     #[test]
     fn test_watch_payment_events() {
         let manager = WalletManager::new(SparkNetwork::Regtest, None);
@@ -196,6 +196,11 @@ impl Namespace for WalletNamespace {
     }
 
     fn write(&self, path: &str, data: Value) -> Result<Scroll> {
+        // Parsing doesn't need a wallet
+        if path == "/parse" {
+            return super::parse::parse_request(&data);
+        }
+
         let guard = self.require_wallet()?;
         let wallet = guard.as_ref().ok_or_else(|| Error::Unavailable("Wallet not available".into()))?;
 
@@ -292,4 +297,19 @@ mod tests {
         let result = ns.read("/balance");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_namespace_parses_without_wallet() {
+        let mnemonic = Arc::new(RwLock::new(None));
+        let ns = WalletNamespace::new(
+            mnemonic,
+            PathBuf::from("/tmp/test"),
+            SparkNetwork::Testnet,
+            None,
+        );
+
+        let scroll = ns.write("/parse", json!({"input": "alice@example.com"})).unwrap();
+        assert_eq!(scroll.type_, "wallet/parsed-input@v1");
+        assert_eq!(scroll.data["type"], "lightning-address");
+    }
 }
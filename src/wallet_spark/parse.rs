@@ -0,0 +1,815 @@
+//! Payment Input Parser - Decode what the user pasted, offline
+//!
+//! `parse_helpers` only guesses the kind of an input. `write("/parse",
+//! {input})` decodes it fully into a `wallet/parsed-input@v1` scroll, with
+//! no SDK and no network:
+//!
+//! | Input | `type` | Decoded |
+//! |-------|--------|---------|
+//! | `lnbc2500u1...` | `bolt11` | amount, expiry, description (hash), payee |
+//! | `bitcoin:bc1q...?amount=..&lightning=..` | `bip21` | address, amount, label, message, invoice |
+//! | `bc1q...` | `bitcoin-address` | network |
+//! | `spark1...` / `sp1...` | `spark-address` | network, identity pubkey |
+//! | `alice@example.com` | `lightning-address` | LNURL-Pay endpoint |
+//! | `lnurl1...`, `lnurlp://...`, `keyauth://...` | `lnurl` | URL, kind, k1 |
+//!
+//! A `lightning:` prefix is stripped first. Anything else is `InvalidData`.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Let the SDK parse (complete, but needs a connection)
+//! **Antithesis**: Guess from prefixes (offline, but shows the user nothing)
+//! **Synthesis**: Decode the formats themselves; leave only LNURL fetches online
+
+use std::str::FromStr;
+
+use bech32::{FromBase32, ToBase32};
+use bitcoin::secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use bitcoin::secp256k1::{Message, Secp256k1};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::nine_s::{self, Scroll, ScrollType};
+
+use super::reactor::WALLET_ROOT;
+
+/// Expiry of a BOLT11 invoice without an `x` field
+const DEFAULT_EXPIRY_SECS: u64 = 3600;
+
+/// `min_final_cltv_expiry_delta` of a BOLT11 invoice without a `c` field
+const DEFAULT_MIN_FINAL_CLTV: u64 = 18;
+
+/// A fully decoded payment input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ScrollType)]
+#[scroll(type = "wallet/parsed-input@v1")]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ParsedInput {
+    Bolt11(Bolt11Invoice),
+    Bip21(Bip21Uri),
+    BitcoinAddress(BitcoinAddress),
+    SparkAddress(SparkAddress),
+    LightningAddress(LightningAddress),
+    Lnurl(Lnurl),
+}
+
+/// A decoded BOLT11 invoice
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bolt11Invoice {
+    pub invoice: String,
+    /// `bitcoin`, `testnet`, `signet` or `regtest`
+    pub network: String,
+    /// `None` for "any amount" invoices
+    pub amount_msat: Option<u64>,
+    /// `amount_msat` rounded up to whole sats
+    pub amount_sat: Option<u64>,
+    /// Unix seconds the invoice was created
+    pub timestamp: u64,
+    pub expiry_secs: u64,
+    /// `timestamp + expiry_secs`
+    pub expires_at: u64,
+    pub payment_hash: String,
+    pub payment_secret: Option<String>,
+    pub description: Option<String>,
+    /// SHA-256 of a description too long to embed (LNURL-Pay metadata)
+    pub description_hash: Option<String>,
+    /// Node pubkey, from the `n` field or recovered from the signature
+    pub payee: String,
+    pub min_final_cltv_expiry_delta: u64,
+}
+
+/// A decoded BIP21 `bitcoin:` URI
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bip21Uri {
+    pub uri: String,
+    /// Empty in lightning-only URIs (`bitcoin:?lightning=...`)
+    pub address: Option<String>,
+    pub network: Option<String>,
+    pub amount_sat: Option<u64>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+    /// The `lightning=` invoice, decoded
+    pub lightning: Option<Bolt11Invoice>,
+}
+
+/// An on-chain address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitcoinAddress {
+    pub address: String,
+    pub network: String,
+}
+
+/// A Spark address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparkAddress {
+    pub address: String,
+    pub network: String,
+    /// Hex identity pubkey the address encodes, when it could be read
+    pub identity_pubkey: Option<String>,
+}
+
+/// A Lightning Address (LUD-16)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightningAddress {
+    pub address: String,
+    pub username: String,
+    pub domain: String,
+    /// Where the LNURL-Pay parameters are fetched from
+    pub lnurlp_url: String,
+}
+
+/// What an LNURL is for, as far as can be told without fetching it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LnurlKind {
+    Pay,
+    Withdraw,
+    Auth,
+    Channel,
+    /// Only the endpoint's response will say
+    Unknown,
+}
+
+/// A decoded LNURL (bech32 or LUD-17 scheme)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lnurl {
+    pub lnurl: String,
+    /// The `https` (or onion `http`) URL it stands for
+    pub url: String,
+    pub domain: String,
+    pub kind: LnurlKind,
+    /// LNURL-Auth challenge
+    pub k1: Option<String>,
+    /// LNURL-Auth action (`register`, `login`, `link`, `auth`)
+    pub action: Option<String>,
+}
+
+impl ParsedInput {
+    /// Decode a payment input
+    ///
+    /// # Errors
+    /// `InvalidData` if the input is malformed or of no known kind.
+    pub fn parse(input: &str) -> nine_s::Result<Self> {
+        let input = input.trim();
+        // A `lightning:` URI carries a bare invoice, LNURL or address
+        let input = strip_prefix_ignore_case(input, "lightning:").unwrap_or(input);
+        let lower = input.to_lowercase();
+
+        if lower.starts_with("bitcoin:") {
+            return parse_bip21(input).map(ParsedInput::Bip21);
+        }
+        if let Some(lnurl) = parse_lnurl(input)? {
+            return Ok(ParsedInput::Lnurl(lnurl));
+        }
+        if lower.starts_with("ln") {
+            if let Ok((hrp, _, _)) = bech32::decode(input) {
+                if hrp.starts_with("ln") {
+                    return parse_bolt11(input).map(ParsedInput::Bolt11);
+                }
+            }
+        }
+        if input.contains('@') {
+            return parse_lightning_address(input).map(ParsedInput::LightningAddress);
+        }
+        if let Some(spark) = parse_spark_address(input) {
+            return Ok(ParsedInput::SparkAddress(spark));
+        }
+        if let Some(address) = parse_bitcoin_address(input) {
+            return Ok(ParsedInput::BitcoinAddress(address));
+        }
+
+        Err(invalid("unrecognised payment input"))
+    }
+
+    /// The `type` field of the scroll (`bolt11`, `bip21`, ...)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParsedInput::Bolt11(_) => "bolt11",
+            ParsedInput::Bip21(_) => "bip21",
+            ParsedInput::BitcoinAddress(_) => "bitcoin-address",
+            ParsedInput::SparkAddress(_) => "spark-address",
+            ParsedInput::LightningAddress(_) => "lightning-address",
+            ParsedInput::Lnurl(_) => "lnurl",
+        }
+    }
+
+    /// The decoded input as a `wallet/parsed-input@v1` scroll
+    pub fn to_scroll(&self) -> nine_s::Result<Scroll> {
+        Scroll::encode(format!("{}/parse", WALLET_ROOT), self)
+    }
+}
+
+/// Handle `write("/parse", {input})`
+///
+/// Shared by every wallet namespace; needs no connection.
+pub fn parse_request(data: &Value) -> nine_s::Result<Scroll> {
+    let input = data["input"].as_str()
+        .or_else(|| data.as_str())
+        .ok_or_else(|| invalid("Missing 'input' field"))?;
+    ParsedInput::parse(input)?.to_scroll()
+}
+
+fn invalid(reason: impl Into<String>) -> nine_s::Error {
+    nine_s::Error::InvalidData(reason.into())
+}
+
+fn strip_prefix_ignore_case<'a>(input: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = input.get(..prefix.len())?;
+    if head.eq_ignore_ascii_case(prefix) {
+        Some(&input[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+// =============================================================================
+// BOLT11
+// =============================================================================
+
+/// Decode a BOLT11 invoice and check its signature
+fn parse_bolt11(invoice: &str) -> nine_s::Result<Bolt11Invoice> {
+    let (hrp, data, _) = bech32::decode(invoice).map_err(|e| invalid(format!("bad invoice: {}", e)))?;
+    let data: Vec<u8> = data.iter().map(|u| u.to_u8()).collect();
+
+    // 7 groups of timestamp, 104 of signature, tagged fields between
+    if data.len() < 7 + 104 {
+        return Err(invalid("invoice too short"));
+    }
+    let (fields, signature) = data.split_at(data.len() - 104);
+
+    let (network, amount_msat) = parse_bolt11_hrp(&hrp)?;
+    let timestamp = to_int(&fields[..7]);
+
+    let mut invoice_out = Bolt11Invoice {
+        invoice: invoice.to_string(),
+        network: network.to_string(),
+        amount_msat,
+        amount_sat: amount_msat.map(|msat| msat / 1000 + u64::from(msat % 1000 != 0)),
+        timestamp,
+        expiry_secs: DEFAULT_EXPIRY_SECS,
+        expires_at: 0,
+        payment_hash: String::new(),
+        payment_secret: None,
+        description: None,
+        description_hash: None,
+        payee: String::new(),
+        min_final_cltv_expiry_delta: DEFAULT_MIN_FINAL_CLTV,
+    };
+    let mut payee = None;
+
+    let mut rest = &fields[7..];
+    while !rest.is_empty() {
+        if rest.len() < 3 {
+            return Err(invalid("truncated invoice field"));
+        }
+        let tag = rest[0];
+        let len = rest[1] as usize * 32 + rest[2] as usize;
+        let value = rest.get(3..3 + len).ok_or_else(|| invalid("truncated invoice field"))?;
+        rest = &rest[3 + len..];
+
+        // Fields of the wrong length are skipped, as BOLT11 requires
+        match (tag, len) {
+            (1, 52) if invoice_out.payment_hash.is_empty() => invoice_out.payment_hash = hex::encode(to_bytes(value, false)),
+            (16, 52) => invoice_out.payment_secret = Some(hex::encode(to_bytes(value, false))),
+            (23, 52) => invoice_out.description_hash = Some(hex::encode(to_bytes(value, false))),
+            (19, 53) => payee = Some(to_bytes(value, false)),
+            (13, _) => {
+                let text = String::from_utf8(to_bytes(value, false))
+                    .map_err(|_| invalid("invoice description is not UTF-8"))?;
+                invoice_out.description = Some(text);
+            }
+            // 12 groups are 60 bits; anything longer can't be a u64
+            (6 | 24, len) if len > 12 => return Err(invalid("invoice integer field too long")),
+            (6, _) => invoice_out.expiry_secs = to_int(value),
+            (24, _) => invoice_out.min_final_cltv_expiry_delta = to_int(value),
+            _ => {}
+        }
+    }
+
+    if invoice_out.payment_hash.is_empty() {
+        return Err(invalid("invoice has no payment hash"));
+    }
+
+    // The signature covers the hrp and the data before it
+    let mut preimage = hrp.as_bytes().to_vec();
+    preimage.extend(to_bytes(fields, true));
+    let digest: [u8; 32] = Sha256::digest(&preimage).into();
+
+    let signature = to_bytes(signature, false);
+    let recovery_id = RecoveryId::from_i32(signature[64] as i32)
+        .map_err(|_| invalid("bad invoice signature"))?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+        .map_err(|_| invalid("bad invoice signature"))?;
+    let recovered = Secp256k1::verification_only()
+        .recover_ecdsa(&Message::from_digest(digest), &signature)
+        .map_err(|_| invalid("bad invoice signature"))?
+        .serialize();
+
+    if let Some(payee) = payee.filter(|p| p.as_slice() != recovered.as_slice()) {
+        return Err(invalid(format!("invoice not signed by payee {}", hex::encode(payee))));
+    }
+
+    invoice_out.payee = hex::encode(recovered);
+    invoice_out.expires_at = invoice_out.timestamp.saturating_add(invoice_out.expiry_secs);
+    Ok(invoice_out)
+}
+
+/// Network and amount from `ln` + currency + amount
+fn parse_bolt11_hrp(hrp: &str) -> nine_s::Result<(&'static str, Option<u64>)> {
+    let rest = hrp.strip_prefix("ln").ok_or_else(|| invalid("invoice prefix must be 'ln'"))?;
+
+    // Longest currency prefixes first: "bcrt" before "bc", "tbs" before "tb"
+    let (network, amount) = [("bcrt", "regtest"), ("bc", "bitcoin"), ("tbs", "signet"), ("tb", "testnet")]
+        .iter()
+        .find_map(|(currency, network)| rest.strip_prefix(currency).map(|amount| (*network, amount)))
+        .ok_or_else(|| invalid(format!("unknown invoice currency in '{}'", hrp)))?;
+
+    if amount.is_empty() {
+        return Ok((network, None));
+    }
+
+    let (digits, multiplier) = match amount.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&amount[..i], Some(c)),
+        _ => (amount, None),
+    };
+    let value: u64 = digits.parse().map_err(|_| invalid(format!("bad invoice amount '{}'", amount)))?;
+
+    // Millisatoshis per unit of each multiplier
+    let msat = match multiplier {
+        None => value.checked_mul(100_000_000_000),
+        Some('m') => value.checked_mul(100_000_000),
+        Some('u') => value.checked_mul(100_000),
+        Some('n') => value.checked_mul(100),
+        Some('p') if value % 10 == 0 => Some(value / 10),
+        _ => None,
+    }
+    .ok_or_else(|| invalid(format!("bad invoice amount '{}'", amount)))?;
+
+    Ok((network, Some(msat)))
+}
+
+/// Big-endian integer from 5-bit groups
+fn to_int(groups: &[u8]) -> u64 {
+    groups.iter().fold(0, |acc, g| (acc << 5) | *g as u64)
+}
+
+/// Bytes from 5-bit groups
+///
+/// With `pad`, leftover bits are zero-padded into a final byte (how the
+/// signed data is hashed); without, they are dropped (how fields decode).
+fn to_bytes(groups: &[u8], pad: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(groups.len() * 5 / 8 + 1);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for group in groups {
+        acc = (acc << 5) | *group as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    if pad && bits > 0 {
+        bytes.push((acc << (8 - bits)) as u8);
+    }
+    bytes
+}
+
+// =============================================================================
+// BIP21
+// =============================================================================
+
+/// Decode `bitcoin:<address>?amount=..&label=..&message=..&lightning=..`
+fn parse_bip21(uri: &str) -> nine_s::Result<Bip21Uri> {
+    let body = &uri["bitcoin:".len()..];
+    let (address, query) = body.split_once('?').unwrap_or((body, ""));
+
+    let mut parsed = Bip21Uri {
+        uri: uri.to_string(),
+        address: None,
+        network: None,
+        amount_sat: None,
+        label: None,
+        message: None,
+        lightning: None,
+    };
+
+    if !address.is_empty() {
+        let decoded = parse_bitcoin_address(address)
+            .ok_or_else(|| invalid(format!("bad address in URI: {}", address)))?;
+        parsed.address = Some(decoded.address);
+        parsed.network = Some(decoded.network);
+    }
+
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.to_lowercase().as_str() {
+            "amount" => {
+                let sats = btc_to_sat(&value).ok_or_else(|| invalid(format!("bad amount '{}'", value)))?;
+                parsed.amount_sat = Some(sats);
+            }
+            "label" => parsed.label = Some(value.into_owned()),
+            "message" => parsed.message = Some(value.into_owned()),
+            "lightning" => parsed.lightning = Some(parse_bolt11(&value)?),
+            other if other.starts_with("req-") => {
+                return Err(invalid(format!("unsupported required parameter '{}'", key)));
+            }
+            _ => {}
+        }
+    }
+
+    if parsed.address.is_none() && parsed.lightning.is_none() {
+        return Err(invalid("URI has neither an address nor an invoice"));
+    }
+
+    Ok(parsed)
+}
+
+/// Decimal BTC (`0.0005`) to sats, without going through floats
+fn btc_to_sat(amount: &str) -> Option<u64> {
+    let (whole, frac) = amount.split_once('.').unwrap_or((amount, ""));
+    if (whole.is_empty() && frac.is_empty()) || frac.len() > 8 {
+        return None;
+    }
+    if !whole.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+    let frac: u64 = format!("{:0<8}", frac).parse().ok()?;
+    whole.checked_mul(100_000_000)?.checked_add(frac)
+}
+
+// =============================================================================
+// Addresses
+// =============================================================================
+
+/// An on-chain address, with the network it belongs to
+fn parse_bitcoin_address(address: &str) -> Option<BitcoinAddress> {
+    use bitcoin::Network;
+
+    let unchecked = bitcoin::Address::from_str(address).ok()?;
+    let network = if unchecked.is_valid_for_network(Network::Bitcoin) {
+        "bitcoin"
+    } else if address.to_lowercase().starts_with("bcrt1") {
+        "regtest"
+    } else if unchecked.is_valid_for_network(Network::Testnet) {
+        // Base58 regtest addresses are indistinguishable from testnet ones
+        "testnet"
+    } else {
+        return None;
+    };
+
+    Some(BitcoinAddress {
+        address: address.to_string(),
+        network: network.to_string(),
+    })
+}
+
+/// A bech32m Spark address (`spark1...`, or the short `sp1...` form)
+fn parse_spark_address(address: &str) -> Option<SparkAddress> {
+    let (hrp, data, _) = bech32::decode(address).ok()?;
+    let network = match hrp.as_str() {
+        "spark" | "sp" => "bitcoin",
+        "sparkt" | "spt" => "testnet",
+        "sparks" | "sps" => "signet",
+        "sparkrt" | "sprt" => "regtest",
+        _ => return None,
+    };
+
+    // The payload is a protobuf whose field 1 is the 33-byte identity key
+    let payload = Vec::<u8>::from_base32(&data).ok()?;
+    let identity_pubkey = match payload.as_slice() {
+        [0x0a, 33, key @ ..] if key.len() >= 33 => Some(hex::encode(&key[..33])),
+        _ => None,
+    };
+
+    Some(SparkAddress {
+        address: address.to_string(),
+        network: network.to_string(),
+        identity_pubkey,
+    })
+}
+
+/// `user@domain`, per LUD-16
+fn parse_lightning_address(address: &str) -> nine_s::Result<LightningAddress> {
+    let lower = address.to_lowercase();
+    let (username, domain) = lower
+        .split_once('@')
+        .ok_or_else(|| invalid("lightning address needs '@'"))?;
+
+    let username_ok = !username.is_empty()
+        && username.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.+".contains(&b));
+    let domain_ok = domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && domain.bytes().all(|b| b.is_ascii_alphanumeric() || b"-.:".contains(&b));
+    if !username_ok || !domain_ok {
+        return Err(invalid(format!("bad lightning address '{}'", address)));
+    }
+
+    // Onion services are fetched over plain http (LUD-16)
+    let scheme = if domain.ends_with(".onion") { "http" } else { "https" };
+    Ok(LightningAddress {
+        address: lower.clone(),
+        username: username.to_string(),
+        domain: domain.to_string(),
+        lnurlp_url: format!("{}://{}/.well-known/lnurlp/{}", scheme, domain, username),
+    })
+}
+
+// =============================================================================
+// LNURL
+// =============================================================================
+
+/// An LNURL, bech32 (LUD-01) or scheme-prefixed (LUD-17)
+///
+/// `Ok(None)` means the input isn't an LNURL at all.
+fn parse_lnurl(input: &str) -> nine_s::Result<Option<Lnurl>> {
+    let lower = input.to_lowercase();
+
+    let schemes = [
+        ("lnurlp://", LnurlKind::Pay),
+        ("lnurlw://", LnurlKind::Withdraw),
+        ("lnurlc://", LnurlKind::Channel),
+        ("keyauth://", LnurlKind::Auth),
+    ];
+    let (url, scheme_kind) = if let Some((scheme, kind)) = schemes.iter().find(|(s, _)| lower.starts_with(s)) {
+        let rest = &input[scheme.len()..];
+        let http = if rest.split('/').next().is_some_and(|host| host.ends_with(".onion")) { "http" } else { "https" };
+        (format!("{}://{}", http, rest), Some(*kind))
+    } else if lower.starts_with("lnurl1") {
+        let (_, data, _) = bech32::decode(input).map_err(|e| invalid(format!("bad LNURL: {}", e)))?;
+        let bytes = Vec::<u8>::from_base32(&data).map_err(|e| invalid(format!("bad LNURL: {}", e)))?;
+        let url = String::from_utf8(bytes).map_err(|_| invalid("LNURL is not a UTF-8 URL"))?;
+        (url, None)
+    } else {
+        return Ok(None);
+    };
+
+    let parsed = url::Url::parse(&url).map_err(|e| invalid(format!("bad LNURL URL: {}", e)))?;
+    let domain = parsed.host_str().ok_or_else(|| invalid("LNURL URL has no host"))?.to_string();
+    let query = |name: &str| parsed.query_pairs().find(|(k, _)| k == name).map(|(_, v)| v.into_owned());
+
+    let kind = scheme_kind.unwrap_or_else(|| match query("tag").as_deref() {
+        Some("login") => LnurlKind::Auth,
+        Some("withdrawRequest") => LnurlKind::Withdraw,
+        Some("payRequest") => LnurlKind::Pay,
+        Some("channelRequest") => LnurlKind::Channel,
+        _ => LnurlKind::Unknown,
+    });
+
+    let (k1, action) = match kind {
+        LnurlKind::Auth => {
+            let k1 = query("k1").ok_or_else(|| invalid("LNURL-Auth without k1"))?;
+            if hex::decode(&k1).ok().map(|b| b.len()) != Some(32) {
+                return Err(invalid("LNURL-Auth k1 must be 32 bytes of hex"));
+            }
+            (Some(k1), query("action"))
+        }
+        _ => (None, None),
+    };
+
+    Ok(Some(Lnurl {
+        lnurl: input.to_string(),
+        url,
+        domain,
+        kind,
+        k1,
+        action,
+    }))
+}
+
+/// Encode a URL as a bech32 `lnurl1...` string
+pub fn encode_lnurl(url: &str) -> nine_s::Result<String> {
+    bech32::encode("lnurl", url.as_bytes().to_base32(), bech32::Variant::Bech32)
+        .map(|s| s.to_uppercase())
+        .map_err(|e| invalid(e.to_string()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bech32::u5;
+    use bitcoin::secp256k1::{PublicKey, SecretKey};
+    use serde_json::json;
+
+    pub(crate) fn groups(bytes: &[u8]) -> Vec<u8> {
+        bytes.to_base32().iter().map(|u| u.to_u8()).collect()
+    }
+
+    fn int_groups(mut value: u64, len: usize) -> Vec<u8> {
+        let mut out = vec![0; len];
+        for slot in out.iter_mut().rev() {
+            *slot = (value & 31) as u8;
+            value >>= 5;
+        }
+        out
+    }
+
+    /// Sign a BOLT11 invoice with `secret` (tests only need a few fields)
+    pub(crate) fn sign_invoice(hrp: &str, timestamp: u64, tags: &[(u8, Vec<u8>)], secret: [u8; 32]) -> String {
+        let mut data = int_groups(timestamp, 7);
+        for (tag, value) in tags {
+            data.push(*tag);
+            data.extend(int_groups(value.len() as u64, 2));
+            data.extend(value);
+        }
+
+        let mut preimage = hrp.as_bytes().to_vec();
+        preimage.extend(to_bytes(&data, true));
+        let digest: [u8; 32] = Sha256::digest(&preimage).into();
+        let secret = SecretKey::from_slice(&secret).unwrap();
+        let (id, compact) = Secp256k1::new()
+            .sign_ecdsa_recoverable(&Message::from_digest(digest), &secret)
+            .serialize_compact();
+        let mut signature = compact.to_vec();
+        signature.push(id.to_i32() as u8);
+        data.extend(groups(&signature));
+
+        let data: Vec<u5> = data.into_iter().map(|g| u5::try_from_u8(g).unwrap()).collect();
+        bech32::encode(hrp, data, bech32::Variant::Bech32).unwrap()
+    }
+
+    pub(crate) fn payee(secret: [u8; 32]) -> String {
+        let secret = SecretKey::from_slice(&secret).unwrap();
+        hex::encode(PublicKey::from_secret_key(&Secp256k1::new(), &secret).serialize())
+    }
+
+    fn coffee_invoice() -> String {
+        sign_invoice(
+            "lnbcrt2500u",
+            1_700_000_000,
+            &[
+                (1, groups(&[7; 32])),
+                (16, groups(&[9; 32])),
+                (13, groups(b"coffee")),
+                (6, int_groups(600, 2)),
+            ],
+            [1; 32],
+        )
+    }
+
+    fn bolt11(input: &str) -> Bolt11Invoice {
+        match ParsedInput::parse(input).unwrap() {
+            ParsedInput::Bolt11(invoice) => invoice,
+            other => panic!("expected bolt11, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bolt11_fields_and_recovered_payee() {
+        let invoice = bolt11(&coffee_invoice());
+        assert_eq!(invoice.network, "regtest");
+        assert_eq!(invoice.amount_msat, Some(250_000_000));
+        assert_eq!(invoice.amount_sat, Some(250_000));
+        assert_eq!(invoice.description.as_deref(), Some("coffee"));
+        assert_eq!(invoice.expiry_secs, 600);
+        assert_eq!(invoice.expires_at, 1_700_000_600);
+        assert_eq!(invoice.payment_hash, hex::encode([7; 32]));
+        assert_eq!(invoice.payment_secret, Some(hex::encode([9; 32])));
+        assert_eq!(invoice.payee, payee([1; 32]));
+        assert_eq!(invoice.min_final_cltv_expiry_delta, DEFAULT_MIN_FINAL_CLTV);
+
+        // Same invoice, uppercase and behind lightning:
+        let upper = format!("LIGHTNING:{}", coffee_invoice().to_uppercase());
+        assert_eq!(bolt11(&upper).payee, payee([1; 32]));
+
+        // The prefix is stripped once, not peeled off recursively
+        let nested = format!("{}{}", "lightning:".repeat(10_000), coffee_invoice());
+        assert!(matches!(ParsedInput::parse(&nested), Err(nine_s::Error::InvalidData(_))));
+    }
+
+    #[test]
+    fn bolt11_huge_expiry_does_not_overflow() {
+        let invoice = |expiry: Vec<u8>| {
+            sign_invoice("lnbcrt1m", 1_700_000_000, &[(1, groups(&[7; 32])), (6, expiry)], [1; 32])
+        };
+
+        let longest = bolt11(&invoice(int_groups(u64::MAX, 12)));
+        assert_eq!(longest.expiry_secs, (1 << 60) - 1);
+        assert_eq!(longest.expires_at, 1_700_000_000 + (1 << 60) - 1);
+
+        let too_long = ParsedInput::parse(&invoice(int_groups(u64::MAX, 13)));
+        assert!(matches!(too_long, Err(nine_s::Error::InvalidData(_))));
+    }
+
+    #[test]
+    fn bolt11_description_hash_amountless_and_payee_field() {
+        let metadata_hash = Sha256::digest(b"[[\"text/plain\",\"zap\"]]");
+        let mut key = vec![0; 33];
+        key.copy_from_slice(&hex::decode(payee([2; 32])).unwrap());
+        let invoice = sign_invoice(
+            "lnbc",
+            1_700_000_000,
+            &[(1, groups(&[7; 32])), (23, groups(&metadata_hash)), (19, groups(&key))],
+            [2; 32],
+        );
+
+        let decoded = bolt11(&invoice);
+        assert_eq!(decoded.network, "bitcoin");
+        assert_eq!(decoded.amount_msat, None);
+        assert_eq!(decoded.description_hash, Some(hex::encode(metadata_hash)));
+        assert_eq!(decoded.expiry_secs, DEFAULT_EXPIRY_SECS);
+
+        // An `n` field the signature doesn't match is refused
+        let forged = sign_invoice("lnbc", 1, &[(1, groups(&[7; 32])), (19, groups(&key))], [3; 32]);
+        assert!(matches!(ParsedInput::parse(&forged), Err(nine_s::Error::InvalidData(_))));
+    }
+
+    #[test]
+    fn bolt11_amount_multipliers() {
+        assert_eq!(parse_bolt11_hrp("lnbc1").unwrap(), ("bitcoin", Some(100_000_000_000)));
+        assert_eq!(parse_bolt11_hrp("lntb20m").unwrap(), ("testnet", Some(2_000_000_000)));
+        assert_eq!(parse_bolt11_hrp("lntbs5n").unwrap(), ("signet", Some(500)));
+        assert_eq!(parse_bolt11_hrp("lnbcrt10p").unwrap(), ("regtest", Some(1)));
+        assert!(parse_bolt11_hrp("lnbc15p").is_err());
+        assert!(parse_bolt11_hrp("lnxx1m").is_err());
+    }
+
+    #[test]
+    fn bip21_with_lightning_fallback() {
+        let uri = format!(
+            "bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?amount=0.0005&label=Coffee%20Shop&lightning={}",
+            coffee_invoice()
+        );
+        let parsed = ParsedInput::parse(&uri).unwrap();
+        let ParsedInput::Bip21(bip21) = &parsed else { panic!("expected bip21") };
+        assert_eq!(bip21.network.as_deref(), Some("bitcoin"));
+        assert_eq!(bip21.amount_sat, Some(50_000));
+        assert_eq!(bip21.label.as_deref(), Some("Coffee Shop"));
+        assert_eq!(bip21.lightning.as_ref().unwrap().amount_sat, Some(250_000));
+
+        let scroll = parsed.to_scroll().unwrap();
+        assert_eq!(scroll.type_, "wallet/parsed-input@v1");
+        assert_eq!(scroll.data["type"], "bip21");
+        assert_eq!(scroll.decode::<ParsedInput>().unwrap(), parsed);
+
+        let lightning_only = format!("bitcoin:?lightning={}", coffee_invoice());
+        assert!(matches!(ParsedInput::parse(&lightning_only).unwrap(), ParsedInput::Bip21(_)));
+        assert!(ParsedInput::parse("bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?req-pop=1").is_err());
+        assert!(ParsedInput::parse("bitcoin:bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4?amount=0.000000001").is_err());
+    }
+
+    #[test]
+    fn btc_amounts_convert_exactly() {
+        assert_eq!(btc_to_sat("1"), Some(100_000_000));
+        assert_eq!(btc_to_sat("0.00000001"), Some(1));
+        assert_eq!(btc_to_sat(".5"), Some(50_000_000));
+        assert_eq!(btc_to_sat("1e3"), None);
+        assert_eq!(btc_to_sat(""), None);
+    }
+
+    #[test]
+    fn addresses() {
+        let onchain = ParsedInput::parse("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").unwrap();
+        assert_eq!(onchain.kind(), "bitcoin-address");
+
+        let mut payload = vec![0x0a, 33];
+        payload.extend(hex::decode(payee([4; 32])).unwrap());
+        let spark = bech32::encode("sparkrt", payload.to_base32(), bech32::Variant::Bech32m).unwrap();
+        let ParsedInput::SparkAddress(decoded) = ParsedInput::parse(&spark).unwrap() else {
+            panic!("expected spark address")
+        };
+        assert_eq!(decoded.network, "regtest");
+        assert_eq!(decoded.identity_pubkey, Some(payee([4; 32])));
+
+        let ParsedInput::LightningAddress(ln) = ParsedInput::parse("Alice@Example.com").unwrap() else {
+            panic!("expected lightning address")
+        };
+        assert_eq!(ln.lnurlp_url, "https://example.com/.well-known/lnurlp/alice");
+        assert!(ParsedInput::parse("alice@localhost").is_err());
+        assert!(ParsedInput::parse("hello world").is_err());
+    }
+
+    #[test]
+    fn lnurl_variants() {
+        let lnurl = encode_lnurl("https://service.com/api?q=1").unwrap();
+        let ParsedInput::Lnurl(decoded) = ParsedInput::parse(&lnurl).unwrap() else { panic!("expected lnurl") };
+        assert_eq!(decoded.url, "https://service.com/api?q=1");
+        assert_eq!(decoded.kind, LnurlKind::Unknown);
+
+        let k1 = "e2af6254a8df433264fa23f67eb8188635d15ce883e8fc020989d5f82ae6f11e";
+        let auth = encode_lnurl(&format!("https://site.com/auth?tag=login&k1={}&action=login", k1)).unwrap();
+        let ParsedInput::Lnurl(decoded) = ParsedInput::parse(&format!("lightning:{}", auth)).unwrap() else {
+            panic!("expected lnurl")
+        };
+        assert_eq!(decoded.kind, LnurlKind::Auth);
+        assert_eq!(decoded.k1.as_deref(), Some(k1));
+        assert_eq!(decoded.action.as_deref(), Some("login"));
+
+        let ParsedInput::Lnurl(withdraw) = ParsedInput::parse("lnurlw://atm.example.com/w?id=1").unwrap() else {
+            panic!("expected lnurl")
+        };
+        assert_eq!(withdraw.kind, LnurlKind::Withdraw);
+        assert_eq!(withdraw.url, "https://atm.example.com/w?id=1");
+    }
+
+    #[test]
+    fn parse_request_reads_input_field() {
+        let scroll = parse_request(&json!({"input": "alice@example.com"})).unwrap();
+        assert_eq!(scroll.key, "/wallet/parse");
+        assert_eq!(scroll.data["type"], "lightning-address");
+        assert!(matches!(parse_request(&json!({})), Err(nine_s::Error::InvalidData(_))));
+    }
+}
@@ -33,6 +33,7 @@
 //! |------|-------------|-------------|
 //! | `/wallet/balance` | Write-through | Always persist |
 //! | `/wallet/tx/{id}` | Write-through | Transaction history |
+//! | `/wallet/intent/{id}` | Write-through | Send intents (idempotency keys) |
 //! | `/wallet/config` | Write-through | Wallet configuration |
 //! | `/wallet/synced` | Ephemeral | Not persisted |
 
@@ -44,6 +45,7 @@ use std::sync::Arc;
 const PERSISTENT_PATHS: &[&str] = &[
     "/wallet/balance",
     "/wallet/tx/",
+    "/wallet/intent/",
     "/wallet/config",
     "/wallet/address",
     "/wallet/pubkey",
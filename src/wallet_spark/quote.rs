@@ -0,0 +1,215 @@
+//! Send Quotes - Review the fee before paying
+//!
+//! `write("/send")` prepares and pays in one step, so the user never sees
+//! the fee. The two-phase flow splits it:
+//!
+//! ```text
+//! write("/send/prepare", {to, amount})   → wallet/send-quote@v1 (fee, expiry)
+//!            │                               held in the reactor cache
+//!            ▼  user reviews
+//! write("/send/confirm", {quote_id, to, amount}) → wallet/payment@v1
+//! ```
+//!
+//! A confirm pays exactly the quote it names, once. It is rejected with
+//! `Error::Conflict` if the quote has expired or if the destination or
+//! amount in the confirm differ from the quote; the quote is spent either
+//! way and the user prepares again. A confirm that fails in flight can be
+//! repeated until the quote expires: it pays the reviewed fee or is
+//! refused, never re-prepared behind the user's back.
+//!
+//! # Dialectics
+//!
+//! **Thesis**: Pay in one call (simple, but the fee is a surprise)
+//! **Antithesis**: Hand the SDK's prepare response to the UI (leaks SDK types)
+//! **Synthesis**: A quote scroll the UI shows and a quote id it sends back
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::nine_s::{self, Scroll, ScrollType};
+
+use super::parse_helpers::{detect_input_type, InputTypeHint};
+use super::reactor::{WalletReactor, WALLET_ROOT};
+
+/// How long a quote can be confirmed for
+pub const QUOTE_TTL_SECS: u64 = 120;
+
+/// A prepared send, as shown to the user for review
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ScrollType)]
+#[scroll(type = "wallet/send-quote@v1")]
+pub struct SendQuote {
+    /// Id the confirm must name
+    pub quote_id: String,
+    /// Destination exactly as given to `/send/prepare`
+    pub destination: String,
+    /// `bolt11`, `bitcoin-address`, `spark-address`, ...
+    pub destination_type: String,
+    /// Amount the recipient gets
+    pub amount_sat: u64,
+    /// Fee on top of the amount
+    pub fee_sat: u64,
+    /// `amount_sat + fee_sat`
+    pub total_sat: u64,
+    /// Unix seconds after which the quote is refused
+    pub expires_at: u64,
+}
+
+impl SendQuote {
+    /// Build a quote that expires `QUOTE_TTL_SECS` after `now`
+    ///
+    /// # Errors
+    /// `InvalidData` if the total or the expiry overflows.
+    pub fn new(
+        quote_id: impl Into<String>,
+        destination: impl Into<String>,
+        destination_type: impl Into<String>,
+        amount_sat: u64,
+        fee_sat: u64,
+        now: u64,
+    ) -> nine_s::Result<Self> {
+        let total_sat = amount_sat.checked_add(fee_sat).ok_or_else(|| {
+            nine_s::Error::InvalidData(format!("amount {} plus fee {} overflows", amount_sat, fee_sat))
+        })?;
+        let expires_at = now.checked_add(QUOTE_TTL_SECS)
+            .ok_or_else(|| nine_s::Error::InvalidData(format!("quote expiry after {} overflows", now)))?;
+
+        Ok(Self {
+            quote_id: quote_id.into(),
+            destination: destination.into(),
+            destination_type: destination_type.into(),
+            amount_sat,
+            fee_sat,
+            total_sat,
+            expires_at,
+        })
+    }
+
+    /// Reactor cache key of this quote
+    pub fn key(&self) -> String {
+        quote_key(&self.quote_id)
+    }
+
+    /// The quote as a `wallet/send-quote@v1` scroll
+    pub fn to_scroll(&self) -> nine_s::Result<Scroll> {
+        Scroll::encode(self.key(), self)
+    }
+
+    /// Whether the quote can no longer be confirmed at `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    /// Check a `/send/confirm` request against this quote
+    ///
+    /// The request must name a destination; an amount is checked when given.
+    ///
+    /// # Errors
+    /// `Conflict` if the quote expired or the request asks for something else.
+    pub fn check(&self, request: &Value, now: u64) -> nine_s::Result<()> {
+        if self.is_expired(now) {
+            return Err(nine_s::Error::Conflict(format!(
+                "quote {} expired at {}",
+                self.quote_id, self.expires_at
+            )));
+        }
+
+        let destination = request["to"].as_str()
+            .or_else(|| request["destination"].as_str())
+            .ok_or_else(|| nine_s::Error::InvalidData("Missing 'to' field".into()))?;
+        if destination != self.destination {
+            return Err(nine_s::Error::Conflict(format!(
+                "quote {} is for {}, not {}",
+                self.quote_id, self.destination, destination
+            )));
+        }
+
+        let amount = request["amount"].as_u64().or_else(|| request["amount_sat"].as_u64());
+        if let Some(amount) = amount.filter(|a| *a != self.amount_sat) {
+            return Err(nine_s::Error::Conflict(format!(
+                "quote {} is for {} sat, not {}",
+                self.quote_id, self.amount_sat, amount
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Reactor cache key of quote `quote_id`
+pub fn quote_key(quote_id: &str) -> String {
+    format!("{}/send/quote/{}", WALLET_ROOT, quote_id)
+}
+
+/// Remove the quote a `/send/confirm` request names from the reactor cache
+///
+/// Taking it is what makes a quote single use: a second confirm, or one
+/// racing the first, finds nothing.
+///
+/// # Errors
+/// `InvalidData` without a `quote_id`; `NotFound` if the quote was never
+/// issued or has already been used.
+pub fn take(reactor: &WalletReactor, request: &Value) -> nine_s::Result<SendQuote> {
+    let quote_id = request["quote_id"].as_str()
+        .ok_or_else(|| nine_s::Error::InvalidData("Missing 'quote_id' field".into()))?;
+    let scroll = reactor
+        .take_cached(&quote_key(quote_id))
+        .ok_or_else(|| nine_s::Error::NotFound(format!("quote {}", quote_id)))?;
+    scroll.decode()
+}
+
+/// Destination type from the shape of the input (no SDK needed)
+pub fn destination_type(destination: &str) -> &'static str {
+    match detect_input_type(destination) {
+        InputTypeHint::Bolt11 => "bolt11",
+        InputTypeHint::BitcoinAddress => "bitcoin-address",
+        InputTypeHint::LnUrl | InputTypeHint::LnUrlAuth => "lnurl",
+        InputTypeHint::Unknown => "spark-address",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn quote() -> SendQuote {
+        SendQuote::new("q1", "sp1qfriend", "spark-address", 1_000, 3, 100).unwrap()
+    }
+
+    #[test]
+    fn quote_round_trips_through_the_reactor_once() {
+        let reactor = WalletReactor::new();
+        reactor.emit(quote().to_scroll().unwrap());
+
+        let request = json!({"quote_id": "q1", "to": "sp1qfriend"});
+        let taken = take(&reactor, &request).unwrap();
+        assert_eq!(taken, quote());
+        assert_eq!(taken.total_sat, 1_003);
+        assert!(matches!(take(&reactor, &request), Err(nine_s::Error::NotFound(_))));
+    }
+
+    #[test]
+    fn check_rejects_expired_or_changed_requests() {
+        let quote = quote();
+        let now = 100;
+
+        quote.check(&json!({"to": "sp1qfriend", "amount": 1_000}), now).unwrap();
+        quote.check(&json!({"to": "sp1qfriend"}), now).unwrap();
+
+        let expired = quote.check(&json!({"to": "sp1qfriend"}), now + QUOTE_TTL_SECS);
+        assert!(matches!(expired, Err(nine_s::Error::Conflict(_))));
+        let elsewhere = quote.check(&json!({"to": "sp1qmallory"}), now);
+        assert!(matches!(elsewhere, Err(nine_s::Error::Conflict(_))));
+        let more = quote.check(&json!({"to": "sp1qfriend", "amount": 2_000}), now);
+        assert!(matches!(more, Err(nine_s::Error::Conflict(_))));
+        assert!(matches!(quote.check(&json!({}), now), Err(nine_s::Error::InvalidData(_))));
+    }
+
+    #[test]
+    fn overflowing_quotes_are_refused() {
+        let total = SendQuote::new("q1", "sp1qfriend", "spark-address", u64::MAX, 1, 100);
+        assert!(matches!(total, Err(nine_s::Error::InvalidData(_))));
+        let expiry = SendQuote::new("q1", "sp1qfriend", "spark-address", 1_000, 3, u64::MAX);
+        assert!(matches!(expiry, Err(nine_s::Error::InvalidData(_))));
+    }
+}
@@ -69,10 +69,15 @@ use std::sync::{Arc, Mutex, RwLock};
 /// Channel capacity for event bus
 const EVENT_BUS_CAPACITY: usize = 256;
 
+/// Prefix of every key the reactor emits
+pub const WALLET_ROOT: &str = "/wallet";
+
 /// Watcher entry with pattern and sender
 struct Watcher {
     pattern: String,
     sender: Sender<Scroll>,
+    /// Deliver keys with `WALLET_ROOT` stripped (mount-relative watch)
+    relative: bool,
 }
 
 /// Wallet Reactor - The reactive core
@@ -229,6 +234,26 @@ impl WalletReactor {
         self.state.read().ok()?.get(path).cloned()
     }
 
+    /// Remove and return cached state for a path
+    ///
+    /// Atomic, so of two callers racing for the same entry only one gets it.
+    pub fn take_cached(&self, path: &str) -> Option<Scroll> {
+        self.state.write().ok()?.remove(path)
+    }
+
+    /// Every cached scroll under a path prefix
+    pub fn cached_under(&self, prefix: &str) -> Vec<Scroll> {
+        let Ok(state) = self.state.read() else {
+            return Vec::new();
+        };
+        let prefix = format!("{}/", prefix.trim_end_matches('/'));
+        state
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(_, scroll)| scroll.clone())
+            .collect()
+    }
+
     /// Set state directly (for initial load)
     pub fn set_state(&self, scroll: Scroll) {
         self.update_state(&scroll);
@@ -258,6 +283,33 @@ impl WalletReactor {
         let watcher = Watcher {
             pattern: pattern.to_string(),
             sender: tx,
+            relative: false,
+        };
+
+        if let Ok(mut watchers) = self.watchers.lock() {
+            watchers.push(watcher);
+        }
+
+        rx
+    }
+
+    /// Subscribe with a mount-relative pattern (`/balance`, `/tx/**`)
+    ///
+    /// Scrolls arrive keyed the way `WalletManager::read` and `list` are
+    /// addressed (`/tx/abc` rather than `/wallet/tx/abc`), which is what a
+    /// Kernel expects from a namespace mounted at `/wallet`.
+    pub fn watch_relative(&self, pattern: &str) -> Receiver<Scroll> {
+        let (tx, rx) = channel(EVENT_BUS_CAPACITY);
+
+        let pattern = if pattern == "/" {
+            WALLET_ROOT.to_string()
+        } else {
+            format!("{}{}", WALLET_ROOT, pattern)
+        };
+        let watcher = Watcher {
+            pattern,
+            sender: tx,
+            relative: true,
         };
 
         if let Ok(mut watchers) = self.watchers.lock() {
@@ -277,8 +329,16 @@ impl WalletReactor {
         // Remove disconnected watchers and dispatch to matching ones
         watchers.retain(|watcher| {
             if path_matches(&scroll.key, &watcher.pattern) {
+                let mut scroll = scroll.clone();
+                if watcher.relative {
+                    scroll.key = match &scroll.key[WALLET_ROOT.len()..] {
+                        "" => "/".to_string(),
+                        rest => rest.to_string(),
+                    };
+                }
+
                 // Try to send, remove if channel is disconnected
-                watcher.sender.try_send(scroll.clone()).is_ok()
+                watcher.sender.try_send(scroll).is_ok()
             } else {
                 // Keep watcher even if this scroll doesn't match
                 true
@@ -331,7 +391,7 @@ impl Default for WalletReactor {
 }
 
 /// Get current Unix timestamp in seconds
-fn now_unix() -> u64 {
+pub(crate) fn now_unix() -> u64 {
     use std::time::{SystemTime, UNIX_EPOCH};
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -428,6 +488,18 @@ mod tests {
         assert_eq!(scroll.data["amount_sat"], 5000);
     }
 
+    #[test]
+    fn test_watch_relative_strips_wallet_root() {
+        let reactor = WalletReactor::new();
+        let mut rx = reactor.watch_relative("/**");
+        let mut balance_rx = reactor.watch_relative("/balance");
+
+        reactor.emit_balance(7000, 0);
+
+        assert_eq!(rx.try_recv().expect("Should receive scroll").key, "/balance");
+        assert_eq!(balance_rx.try_recv().expect("Should receive scroll").key, "/balance");
+    }
+
     #[test]
     fn test_state_caching() {
         let reactor = WalletReactor::new();
@@ -441,6 +513,16 @@ mod tests {
         assert_eq!(cached.unwrap().data["confirmed"], 20000);
     }
 
+    #[test]
+    fn test_take_cached_removes_entry() {
+        let reactor = WalletReactor::new();
+        reactor.emit_balance(1000, 0);
+
+        assert!(reactor.take_cached("/wallet/balance").is_some());
+        assert!(reactor.take_cached("/wallet/balance").is_none());
+        assert!(reactor.get_cached("/wallet/balance").is_none());
+    }
+
     #[test]
     fn test_multiple_watchers() {
         let reactor = WalletReactor::new();
@@ -0,0 +1,120 @@
+//! Schemas for the scrolls the wallet emits
+//!
+//! Amounts are always `amount_sat` / `fee_sat` (integers, in sats). Inputs
+//! to `write` still accept `amount` and `amountSat`, but nothing the wallet
+//! emits uses them.
+//!
+//! ```rust,ignore
+//! let registry = Arc::new(wallet_spark::schemas::registry());
+//! let checked = InterceptedNamespace::new(kernel)
+//!     .with(Arc::new(Validate::schemas("/wallet/**", registry)));
+//! ```
+
+use crate::nine_s::schema::{Kind, Schema, SchemaRegistry};
+
+use super::intent::SendIntent;
+use super::lnurl::LnurlParams;
+use super::parse::ParsedInput;
+use super::quote::SendQuote;
+
+/// Register every wallet type
+pub fn register(registry: &mut SchemaRegistry) -> crate::nine_s::Result<()> {
+    for schema in schemas() {
+        registry.register(schema)?;
+    }
+    Ok(())
+}
+
+/// A registry holding only the wallet types
+pub fn registry() -> SchemaRegistry {
+    let mut registry = SchemaRegistry::new();
+    register(&mut registry).expect("wallet schema types are well formed");
+    registry
+}
+
+fn schemas() -> Vec<Schema> {
+    vec![
+        Schema::new("wallet/status@v1").required("connected", Kind::Bool),
+        Schema::new("wallet/balance@v1")
+            .required("confirmed", Kind::U64)
+            .optional("trusted_pending", Kind::U64)
+            .optional("untrusted_pending", Kind::U64)
+            .optional("immature", Kind::U64)
+            .optional("pending", Kind::U64)
+            .optional("spendable", Kind::U64)
+            .optional("total", Kind::U64),
+        Schema::new("wallet/balance-hint@v1")
+            .required("hint", Kind::String)
+            .optional("last_payment_id", Kind::String),
+        Schema::new("wallet/address@v1").required("address", Kind::String),
+        Schema::new("wallet/payment@v1")
+            .required("id", Kind::String)
+            .required("type", Kind::String)
+            .required("amount_sat", Kind::U64)
+            .optional("fee_sat", Kind::U64)
+            .optional("timestamp", Kind::U64)
+            .optional("txid", Kind::String)
+            .optional("state", Kind::String)
+            .optional("status", Kind::String)
+            .optional("description", Kind::String)
+            .optional("details", Kind::Any)
+            .optional("quote_id", Kind::String)
+            .optional("intent_id", Kind::String)
+            .optional("idempotency_key", Kind::String)
+            .optional("lnurl_domain", Kind::String)
+            .optional("lightning_address", Kind::String)
+            .optional("success_action", Kind::Object),
+        Schema::new("wallet/transactions@v1").required("transactions", Kind::Array),
+        Schema::new("wallet/invoice@v1")
+            .required("invoice", Kind::String)
+            .optional("destination", Kind::String)
+            .optional("fee_sat", Kind::U64),
+        Schema::of::<SendQuote>(),
+        Schema::of::<SendIntent>(),
+        Schema::of::<ParsedInput>(),
+        Schema::of::<LnurlParams>(),
+        Schema::new("wallet/lnurl-withdraw@v1")
+            .required("domain", Kind::String)
+            .required("invoice", Kind::String)
+            .required("amount_sat", Kind::U64)
+            .required("status", Kind::String),
+        Schema::new("wallet/deposit@v1")
+            .required("amount_sat", Kind::U64)
+            .required("status", Kind::String)
+            .optional("txid", Kind::String)
+            .optional("address", Kind::String)
+            .optional("timestamp", Kind::U64),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nine_s::Scroll;
+    use crate::wallet_spark::reactor::WalletReactor;
+    use serde_json::json;
+
+    #[test]
+    fn emitted_balance_matches_schema() {
+        let registry = registry();
+        let reactor = WalletReactor::new();
+        reactor.emit_balance(1000, 50);
+
+        let balance = reactor.get_cached("/wallet/balance").unwrap();
+        registry.validate(&balance).unwrap();
+    }
+
+    #[test]
+    fn payment_amount_must_be_amount_sat() {
+        let registry = registry();
+        let canonical = json!({"id": "p1", "type": "send", "amount_sat": 10, "fee_sat": null});
+        let camel = json!({"id": "p1", "type": "send", "amountSat": 10});
+
+        registry
+            .validate(&Scroll::typed("/wallet/tx/p1", canonical, "wallet/payment@v1"))
+            .unwrap();
+        assert!(registry
+            .validate(&Scroll::typed("/wallet/tx/p1", camel, "wallet/payment@v1"))
+            .is_err());
+    }
+}
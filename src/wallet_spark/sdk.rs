@@ -26,7 +26,8 @@
 //! └─────────────────────────────────────────────────────────────┘
 //! ```
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Handle;
 use tokio::sync::RwLock;
 
@@ -44,6 +45,8 @@ use breez_sdk_spark::{
     ReceivePaymentMethod,
     ReceivePaymentRequest,
     PrepareSendPaymentRequest,
+    PrepareSendPaymentResponse,
+    SendPaymentMethod,
     SendPaymentRequest,
     SdkError,
     SdkEvent as SdkSdkEvent,
@@ -55,16 +58,26 @@ use breez_sdk_spark::{
 
 use super::{
     SparkNetwork,
+    WalletError,
     events::{SdkEvent, Payment, PaymentType, PaymentState, PaymentDetails},
-    reactor::WalletReactor,
+    quote::SendQuote,
+    reactor::{now_unix, WalletReactor},
 };
 
+/// A prepare response waiting for `/send/confirm`
+struct HeldQuote {
+    response: PrepareSendPaymentResponse,
+    expires_at: u64,
+}
+
 /// Spark SDK wrapper that bridges to our reactive system
 pub struct SparkSdkWrapper {
     /// The underlying Breez SDK
     sdk: Arc<RwLock<Option<Arc<BreezSdk>>>>,
     /// Reactor to push events to
     reactor: Arc<WalletReactor>,
+    /// Prepared sends by quote id (the SDK half of a `SendQuote`)
+    quotes: Mutex<HashMap<String, HeldQuote>>,
     /// Tokio runtime handle
     #[allow(dead_code)]
     runtime: Handle,
@@ -76,6 +89,7 @@ impl SparkSdkWrapper {
         Self {
             sdk: Arc::new(RwLock::new(None)),
             reactor,
+            quotes: Mutex::new(HashMap::new()),
             runtime,
         }
     }
@@ -177,7 +191,7 @@ impl SparkSdkWrapper {
 
         Ok(ReceiveInfo {
             destination: response.payment_request,
-            fee_sat: response.fee as u64,
+            fee_sat: to_sat(response.fee).map_err(|e| SdkError::Generic(e.to_string()))?,
         })
     }
 
@@ -191,7 +205,10 @@ impl SparkSdkWrapper {
             ..Default::default()
         }).await?;
 
-        Ok(response.payments.into_iter().map(PaymentInfo::from).collect())
+        response.payments.into_iter()
+            .map(PaymentInfo::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(|e| SdkError::Generic(e.to_string()))
     }
 
     // =========================================================================
@@ -219,15 +236,41 @@ impl SparkSdkWrapper {
 
         Ok(ReceiveInfo {
             destination: response.payment_request,
-            fee_sat: response.fee as u64,
+            fee_sat: to_sat(response.fee).map_err(|e| SdkError::Generic(e.to_string()))?,
         })
     }
 
-    /// Send payment (two-step: prepare then send)
+    /// Create a BOLT11 invoice (LNURL-Withdraw services only pay Lightning)
+    pub async fn create_lightning_invoice(
+        &self,
+        amount_sat: u64,
+        description: String,
+    ) -> Result<ReceiveInfo, SdkError> {
+        let guard = self.sdk.read().await;
+        let sdk = guard.as_ref().ok_or_else(|| SdkError::Generic("Not connected".into()))?;
+
+        let response = sdk.receive_payment(ReceivePaymentRequest {
+            payment_method: ReceivePaymentMethod::Bolt11Invoice {
+                description,
+                amount_sats: Some(amount_sat),
+                expiry_secs: None,
+            },
+        }).await?;
+
+        Ok(ReceiveInfo {
+            destination: response.payment_request,
+            fee_sat: to_sat(response.fee).map_err(|e| SdkError::Generic(e.to_string()))?,
+        })
+    }
+
+    /// Send payment (two-step: prepare then send, no review)
+    ///
+    /// Repeating a call with the same `idempotency_key` pays at most once.
     pub async fn send(
         &self,
         destination: &str,
         amount_sat: Option<u64>,
+        idempotency_key: Option<String>,
     ) -> Result<PaymentInfo, SdkError> {
         let guard = self.sdk.read().await;
         let sdk = guard.as_ref().ok_or_else(|| SdkError::Generic("Not connected".into()))?;
@@ -243,10 +286,114 @@ impl SparkSdkWrapper {
         let response = sdk.send_payment(SendPaymentRequest {
             prepare_response,
             options: None,
-            idempotency_key: None,
+            idempotency_key,
         }).await?;
 
-        Ok(PaymentInfo::from(response.payment))
+        PaymentInfo::try_from(response.payment).map_err(|e| SdkError::Generic(e.to_string()))
+    }
+
+    /// Prepare a send and hold it for review
+    ///
+    /// The SDK's prepare response stays here under the quote id; only
+    /// `send_quote` with that id can pay it.
+    pub async fn prepare_send(
+        &self,
+        destination: &str,
+        amount_sat: Option<u64>,
+    ) -> Result<SendQuote, SdkError> {
+        let guard = self.sdk.read().await;
+        let sdk = guard.as_ref().ok_or_else(|| SdkError::Generic("Not connected".into()))?;
+
+        let response = sdk.prepare_send_payment(PrepareSendPaymentRequest {
+            payment_request: destination.to_string(),
+            amount: amount_sat.map(|a| a as u128),
+            token_identifier: None,
+        }).await?;
+
+        let (destination_type, fee_sat) = quote_terms(&response.payment_method)?;
+        let quote = SendQuote::new(
+            hex::encode(rand::random::<[u8; 16]>()),
+            destination,
+            destination_type,
+            to_sat(response.amount).map_err(|e| SdkError::Generic(e.to_string()))?,
+            fee_sat,
+            now_unix(),
+        ).map_err(|e| SdkError::Generic(e.to_string()))?;
+
+        let mut quotes = self.quotes.lock().map_err(|e| SdkError::Generic(e.to_string()))?;
+        quotes.insert(quote.quote_id.clone(), HeldQuote {
+            response,
+            expires_at: quote.expires_at,
+        });
+
+        Ok(quote)
+    }
+
+    /// Pay a prepared send exactly as quoted
+    ///
+    /// The quote is held until the payment succeeds, so a retry under the
+    /// same idempotency key pays the reviewed fee; an expired quote is
+    /// dropped and refused.
+    pub async fn send_quote(
+        &self,
+        quote_id: &str,
+        idempotency_key: Option<String>,
+    ) -> Result<PaymentInfo, SdkError> {
+        let response = {
+            let mut quotes = self.quotes.lock().map_err(|e| SdkError::Generic(e.to_string()))?;
+            let held = quotes.get(quote_id)
+                .ok_or_else(|| SdkError::Generic(format!("Quote {} consumed, prepare again", quote_id)))?;
+            if now_unix() >= held.expires_at {
+                quotes.remove(quote_id);
+                return Err(SdkError::Generic(format!("Quote {} expired, prepare again", quote_id)));
+            }
+            held.response.clone()
+        };
+
+        let guard = self.sdk.read().await;
+        let sdk = guard.as_ref().ok_or_else(|| SdkError::Generic("Not connected".into()))?;
+
+        let response = sdk.send_payment(SendPaymentRequest {
+            prepare_response: response,
+            options: None,
+            idempotency_key,
+        }).await?;
+
+        self.discard_quote(quote_id);
+        PaymentInfo::try_from(response.payment).map_err(|e| SdkError::Generic(e.to_string()))
+    }
+
+    /// Whether a prepared send is still held and payable at `now`
+    pub fn holds_quote(&self, quote_id: &str, now: u64) -> bool {
+        self.quotes.lock()
+            .map(|quotes| quotes.get(quote_id).is_some_and(|held| now < held.expires_at))
+            .unwrap_or(false)
+    }
+
+    /// Drop a prepared send without paying it
+    pub fn discard_quote(&self, quote_id: &str) {
+        if let Ok(mut quotes) = self.quotes.lock() {
+            quotes.remove(quote_id);
+        }
+    }
+
+    /// Drop every prepared send that expired before `now`
+    ///
+    /// # Returns
+    /// The ids dropped, so their quote scrolls can be evicted too.
+    pub fn prune_quotes(&self, now: u64) -> Vec<String> {
+        let Ok(mut quotes) = self.quotes.lock() else {
+            return Vec::new();
+        };
+        let expired: Vec<String> = quotes
+            .iter()
+            .filter(|(_, held)| now >= held.expires_at)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &expired {
+            quotes.remove(id);
+        }
+        expired
     }
 
     /// Sync wallet state with the network
@@ -326,19 +473,19 @@ fn convert_sdk_event(event: SdkSdkEvent) -> Option<SdkEvent> {
 
         SdkSdkEvent::PaymentSucceeded { payment } => {
             Some(SdkEvent::PaymentSucceeded {
-                payment: convert_payment(payment),
+                payment: convert_payment(payment).ok()?,
             })
         }
 
         SdkSdkEvent::PaymentPending { payment } => {
             Some(SdkEvent::PaymentPending {
-                payment: convert_payment(payment),
+                payment: convert_payment(payment).ok()?,
             })
         }
 
         SdkSdkEvent::PaymentFailed { payment } => {
             Some(SdkEvent::PaymentFailed {
-                payment: convert_payment(payment),
+                payment: convert_payment(payment).ok()?,
             })
         }
 
@@ -348,8 +495,11 @@ fn convert_sdk_event(event: SdkSdkEvent) -> Option<SdkEvent> {
 }
 
 /// Convert SDK Payment to our Payment
-fn convert_payment(p: SdkPayment) -> Payment {
-    Payment {
+///
+/// A payment whose amount doesn't fit in `u64` isn't forwarded as an
+/// event; `list_payments` reports it as invalid data.
+fn convert_payment(p: SdkPayment) -> Result<Payment, WalletError> {
+    Ok(Payment {
         id: p.id,
         payment_type: match p.payment_type {
             SdkPaymentType::Receive => PaymentType::Receive,
@@ -360,12 +510,18 @@ fn convert_payment(p: SdkPayment) -> Payment {
             SdkPaymentStatus::Completed => PaymentState::Complete,
             SdkPaymentStatus::Failed => PaymentState::Failed,
         },
-        amount_sat: p.amount as u64,
-        fee_sat: Some(p.fees as u64),
+        amount_sat: to_sat(p.amount)?,
+        fee_sat: Some(to_sat(p.fees)?),
         timestamp: Some(p.timestamp),
         description: None, // SDK Payment doesn't have description at top level
         details: convert_payment_details(p.method, p.details),
-    }
+    })
+}
+
+/// Narrow an SDK amount to sats, refusing one that doesn't fit
+fn to_sat(amount: u128) -> Result<u64, WalletError> {
+    u64::try_from(amount)
+        .map_err(|_| WalletError::InvalidData(format!("Amount {} sat out of range", amount)))
 }
 
 /// Convert SDK payment method/details to our PaymentDetails
@@ -425,6 +581,29 @@ fn convert_payment_details(
     }
 }
 
+/// Destination type and total fee of a prepared send
+///
+/// Quoted for `send_payment` without options: on-chain at medium speed,
+/// invoices over Lightning. A method we can't price is refused rather
+/// than quoted at zero.
+fn quote_terms(method: &SendPaymentMethod) -> Result<(&'static str, u64), SdkError> {
+    match method {
+        SendPaymentMethod::BitcoinAddress { fee_quote, .. } => {
+            let fee = fee_quote.speed_medium.user_fee_sat
+                .checked_add(fee_quote.speed_medium.l1_broadcast_fee_sat)
+                .ok_or_else(|| SdkError::Generic("On-chain fee overflows".into()))?;
+            Ok(("bitcoin-address", fee))
+        }
+        SendPaymentMethod::Bolt11Invoice { lightning_fee_sats, .. } => Ok(("bolt11", *lightning_fee_sats)),
+        SendPaymentMethod::SparkAddress { fee, .. } => {
+            let fee = to_sat(*fee).map_err(|e| SdkError::Generic(e.to_string()))?;
+            Ok(("spark-address", fee))
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(SdkError::Generic("No fee quote for this payment method".into())),
+    }
+}
+
 // ============================================================================
 // Response Types
 // ============================================================================
@@ -439,11 +618,15 @@ pub struct PaymentInfo {
     pub fee_sat: Option<u64>,
     pub timestamp: Option<u64>,
     pub description: Option<String>,
+    /// BOLT11 the payment settled, when it went over Lightning
+    pub invoice: Option<String>,
 }
 
-impl From<SdkPayment> for PaymentInfo {
-    fn from(p: SdkPayment) -> Self {
-        PaymentInfo {
+impl TryFrom<SdkPayment> for PaymentInfo {
+    type Error = WalletError;
+
+    fn try_from(p: SdkPayment) -> Result<Self, WalletError> {
+        Ok(PaymentInfo {
             id: p.id,
             payment_type: match p.payment_type {
                 SdkPaymentType::Receive => PaymentType::Receive,
@@ -454,11 +637,15 @@ impl From<SdkPayment> for PaymentInfo {
                 SdkPaymentStatus::Completed => PaymentState::Complete,
                 SdkPaymentStatus::Failed => PaymentState::Failed,
             },
-            amount_sat: p.amount as u64,
-            fee_sat: Some(p.fees as u64),
+            amount_sat: to_sat(p.amount)?,
+            fee_sat: Some(to_sat(p.fees)?),
             timestamp: Some(p.timestamp),
             description: None,
-        }
+            invoice: match p.details {
+                Some(breez_sdk_spark::PaymentDetails::Lightning { invoice, .. }) => Some(invoice),
+                _ => None,
+            },
+        })
     }
 }
 
@@ -518,4 +705,11 @@ mod tests {
             _ => panic!("Expected Testnet to map to Regtest"),
         }
     }
+
+    #[test]
+    fn test_to_sat_refuses_truncation() {
+        assert_eq!(to_sat(21_000_000).unwrap(), 21_000_000);
+        assert_eq!(to_sat(u64::MAX as u128).unwrap(), u64::MAX);
+        assert!(matches!(to_sat(u64::MAX as u128 + 1), Err(WalletError::InvalidData(_))));
+    }
 }